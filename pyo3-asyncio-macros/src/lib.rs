@@ -1,6 +1,8 @@
 #![forbid(unsafe_code, future_incompatible, rust_2018_idioms)]
 #![deny(missing_debug_implementations, nonstandard_style)]
 #![recursion_limit = "512"]
+// the macros are compiled out of the unit tests (see the NOTEs below)
+#![cfg_attr(test, allow(dead_code, unused_imports))]
 
 mod tokio;
 
@@ -107,7 +109,9 @@ pub fn tokio_main(args: TokenStream, item: TokenStream) -> TokenStream {
 ///
 /// This attribute is meant to mirror the `#[test]` attribute and allow you to mark a function for
 /// testing within an integration test. Like the `#[async_std::test]` attribute, it will accept
-/// `async` test functions, but it will also accept blocking functions as well.
+/// `async` test functions, but it will also accept blocking functions as well. Test functions may
+/// return `()` or any `Result<(), E>` whose error implements `Debug` (see
+/// `pyo3_asyncio::testing::TestResult`).
 ///
 /// # Examples
 /// ```ignore
@@ -128,6 +132,12 @@ pub fn tokio_main(args: TokenStream, item: TokenStream) -> TokenStream {
 ///     thread::sleep(Duration::from_secs(1));
 ///     Ok(())
 /// }
+///
+/// // test functions can return anything a `#[test]` function can
+/// #[pyo3_asyncio::async_std::test]
+/// fn test_plain() {
+///     assert_eq!(2 + 2, 4);
+/// }
 /// ```
 #[cfg(not(test))] // NOTE: exporting main breaks tests, we should file an issue.
 #[proc_macro_attribute]
@@ -147,7 +157,7 @@ pub fn async_std_test(_attr: TokenStream, item: TokenStream) -> TokenStream {
                 }
 
                 Box::pin(pyo3_asyncio::async_std::re_exports::spawn_blocking(move || {
-                    #name()
+                    pyo3_asyncio::testing::TestResult::into_test_result(#name())
                 }))
             }
         }
@@ -158,7 +168,9 @@ pub fn async_std_test(_attr: TokenStream, item: TokenStream) -> TokenStream {
                     #body
                 }
 
-                Box::pin(#name())
+                Box::pin(async {
+                    pyo3_asyncio::testing::TestResult::into_test_result(#name().await)
+                })
             }
         }
     };
@@ -183,7 +195,9 @@ pub fn async_std_test(_attr: TokenStream, item: TokenStream) -> TokenStream {
 ///
 /// This attribute is meant to mirror the `#[test]` attribute and allow you to mark a function for
 /// testing within an integration test. Like the `#[tokio::test]` attribute, it will accept `async`
-/// test functions, but it will also accept blocking functions as well.
+/// test functions, but it will also accept blocking functions as well. Test functions may return
+/// `()` or any `Result<(), E>` whose error implements `Debug` (see
+/// `pyo3_asyncio::testing::TestResult`).
 ///
 /// # Examples
 /// ```ignore
@@ -204,17 +218,58 @@ pub fn async_std_test(_attr: TokenStream, item: TokenStream) -> TokenStream {
 ///     thread::sleep(Duration::from_secs(1));
 ///     Ok(())
 /// }
+///
+/// // test functions can return anything a `#[test]` function can
+/// #[pyo3_asyncio::tokio::test]
+/// fn test_plain() {
+///     assert_eq!(2 + 2, 4);
+/// }
+/// ```
+///
+/// ## Options
+///
+/// By default, tests share the runtime that `pyo3_asyncio::tokio` was initialized with. An `async`
+/// test that sets any of these options runs on a runtime of its own instead. Conversions started
+/// with the module-level functions still spawn onto the shared runtime, so use
+/// `pyo3_asyncio::tokio::Bridge::new(tokio::runtime::Handle::current())` to convert futures onto
+/// the test's runtime:
+///
+/// * `flavor` - selects the type of tokio runtime ["current_thread", "multi_thread"], defaults to
+///   "current_thread"
+/// * `worker_threads` - number of worker threads, only for the "multi_thread" flavor
+/// * `start_paused` - starts the tokio clock paused, so timers advance as soon as the runtime has
+///   nothing else to do. Requires the "current_thread" flavor and tokio's `test-util` feature.
+///
+/// ```ignore
+/// use std::time::Duration;
+///
+/// use pyo3::prelude::*;
+///
+/// // finishes right away, tokio's clock skips ahead to the end of the sleep
+/// #[pyo3_asyncio::tokio::test(start_paused = true)]
+/// async fn test_paused_sleep() -> PyResult<()> {
+///     tokio::time::sleep(Duration::from_secs(3600)).await;
+///     Ok(())
+/// }
 /// ```
 #[cfg(not(test))] // NOTE: exporting main breaks tests, we should file an issue.
 #[proc_macro_attribute]
-pub fn tokio_test(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn tokio_test(args: TokenStream, item: TokenStream) -> TokenStream {
     let input = syn::parse_macro_input!(item as syn::ItemFn);
+    let args = syn::parse_macro_input!(args as syn::AttributeArgs);
 
     let sig = &input.sig;
     let name = &input.sig.ident;
     let body = &input.block;
     let vis = &input.vis;
 
+    if input.sig.asyncness.is_none() && !args.is_empty() {
+        let msg = "runtime options are only supported for async tests";
+        return syn::Error::new_spanned(sig.fn_token, msg)
+            .to_compile_error()
+            .into();
+    }
+
     let fn_impl = if input.sig.asyncness.is_none() {
         quote! {
             #vis fn #name() -> std::pin::Pin<Box<dyn std::future::Future<Output = pyo3::PyResult<()>> + Send>> {
@@ -223,7 +278,11 @@ pub fn tokio_test(_attr: TokenStream, item: TokenStream) -> TokenStream {
                 }
 
                 Box::pin(async {
-                    match pyo3_asyncio::tokio::get_runtime().spawn_blocking(&#name).await {
+                    match pyo3_asyncio::tokio::spawn_blocking(|| {
+                        pyo3_asyncio::testing::TestResult::into_test_result(#name())
+                    })
+                    .await
+                    {
                         Ok(result) => result,
                         Err(e) => {
                             assert!(e.is_panic());
@@ -233,14 +292,46 @@ pub fn tokio_test(_attr: TokenStream, item: TokenStream) -> TokenStream {
                 })
             }
         }
+    } else if args.is_empty() {
+        quote! {
+            #vis fn #name() -> std::pin::Pin<Box<dyn std::future::Future<Output = pyo3::PyResult<()>> + Send>> {
+                #sig {
+                    #body
+                }
+
+                Box::pin(async {
+                    pyo3_asyncio::testing::TestResult::into_test_result(#name().await)
+                })
+            }
+        }
     } else {
+        let rt = match tokio::test_runtime(args) {
+            Ok(rt) => rt,
+            Err(e) => return e.to_compile_error().into(),
+        };
+
         quote! {
             #vis fn #name() -> std::pin::Pin<Box<dyn std::future::Future<Output = pyo3::PyResult<()>> + Send>> {
                 #sig {
                     #body
                 }
 
-                Box::pin(#name())
+                Box::pin(async {
+                    let rt = #rt.build().unwrap();
+
+                    // the test's runtime is driven by a thread of the shared runtime's blocking pool
+                    match pyo3_asyncio::tokio::spawn_blocking(move || rt.block_on(async {
+                        pyo3_asyncio::testing::TestResult::into_test_result(#name().await)
+                    }))
+                    .await
+                    {
+                        Ok(result) => result,
+                        Err(e) => {
+                            assert!(e.is_panic());
+                            Err(pyo3::exceptions::PyException::new_err("rust future panicked"))
+                        }
+                    }
+                })
             }
         }
     };
@@ -25,6 +25,7 @@ impl RuntimeFlavor {
 struct FinalConfig {
     flavor: RuntimeFlavor,
     worker_threads: Option<usize>,
+    start_paused: Option<bool>,
 }
 
 struct Configuration {
@@ -32,6 +33,8 @@ struct Configuration {
     default_flavor: RuntimeFlavor,
     flavor: Option<RuntimeFlavor>,
     worker_threads: Option<(usize, Span)>,
+    start_paused: Option<(bool, Span)>,
+    is_test: bool,
 }
 
 impl Configuration {
@@ -44,6 +47,24 @@ impl Configuration {
             },
             flavor: None,
             worker_threads: None,
+            start_paused: None,
+            is_test,
+        }
+    }
+
+    fn macro_name(&self) -> &'static str {
+        if self.is_test {
+            "pyo3_asyncio::tokio::test"
+        } else {
+            "pyo3_asyncio::tokio::main"
+        }
+    }
+
+    fn attributes(&self) -> &'static str {
+        if self.is_test {
+            "`flavor`, `worker_threads`, `start_paused`"
+        } else {
+            "`flavor`, `worker_threads`"
         }
     }
 
@@ -79,9 +100,32 @@ impl Configuration {
         Ok(())
     }
 
+    fn set_start_paused(&mut self, start_paused: syn::Lit, span: Span) -> Result<(), syn::Error> {
+        if self.start_paused.is_some() {
+            return Err(syn::Error::new(span, "`start_paused` set multiple times."));
+        }
+
+        let start_paused = parse_bool(start_paused, span, "start_paused")?;
+        self.start_paused = Some((start_paused, span));
+        Ok(())
+    }
+
     fn build(&self) -> Result<FinalConfig, syn::Error> {
         let flavor = self.flavor.unwrap_or(self.default_flavor);
         use RuntimeFlavor::*;
+
+        let start_paused = match (flavor, self.start_paused) {
+            (Threaded, Some((_, start_paused_span))) => {
+                let msg = format!(
+                    "The `start_paused` option requires the `current_thread` runtime flavor. Use `#[{}(flavor = \"current_thread\")]`",
+                    self.macro_name(),
+                );
+                return Err(syn::Error::new(start_paused_span, msg));
+            }
+            (CurrentThread, Some((start_paused, _))) => Some(start_paused),
+            (_, None) => None,
+        };
+
         match (flavor, self.worker_threads) {
             (CurrentThread, Some((_, worker_threads_span))) => Err(syn::Error::new(
                 worker_threads_span,
@@ -90,10 +134,12 @@ impl Configuration {
             (CurrentThread, None) => Ok(FinalConfig {
                 flavor,
                 worker_threads: None,
+                start_paused,
             }),
             (Threaded, worker_threads) if self.rt_multi_thread_available => Ok(FinalConfig {
                 flavor,
                 worker_threads: worker_threads.map(|(val, _span)| val),
+                start_paused,
             }),
             (Threaded, _) => {
                 let msg = if self.flavor.is_none() {
@@ -123,6 +169,16 @@ fn parse_int(int: syn::Lit, span: Span, field: &str) -> Result<usize, syn::Error
     }
 }
 
+fn parse_bool(bool: syn::Lit, span: Span, field: &str) -> Result<bool, syn::Error> {
+    match bool {
+        syn::Lit::Bool(b) => Ok(b.value),
+        _ => Err(syn::Error::new(
+            span,
+            format!("Failed to parse {} as bool.", field),
+        )),
+    }
+}
+
 fn parse_string(int: syn::Lit, span: Span, field: &str) -> Result<String, syn::Error> {
     match int {
         syn::Lit::Str(s) => Ok(s.value()),
@@ -134,25 +190,13 @@ fn parse_string(int: syn::Lit, span: Span, field: &str) -> Result<String, syn::E
     }
 }
 
-fn parse_knobs(
-    input: syn::ItemFn,
+fn parse_config(
     args: syn::AttributeArgs,
     is_test: bool,
     rt_multi_thread: bool,
-) -> Result<TokenStream, syn::Error> {
-    let sig = &input.sig;
-    let ret = &input.sig.output;
-    let body = &input.block;
-    let attrs = &input.attrs;
-    let vis = input.vis;
-
-    if sig.asyncness.is_none() {
-        let msg = "the async keyword is missing from the function declaration";
-        return Err(syn::Error::new_spanned(sig.fn_token, msg));
-    }
-
-    let macro_name = "pyo3_asyncio::tokio::main";
+) -> Result<FinalConfig, syn::Error> {
     let mut config = Configuration::new(is_test, rt_multi_thread);
+    let macro_name = config.macro_name();
 
     for arg in args {
         match arg {
@@ -169,12 +213,19 @@ fn parse_knobs(
                     "flavor" => {
                         config.set_flavor(namevalue.lit.clone(), namevalue.span())?;
                     }
+                    "start_paused" if is_test => {
+                        config.set_start_paused(namevalue.lit.clone(), namevalue.span())?;
+                    }
                     "core_threads" => {
                         let msg = "Attribute `core_threads` is renamed to `worker_threads`";
                         return Err(syn::Error::new_spanned(namevalue, msg));
                     }
                     name => {
-                        let msg = format!("Unknown attribute {} is specified; expected one of: `flavor`, `worker_threads`", name);
+                        let msg = format!(
+                            "Unknown attribute {} is specified; expected one of: {}",
+                            name,
+                            config.attributes()
+                        );
                         return Err(syn::Error::new_spanned(namevalue, msg));
                     }
                 }
@@ -199,11 +250,15 @@ fn parse_knobs(
                             macro_name
                         )
                     }
-                    "flavor" | "worker_threads" => {
+                    "flavor" | "worker_threads" | "start_paused" => {
                         format!("The `{}` attribute requires an argument.", name)
                     }
                     name => {
-                        format!("Unknown attribute {} is specified; expected one of: `flavor`, `worker_threads`", name)
+                        format!(
+                            "Unknown attribute {} is specified; expected one of: {}",
+                            name,
+                            config.attributes()
+                        )
                     }
                 };
                 return Err(syn::Error::new_spanned(path, msg));
@@ -217,8 +272,11 @@ fn parse_knobs(
         }
     }
 
-    let config = config.build()?;
+    config.build()
+}
 
+/// The `Builder` of the runtime described by `config`, with all of its drivers enabled
+fn runtime_builder(config: &FinalConfig) -> proc_macro2::TokenStream {
     let mut rt = match config.flavor {
         RuntimeFlavor::CurrentThread => quote! {
             pyo3_asyncio::tokio::re_exports::runtime::Builder::new_current_thread()
@@ -230,10 +288,37 @@ fn parse_knobs(
     if let Some(v) = config.worker_threads {
         rt = quote! { #rt.worker_threads(#v) };
     }
+    rt = quote! { #rt.enable_all() };
+    if let Some(v) = config.start_paused {
+        rt = quote! { #rt.start_paused(#v) };
+    }
+
+    rt
+}
+
+fn parse_knobs(
+    input: syn::ItemFn,
+    args: syn::AttributeArgs,
+    is_test: bool,
+    rt_multi_thread: bool,
+) -> Result<TokenStream, syn::Error> {
+    let sig = &input.sig;
+    let ret = &input.sig.output;
+    let body = &input.block;
+    let attrs = &input.attrs;
+    let vis = input.vis;
+
+    if sig.asyncness.is_none() {
+        let msg = "the async keyword is missing from the function declaration";
+        return Err(syn::Error::new_spanned(sig.fn_token, msg));
+    }
+
+    let config = parse_config(args, is_test, rt_multi_thread)?;
+    let rt = runtime_builder(&config);
 
     let rt_init = match config.flavor {
         RuntimeFlavor::CurrentThread => quote! {
-            std::thread::spawn(|| pyo3_asyncio::tokio::get_runtime().block_on(
+            std::thread::spawn(move || rt.block_on(
                 pyo3_asyncio::tokio::re_exports::pending::<()>()
             ));
         },
@@ -247,12 +332,9 @@ fn parse_knobs(
                 #body
             }
 
-            pyo3_asyncio::tokio::init(
-                #rt
-                    .enable_all()
-                    .build()
-                    .unwrap()
-            );
+            let rt = #rt.build().unwrap();
+
+            pyo3_asyncio::tokio::init(rt.handle().clone());
 
             #rt_init
 
@@ -287,3 +369,15 @@ pub(crate) fn main(args: TokenStream, item: TokenStream, rt_multi_thread: bool)
 
     parse_knobs(input, args, false, rt_multi_thread).unwrap_or_else(|e| e.to_compile_error().into())
 }
+
+/// The `Builder` of the runtime that a test with the `args` options runs on
+///
+/// Tests that don't set any options share the runtime that `pyo3_asyncio::tokio` was initialized
+/// with instead.
+#[cfg(not(test))]
+pub(crate) fn test_runtime(
+    args: syn::AttributeArgs,
+) -> Result<proc_macro2::TokenStream, syn::Error> {
+    let config = parse_config(args, true, true)?;
+    Ok(runtime_builder(&config))
+}
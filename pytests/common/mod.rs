@@ -1,8 +1,20 @@
-use std::{thread, time::Duration};
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
 
-use pyo3::prelude::*;
+use futures::future::BoxFuture;
+use pyo3::{prelude::*, wrap_pyfunction};
+use pyo3_asyncio::{
+    generic::AsyncContextManager, AwaitError, BridgeConfig, ConversionKind, ConversionObserver,
+    OverflowPolicy,
+};
 
-pub(super) const TEST_MOD: &'static str = r#"
+pub(super) const TEST_MOD: &str = r#"
 import asyncio 
 
 async def py_sleep(duration):
@@ -55,7 +67,1866 @@ pub(super) async fn test_other_awaitables() -> PyResult<()> {
 pub(super) fn test_init_twice() -> PyResult<()> {
     // try_init has already been called in test main - ensure a second call doesn't mess the other
     // tests up
-    Python::with_gil(|py| pyo3_asyncio::try_init(py))?;
+    Python::with_gil(pyo3_asyncio::try_init)?;
+
+    Ok(())
+}
+
+#[cfg(unix)]
+pub(super) async fn test_add_reader() -> PyResult<()> {
+    use std::{
+        io::Write,
+        os::unix::{io::AsRawFd, net::UnixStream},
+    };
+
+    let (mut tx, rx) = UnixStream::pair()?;
+    tx.write_all(b"ping")?;
+
+    Python::with_gil(|py| pyo3_asyncio::add_reader(py, rx.as_raw_fd()))?.await?;
+
+    Ok(())
+}
+
+#[cfg(unix)]
+pub(super) async fn test_add_reader_bad_fd() -> PyResult<()> {
+    let result = Python::with_gil(|py| pyo3_asyncio::add_reader(py, -1))?.await;
+
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[cfg(unix)]
+const DROPPED_READER_MOD: &str = r#"
+import asyncio
+
+async def reader_removed(fd):
+    # let the event loop run the callbacks scheduled by the dropped future
+    await asyncio.sleep(0.05)
+    return not asyncio.get_running_loop().remove_reader(fd)
+"#;
+
+#[cfg(unix)]
+pub(super) async fn test_add_reader_dropped() -> PyResult<()> {
+    use std::os::unix::{io::AsRawFd, net::UnixStream};
+
+    // nothing is ever written, so the reader can only go away by being removed
+    let (_tx, rx) = UnixStream::pair()?;
+
+    let reader = Python::with_gil(|py| pyo3_asyncio::add_reader(py, rx.as_raw_fd()))?;
+    drop(reader);
+
+    let removed = Python::with_gil(|py| {
+        let test_mod = PyModule::from_code(
+            py,
+            DROPPED_READER_MOD,
+            "test_add_reader_dropped/test_mod.py",
+            "test_mod",
+        )?;
+
+        pyo3_asyncio::into_future(test_mod.call_method1("reader_removed", (rx.as_raw_fd(),))?)
+    })?
+    .await?;
+
+    assert!(Python::with_gil(|py| removed.extract::<bool>(py))?);
+
+    Ok(())
+}
+
+pub(super) async fn test_panic(coro: PyObject) -> PyResult<()> {
+    let fut = Python::with_gil(|py| pyo3_asyncio::into_future(coro.as_ref(py)))?;
+
+    match fut.await {
+        Ok(_) => panic!("coroutine should have raised an exception"),
+        Err(e) => Python::with_gil(|py| {
+            assert!(e.is_instance::<pyo3::exceptions::PyRuntimeError>(py));
+            assert!(e
+                .pvalue(py)
+                .to_string()
+                .contains("rust future panicked: oh no"));
+        }),
+    }
+
+    Ok(())
+}
+
+pub(super) async fn test_await_error() -> PyResult<()> {
+    const RAISE_MOD: &str = r#"
+import asyncio
+
+async def raise_cancelled():
+    raise asyncio.CancelledError()
+
+async def raise_timeout():
+    raise asyncio.TimeoutError()
+
+async def raise_stop():
+    raise StopAsyncIteration()
+
+async def raise_other():
+    raise ValueError()
+"#;
+
+    let raise_mod = Python::with_gil(|py| -> PyResult<PyObject> {
+        Ok(
+            PyModule::from_code(py, RAISE_MOD, "test_await_error/raise_mod.py", "raise_mod")?
+                .into(),
+        )
+    })?;
+
+    for name in &[
+        "raise_cancelled",
+        "raise_timeout",
+        "raise_stop",
+        "raise_other",
+    ] {
+        let fut = Python::with_gil(|py| {
+            pyo3_asyncio::into_future(raise_mod.call_method0(py, name)?.as_ref(py))
+        })?;
+
+        let err = fut.await.unwrap_err();
+        let err = Python::with_gil(|py| AwaitError::from_py_err(py, err));
+
+        match (*name, err) {
+            ("raise_cancelled", AwaitError::Cancelled(_))
+            | ("raise_timeout", AwaitError::Timeout(_))
+            | ("raise_stop", AwaitError::StopAsyncIteration(_))
+            | ("raise_other", AwaitError::Other(_)) => (),
+            (name, err) => panic!("{} was classified as {:?}", name, err),
+        }
+    }
+
+    Ok(())
+}
+
+pub(super) async fn test_scope() -> PyResult<()> {
+    use pyo3_asyncio::scope::Scope;
+
+    let asyncio = Python::with_gil(|py| py.import("asyncio").map(PyObject::from))?;
+
+    let mut scope = Scope::new();
+    Python::with_gil(|py| {
+        scope.spawn_awaitable(asyncio.call_method1(py, "sleep", (0.1,))?.as_ref(py))?;
+        scope.spawn_awaitable(asyncio.call_method1(py, "sleep", (0.2,))?.as_ref(py))
+    })?;
+    scope.spawn(async { Ok(()) });
+    scope.join().await?;
+
+    Ok(())
+}
+
+pub(super) async fn test_scope_failure_cancels_siblings() -> PyResult<()> {
+    use pyo3_asyncio::scope::Scope;
+
+    let asyncio = Python::with_gil(|py| py.import("asyncio").map(PyObject::from))?;
+    let start = std::time::Instant::now();
+
+    let mut scope = Scope::new();
+    Python::with_gil(|py| {
+        scope.spawn_awaitable(asyncio.call_method1(py, "sleep", (10,))?.as_ref(py))
+    })?;
+    scope.spawn(futures::future::pending());
+    scope.spawn(async { Err(pyo3::exceptions::PyValueError::new_err("oh no")) });
+
+    let err = scope.join().await.unwrap_err();
+    Python::with_gil(|py| assert!(err.is_instance::<pyo3::exceptions::PyValueError>(py)));
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    Ok(())
+}
+
+pub(super) async fn test_stats(coro: PyObject, runtime: &str) -> PyResult<()> {
+    let fut = Python::with_gil(|py| pyo3_asyncio::into_future(coro.as_ref(py)))?;
+    fut.await?;
+
+    let spawns = pyo3_asyncio::stats()
+        .spawns
+        .iter()
+        .filter(|(name, _)| name.ends_with(runtime))
+        .map(|(_, count)| *count)
+        .sum::<u64>();
+    assert!(spawns >= 1);
+
+    let dropped = pyo3_asyncio::stats().dropped_into_future;
+
+    let asyncio = Python::with_gil(|py| py.import("asyncio").map(PyObject::from))?;
+    let fut = Python::with_gil(|py| {
+        pyo3_asyncio::into_future(asyncio.call_method1(py, "sleep", (10,))?.as_ref(py))
+    })?;
+    assert!(pyo3_asyncio::stats().pending_into_future >= 1);
+
+    drop(fut);
+    assert!(pyo3_asyncio::stats().dropped_into_future > dropped);
+
+    Ok(())
+}
+
+static CREATED: AtomicU64 = AtomicU64::new(0);
+static RESOLVED: AtomicU64 = AtomicU64::new(0);
+static ERRORED: AtomicU64 = AtomicU64::new(0);
+
+struct CountingObserver {}
+
+impl ConversionObserver for CountingObserver {
+    fn on_created(&self, kind: ConversionKind) {
+        if kind == ConversionKind::IntoFuture {
+            CREATED.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    fn on_resolved(&self, kind: ConversionKind, _elapsed: Duration) {
+        if kind == ConversionKind::IntoFuture {
+            RESOLVED.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    fn on_errored(&self, kind: ConversionKind, _elapsed: Duration) {
+        if kind == ConversionKind::IntoCoroutine {
+            ERRORED.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+}
+
+/// `make_coro` must convert a Rust future that fails
+pub(super) async fn test_conversion_observer(
+    make_coro: impl FnOnce(Python) -> PyResult<PyObject>,
+) -> PyResult<()> {
+    pyo3_asyncio::set_conversion_observer(CountingObserver {});
+
+    let asyncio = Python::with_gil(|py| py.import("asyncio").map(PyObject::from))?;
+    let fut = Python::with_gil(|py| {
+        pyo3_asyncio::into_future(asyncio.call_method1(py, "sleep", (0,))?.as_ref(py))
+    })?;
+    assert!(CREATED.load(Ordering::SeqCst) >= 1);
+
+    fut.await?;
+    assert!(RESOLVED.load(Ordering::SeqCst) >= 1);
+
+    let fut = Python::with_gil(|py| pyo3_asyncio::into_future(make_coro(py)?.as_ref(py)))?;
+    assert!(fut.await.is_err());
+    assert!(ERRORED.load(Ordering::SeqCst) >= 1);
+
+    Ok(())
+}
+
+const AWAITABLES_MOD: &str = r#"
+import asyncio
+
+class OnlyAwait:
+    def __await__(self):
+        yield from asyncio.sleep(0).__await__()
+        return 1
+
+async def coro(val):
+    return val
+
+async def make_awaitables():
+    loop = asyncio.get_running_loop()
+
+    fut = loop.create_future()
+    loop.call_later(0.1, fut.set_result, 2)
+
+    return [OnlyAwait(), fut, coro(3), loop.create_task(coro(4))]
+"#;
+
+pub(super) async fn test_awaitable_kinds() -> PyResult<()> {
+    // futures and tasks have to be created on the event loop
+    let fut = Python::with_gil(|py| {
+        let test_mod = PyModule::from_code(
+            py,
+            AWAITABLES_MOD,
+            "test_awaitable_kinds/test_mod.py",
+            "test_mod",
+        )?;
+
+        pyo3_asyncio::into_future(test_mod.call_method0("make_awaitables")?)
+    })?;
+    let awaitables = fut.await?;
+
+    for i in 0..4 {
+        let fut =
+            Python::with_gil(|py| pyo3_asyncio::into_future(awaitables.as_ref(py).get_item(i)?))?;
+
+        let result = fut.await?;
+        assert_eq!(Python::with_gil(|py| result.extract::<usize>(py))?, i + 1);
+    }
+
+    Python::with_gil(|py| {
+        let err = pyo3_asyncio::into_future(1i32.into_py(py).as_ref(py))
+            .err()
+            .unwrap();
+        assert!(err.is_instance::<pyo3::exceptions::PyTypeError>(py));
+    });
+
+    Ok(())
+}
+
+const EAGER_TASKS_MOD: &str = r#"
+import asyncio
+
+async def value(val):
+    return val
+
+async def sleep_value(val):
+    await asyncio.sleep(0.01)
+    return val
+
+async def fail(val):
+    raise ValueError(val)
+"#;
+
+async fn await_value(test_mod: &PyObject, name: &str, val: i32) -> PyResult<i32> {
+    let fut = Python::with_gil(|py| {
+        pyo3_asyncio::into_future(test_mod.call_method1(py, name, (val,))?.as_ref(py))
+    })?;
+    let result = fut.await?;
+
+    Python::with_gil(|py| result.extract(py))
+}
+
+pub(super) async fn test_eager_task_factory() -> PyResult<()> {
+    let (test_mod, event_loop) = Python::with_gil(|py| -> PyResult<_> {
+        let asyncio = py.import("asyncio")?;
+        if !asyncio.hasattr("eager_task_factory")? {
+            return Err(pyo3_asyncio::testing::skip(
+                "eager task factories were added in Python 3.12",
+            ));
+        }
+
+        let event_loop = pyo3_asyncio::get_event_loop(py);
+        event_loop.call_method1(
+            "set_task_factory",
+            (asyncio.getattr("eager_task_factory")?,),
+        )?;
+
+        let test_mod = PyModule::from_code(
+            py,
+            EAGER_TASKS_MOD,
+            "test_eager_task_factory/test_mod.py",
+            "test_mod",
+        )?;
+
+        Ok((PyObject::from(test_mod), PyObject::from(event_loop)))
+    })?;
+
+    let result = async {
+        // finishes inside ensure_future
+        assert_eq!(await_value(&test_mod, "value", 1).await?, 1);
+        // suspends inside ensure_future
+        assert_eq!(await_value(&test_mod, "sleep_value", 2).await?, 2);
+        // fails inside ensure_future
+        let err = await_value(&test_mod, "fail", 3).await.unwrap_err();
+        Python::with_gil(|py| assert!(err.is_instance::<pyo3::exceptions::PyValueError>(py)));
+
+        Ok(())
+    }
+    .await;
+
+    Python::with_gil(|py| event_loop.call_method1(py, "set_task_factory", (py.None(),)))?;
+
+    result
+}
+
+const PENDING_IO_MOD: &str = r#"
+import asyncio
+import socket
+
+async def cancel_pending_recv():
+    loop = asyncio.get_running_loop()
+    a, b = socket.socketpair()
+    a.setblocking(False)
+
+    try:
+        # nothing is ever sent, so the receive is still waiting on the loop's selector or proactor
+        task = loop.create_task(loop.sock_recv(a, 1))
+        await asyncio.sleep(0.01)
+        task.cancel()
+
+        try:
+            await task
+        except asyncio.CancelledError:
+            return "cancelled"
+
+        return "completed"
+    finally:
+        a.close()
+        b.close()
+"#;
+
+pub(super) async fn test_cancel_pending_io() -> PyResult<()> {
+    let fut = Python::with_gil(|py| {
+        let test_mod = PyModule::from_code(
+            py,
+            PENDING_IO_MOD,
+            "test_cancel_pending_io/test_mod.py",
+            "test_mod",
+        )?;
+
+        pyo3_asyncio::into_future(test_mod.call_method0("cancel_pending_recv")?)
+    })?;
+
+    let outcome = fut.await?;
+    assert_eq!(
+        Python::with_gil(|py| outcome.extract::<String>(py))?,
+        "cancelled"
+    );
+
+    Ok(())
+}
+
+#[cfg(windows)]
+pub(super) fn test_proactor_event_loop() -> PyResult<()> {
+    Python::with_gil(|py| {
+        let asyncio = py.import("asyncio")?;
+        let isinstance = py.import("builtins")?.getattr("isinstance")?;
+
+        // the proactor policy is the default since Python 3.8
+        let policy = asyncio.call_method0("get_event_loop_policy")?;
+        let proactor_policy = asyncio.getattr("WindowsProactorEventLoopPolicy")?;
+        if !isinstance.call1((policy, proactor_policy))?.is_true()? {
+            return Ok(());
+        }
+
+        let event_loop = pyo3_asyncio::get_event_loop(py);
+        let proactor_loop = asyncio.getattr("ProactorEventLoop")?;
+        assert!(isinstance.call1((event_loop, proactor_loop))?.is_true()?);
+
+        Ok(())
+    })
+}
+
+#[cfg(windows)]
+const PROACTOR_MOD: &str = r#"
+import asyncio
+
+async def cancel(coro):
+    task = asyncio.ensure_future(coro)
+    await asyncio.sleep(0.01)
+    task.cancel()
+
+    try:
+        await task
+    except asyncio.CancelledError:
+        return "cancelled"
+
+    return "completed"
+"#;
+
+/// Run conversions on a `ProactorEventLoop`, whichever event loop the policy hands out
+#[cfg(windows)]
+pub(super) fn test_proactor_conversions<R>() -> PyResult<()>
+where
+    R: pyo3_asyncio::generic::Runtime,
+{
+    Python::with_gil(|py| {
+        let proactor_loop = py.import("asyncio")?.call_method0("ProactorEventLoop")?;
+        let test_mod = PyModule::from_code(
+            py,
+            PROACTOR_MOD,
+            "test_proactor_conversions/test_mod.py",
+            "test_mod",
+        )?;
+        let pending_io = PyModule::from_code(
+            py,
+            PENDING_IO_MOD,
+            "test_proactor_conversions/pending_io.py",
+            "pending_io",
+        )?;
+
+        let event_loop = PyObject::from(proactor_loop);
+        let cancel = PyObject::from(test_mod.getattr("cancel")?);
+        let cancel_pending_recv = PyObject::from(pending_io.getattr("cancel_pending_recv")?);
+        let (dropped_tx, dropped_rx) = futures::channel::oneshot::channel::<()>();
+
+        let result = pyo3_asyncio::generic::run_until_complete_with_loop::<R, _>(
+            py,
+            proactor_loop,
+            async move {
+                // a receive that is still waiting on the proactor is cancelled
+                let outcome = Python::with_gil(|py| {
+                    pyo3_asyncio::into_future_with_loop(
+                        event_loop.as_ref(py),
+                        cancel_pending_recv.as_ref(py).call0()?,
+                    )
+                })?
+                .await?;
+                assert_eq!(
+                    Python::with_gil(|py| outcome.extract::<String>(py))?,
+                    "cancelled"
+                );
+
+                // cancelling a scoped Rust coroutine from the proactor drops the Rust future
+                let outcome = Python::with_gil(|py| {
+                    let event_loop = event_loop.as_ref(py);
+                    let coro = pyo3_asyncio::generic::into_coroutine_scoped_with_loop::<R, _>(
+                        py,
+                        event_loop,
+                        async move {
+                            let _dropped = dropped_tx;
+                            futures::future::pending().await
+                        },
+                    )?;
+
+                    pyo3_asyncio::into_future_with_loop(
+                        event_loop,
+                        cancel.call1(py, (coro,))?.as_ref(py),
+                    )
+                })?
+                .await?;
+                assert_eq!(
+                    Python::with_gil(|py| outcome.extract::<String>(py))?,
+                    "cancelled"
+                );
+                assert!(dropped_rx.await.is_err());
+
+                Ok(())
+            },
+        );
+
+        proactor_loop.call_method0("close")?;
+        result
+    })
+}
+
+const DESTROYED_TASK_MOD: &str = r#"
+import asyncio
+
+async def wait_forever():
+    # nothing else references this future, so the task is garbage once it's waiting on it
+    await asyncio.get_running_loop().create_future()
+"#;
+
+pub(super) async fn test_destroyed_task() -> PyResult<()> {
+    let unresolved = pyo3_asyncio::stats().unresolved_py_futures;
+
+    let fut = Python::with_gil(|py| {
+        let test_mod = PyModule::from_code(
+            py,
+            DESTROYED_TASK_MOD,
+            "test_destroyed_task/test_mod.py",
+            "test_mod",
+        )?;
+
+        pyo3_asyncio::into_future(test_mod.call_method0("wait_forever")?)
+    })?;
+
+    // give the event loop a chance to start the task
+    let sleep = Python::with_gil(|py| {
+        pyo3_asyncio::into_future(py.import("asyncio")?.call_method1("sleep", (0.01,))?)
+    })?;
+    sleep.await?;
+
+    Python::with_gil(|py| -> PyResult<()> {
+        // the task and its future only reference each other, which takes a collection to free on
+        // CPython as well as PyPy. Silence the "Task was destroyed but it is pending!" report.
+        let event_loop = pyo3_asyncio::get_event_loop(py);
+        let handler = event_loop.call_method0("get_exception_handler")?;
+        let ignore = py.eval("lambda loop, context: None", None, None)?;
+
+        event_loop.call_method1("set_exception_handler", (ignore,))?;
+        let collected = py.import("gc")?.call_method0("collect");
+        event_loop.call_method1("set_exception_handler", (handler,))?;
+
+        collected.map(|_| ())
+    })?;
+
+    let err = fut.await.unwrap_err();
+    Python::with_gil(|py| {
+        let cancelled = py.import("asyncio")?.getattr("CancelledError")?;
+        assert!(err.matches(py, cancelled));
+
+        PyResult::Ok(())
+    })?;
+    assert!(pyo3_asyncio::stats().unresolved_py_futures > unresolved);
+
+    Ok(())
+}
+
+/// `run_async` must call the runtime's `with_runtime_async`
+pub(super) fn test_nested_with_runtime(
+    run_async: impl FnOnce(Python) -> PyResult<()>,
+) -> PyResult<()> {
+    Python::with_gil(|py| {
+        // the test harness is already running inside of with_runtime
+        assert_eq!(pyo3_asyncio::with_runtime(py, || Ok(42))?, 42);
+
+        // the event loop is already running the other tests
+        let err = run_async(py).unwrap_err();
+        assert!(err.is_instance::<pyo3::exceptions::PyRuntimeError>(py));
+
+        // neither call closed the outer event loop
+        let event_loop = pyo3_asyncio::get_event_loop(py);
+        assert!(!event_loop.call_method0("is_closed")?.is_true()?);
+
+        Ok(())
+    })
+}
+
+/// `make_coro` must convert a Rust future that completes on the given event loop
+pub(super) async fn test_closed_event_loop(
+    make_coro: impl FnOnce(Python, &PyAny) -> PyResult<PyObject>,
+) -> PyResult<()> {
+    let pending = Python::with_gil(|py| -> PyResult<_> {
+        let other_loop = py.import("asyncio")?.call_method0("new_event_loop")?;
+
+        // the awaitable is scheduled, but never picked up before the event loop is closed
+        let pending = pyo3_asyncio::into_future_with_loop(
+            other_loop,
+            other_loop.call_method0("create_future")?,
+        )?;
+        // the Rust future completes after the event loop is closed, its result is dropped quietly
+        let coro = make_coro(py, other_loop)?;
+        other_loop.call_method0("close")?;
+
+        let err = pyo3_asyncio::into_future_with_loop(
+            other_loop,
+            other_loop.call_method0("create_future")?,
+        )
+        .err()
+        .unwrap();
+        assert!(err.is_instance::<pyo3_asyncio::EventLoopClosedError>(py));
+        // it's still a RuntimeError, like the one asyncio raises
+        assert!(err.is_instance::<pyo3::exceptions::PyRuntimeError>(py));
+
+        drop(coro);
+        Ok(pending)
+    })?;
+
+    let err = pending.await.unwrap_err();
+    Python::with_gil(|py| {
+        assert!(matches!(
+            AwaitError::from_py_err(py, err),
+            AwaitError::EventLoopClosed(_)
+        ));
+    });
+
+    Ok(())
+}
+
+const CONTEXT_MANAGER_MOD: &str = r#"
+async def use_manager(manager, fail):
+    async with manager as value:
+        if fail:
+            raise ValueError("body failed")
+
+        return value
+"#;
+
+/// Records the calls made by `async with`
+pub(super) struct Recorder {
+    pub(super) log: Arc<Mutex<Vec<String>>>,
+    /// Whether exceptions raised inside of the block are suppressed
+    pub(super) suppress: bool,
+}
+
+impl AsyncContextManager for Recorder {
+    fn enter(self: Arc<Self>) -> BoxFuture<'static, PyResult<PyObject>> {
+        Box::pin(async move {
+            self.log.lock().unwrap().push("enter".to_string());
+            Python::with_gil(|py| Ok("resource".into_py(py)))
+        })
+    }
+
+    fn exit(self: Arc<Self>, err: Option<PyErr>) -> BoxFuture<'static, PyResult<bool>> {
+        Box::pin(async move {
+            let entry = match &err {
+                Some(err) => Python::with_gil(|py| format!("exit {}", err.pvalue(py))),
+                None => "exit".to_string(),
+            };
+            self.log.lock().unwrap().push(entry);
+
+            Ok(self.suppress)
+        })
+    }
+}
+
+/// `into_manager` must call the runtime's `into_async_context_manager`
+pub(super) async fn test_async_context_manager(
+    into_manager: impl Fn(Python, Recorder) -> PyResult<PyObject>,
+) -> PyResult<()> {
+    for (fail, suppress) in [(false, false), (true, false), (true, true)] {
+        let log = Arc::new(Mutex::new(Vec::new()));
+
+        let fut = Python::with_gil(|py| {
+            let test_mod = PyModule::from_code(
+                py,
+                CONTEXT_MANAGER_MOD,
+                "test_async_context_manager/test_mod.py",
+                "test_mod",
+            )?;
+            let manager = into_manager(
+                py,
+                Recorder {
+                    log: log.clone(),
+                    suppress,
+                },
+            )?;
+
+            pyo3_asyncio::into_future(test_mod.call_method1("use_manager", (manager, fail))?)
+        })?;
+        let result = fut.await;
+
+        let expected_exit = if fail { "exit body failed" } else { "exit" };
+        assert_eq!(*log.lock().unwrap(), vec!["enter", expected_exit]);
+
+        match (fail, suppress) {
+            (false, _) => Python::with_gil(|py| {
+                assert_eq!(result?.extract::<String>(py)?, "resource");
+                PyResult::Ok(())
+            })?,
+            (true, false) => Python::with_gil(|py| {
+                assert!(result
+                    .unwrap_err()
+                    .is_instance::<pyo3::exceptions::PyValueError>(py))
+            }),
+            // the exception is swallowed, so the block returns None
+            (true, true) => Python::with_gil(|py| assert!(result.unwrap().is_none(py))),
+        }
+    }
+
+    Ok(())
+}
+
+const ASYNC_ITER_MOD: &str = r#"
+import asyncio
+
+async def cancel_then_collect(it):
+    # nothing has been sent yet, so this is cancelled while waiting on the stream
+    pending = asyncio.ensure_future(it.__anext__())
+    await asyncio.sleep(0.01)
+    pending.cancel()
+
+    try:
+        await pending
+    except asyncio.CancelledError:
+        pass
+
+    return [item async for item in it]
+"#;
+
+/// `into_iter` must call the runtime's `into_async_iter`
+pub(super) async fn test_async_iter(
+    into_iter: impl FnOnce(
+        futures::stream::BoxStream<'static, PyResult<PyObject>>,
+    ) -> pyo3_asyncio::generic::PyAsyncIter,
+) -> PyResult<()> {
+    use futures::StreamExt;
+
+    let (tx, rx) = futures::channel::mpsc::unbounded::<i32>();
+    let stream = rx.map(|item| Python::with_gil(|py| Ok(item.into_py(py))));
+
+    // the iterator is stored as a Python object before anything iterates it
+    let it = Python::with_gil(|py| -> PyResult<PyObject> {
+        Ok(PyCell::new(py, into_iter(stream.boxed()))?.into())
+    })?;
+
+    let fut = Python::with_gil(|py| {
+        let test_mod = PyModule::from_code(
+            py,
+            ASYNC_ITER_MOD,
+            "test_async_iter/test_mod.py",
+            "test_mod",
+        )?;
+
+        pyo3_asyncio::into_future(test_mod.call_method1("cancel_then_collect", (it,))?)
+    })?;
+
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        for item in 1..=3 {
+            tx.unbounded_send(item).unwrap();
+        }
+    });
+
+    let items = fut.await?;
+    assert_eq!(
+        Python::with_gil(|py| items.extract::<Vec<i32>>(py))?,
+        vec![1, 2, 3]
+    );
+
+    Ok(())
+}
+
+const ASYNC_GEN_MOD: &str = r#"
+async def talk(gen):
+    replies = [
+        await gen.asend("hello"),
+        await gen.athrow(ValueError("oops")),
+        await gen.asend("world"),
+    ]
+
+    await gen.aclose()
+
+    try:
+        await gen.asend("again")
+    except StopAsyncIteration:
+        replies.append("closed")
+
+    return replies
+
+async def echo():
+    received = yield "ready"
+    while True:
+        try:
+            received = yield f"echo {received}"
+        except ValueError as e:
+            received = yield f"caught {e}"
+"#;
+
+/// `into_gen` must call the runtime's `into_async_gen`
+pub(super) async fn test_async_gen(
+    into_gen: impl FnOnce(
+        std::pin::Pin<Box<dyn futures::Sink<PyResult<PyObject>, Error = PyErr> + Send>>,
+        futures::stream::BoxStream<'static, PyResult<PyObject>>,
+    ) -> pyo3_asyncio::generic::PyAsyncGen,
+) -> PyResult<()> {
+    use futures::{SinkExt, StreamExt};
+
+    let test_mod = Python::with_gil(|py| -> PyResult<PyObject> {
+        Ok(
+            PyModule::from_code(py, ASYNC_GEN_MOD, "test_async_gen/test_mod.py", "test_mod")?
+                .into(),
+        )
+    })?;
+
+    // Rust echo protocol driven from Python
+    let (tx, rx) = futures::channel::mpsc::unbounded::<PyResult<PyObject>>();
+    let sink = tx.sink_map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()));
+    let replies = rx.map(|request| {
+        Python::with_gil(|py| {
+            Ok(match request {
+                Ok(value) => format!("echo {}", value.as_ref(py)),
+                Err(e) => format!("caught {}", e.instance(py)),
+            }
+            .into_py(py))
+        })
+    });
+
+    let fut = Python::with_gil(|py| {
+        let gen = PyCell::new(py, into_gen(Box::pin(sink), replies.boxed()))?;
+        pyo3_asyncio::into_future(test_mod.call_method1(py, "talk", (gen,))?.as_ref(py))
+    })?;
+
+    let replies = fut.await?;
+    assert_eq!(
+        Python::with_gil(|py| replies.extract::<Vec<String>>(py))?,
+        vec!["echo hello", "caught oops", "echo world", "closed"]
+    );
+
+    // Python echo protocol driven from Rust
+    let agen = Python::with_gil(|py| {
+        pyo3_asyncio::AsyncGenerator::new(test_mod.call_method0(py, "echo")?.as_ref(py))
+    })?;
+
+    let extract = |reply: Option<PyObject>| {
+        Python::with_gil(|py| reply.map(|reply| reply.extract::<String>(py)).transpose())
+    };
+
+    let ready = Python::with_gil(|py| agen.asend(py, py.None()))?.await?;
+    assert_eq!(extract(ready)?.as_deref(), Some("ready"));
+
+    let echoed = Python::with_gil(|py| agen.asend(py, "hello".into_py(py)))?.await?;
+    assert_eq!(extract(echoed)?.as_deref(), Some("echo hello"));
+
+    let caught =
+        Python::with_gil(|py| agen.athrow(py, pyo3::exceptions::PyValueError::new_err("oops")))?
+            .await?;
+    assert_eq!(extract(caught)?.as_deref(), Some("caught oops"));
+
+    Python::with_gil(|py| agen.aclose(py))?.await?;
+
+    let closed = Python::with_gil(|py| agen.asend(py, "again".into_py(py)))?.await?;
+    assert!(closed.is_none());
+
+    Ok(())
+}
+
+const BRIDGE_CONFIG_MOD: &str = r#"
+import asyncio
+
+produced = []
+
+async def collect_later(it):
+    await asyncio.sleep(0.2)
+
+    items = []
+    try:
+        async for item in it:
+            items.append(item)
+    except Exception as e:
+        items.append(type(e).__name__)
+
+    return items
+
+async def count(n):
+    for i in range(n):
+        produced.append(i)
+        yield i
+"#;
+
+/// `into_iter` and `into_stream` must call the runtime's `into_async_iter_with_config` and
+/// `into_stream`
+pub(super) async fn test_bridge_config<S>(
+    into_iter: impl Fn(
+        futures::stream::BoxStream<'static, PyResult<PyObject>>,
+        BridgeConfig,
+    ) -> pyo3_asyncio::generic::PyAsyncIter,
+    into_stream: impl FnOnce(&PyAny, BridgeConfig) -> PyResult<S>,
+) -> PyResult<()>
+where
+    S: futures::Stream<Item = PyResult<PyObject>> + Send + 'static,
+{
+    use futures::StreamExt;
+
+    let test_mod = Python::with_gil(|py| -> PyResult<PyObject> {
+        Ok(PyModule::from_code(
+            py,
+            BRIDGE_CONFIG_MOD,
+            "test_bridge_config/test_mod.py",
+            "test_mod",
+        )?
+        .into())
+    })?;
+
+    // a Rust producer that runs ahead of a slow Python consumer
+    for (overflow_policy, expected) in [
+        (OverflowPolicy::DropOldest, "[7, 8, 9]"),
+        (OverflowPolicy::Error, "[0, 1, 2, 'BufferOverflowError']"),
+    ] {
+        let items = futures::stream::iter(0..10)
+            .map(|item| Python::with_gil(|py| Ok(item.into_py(py))))
+            .boxed();
+        let config = BridgeConfig {
+            buffer_size: 3,
+            overflow_policy,
+        };
+
+        let fut = Python::with_gil(|py| {
+            let it = PyCell::new(py, into_iter(items, config))?;
+            pyo3_asyncio::into_future(
+                test_mod
+                    .call_method1(py, "collect_later", (it,))?
+                    .as_ref(py),
+            )
+        })?;
+
+        let collected = fut.await?;
+        assert_eq!(
+            Python::with_gil(|py| collected.as_ref(py).repr()?.extract::<String>())?,
+            expected
+        );
+    }
+
+    // a Python producer that waits for a slow Rust consumer
+    let stream = Python::with_gil(|py| {
+        into_stream(
+            test_mod.call_method1(py, "count", (10,))?.as_ref(py),
+            BridgeConfig {
+                buffer_size: 2,
+                overflow_policy: OverflowPolicy::Block,
+            },
+        )
+    })?;
+
+    let sleep = Python::with_gil(|py| {
+        pyo3_asyncio::into_future(py.import("asyncio")?.call_method1("sleep", (0.2,))?)
+    })?;
+    sleep.await?;
+
+    // two buffered items, and one more waiting for room in the buffer
+    let produced = Python::with_gil(|py| test_mod.getattr(py, "produced")?.as_ref(py).len())?;
+    assert_eq!(produced, 3);
+
+    let items = stream
+        .map(|item| item.and_then(|item| Python::with_gil(|py| item.extract::<i32>(py))))
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<PyResult<Vec<_>>>()?;
+    assert_eq!(items, (0..10).collect::<Vec<_>>());
+
+    Ok(())
+}
+
+const CALL_ASYNC_MOD: &str = r#"
+async def add(a, b=0):
+    return a + b
+
+def not_async(a):
+    return a
+
+async def fail_later():
+    raise ValueError("later")
+"#;
+
+pub(super) async fn test_call_async() -> PyResult<()> {
+    let test_mod = Python::with_gil(|py| -> PyResult<PyObject> {
+        Ok(PyModule::from_code(
+            py,
+            CALL_ASYNC_MOD,
+            "test_call_async/test_mod.py",
+            "test_mod",
+        )?
+        .into())
+    })?;
+
+    let sum = Python::with_gil(|py| -> PyResult<_> {
+        let kwargs = pyo3::types::PyDict::new(py);
+        kwargs.set_item("b", 2)?;
+
+        Ok(pyo3_asyncio::call_async(
+            py,
+            test_mod.getattr(py, "add")?.as_ref(py),
+            (1,),
+            Some(kwargs),
+        ))
+    })?
+    .await?;
+    assert_eq!(Python::with_gil(|py| sum.extract::<i32>(py))?, 3);
+
+    // the call fails before there's anything to await
+    let wrong_args = Python::with_gil(|py| -> PyResult<_> {
+        Ok(pyo3_asyncio::call_async(
+            py,
+            test_mod.getattr(py, "add")?.as_ref(py),
+            (),
+            None,
+        ))
+    })?
+    .await;
+
+    // the result isn't awaitable
+    let not_awaitable = Python::with_gil(|py| -> PyResult<_> {
+        Ok(pyo3_asyncio::call_async(
+            py,
+            test_mod.getattr(py, "not_async")?.as_ref(py),
+            (1,),
+            None,
+        ))
+    })?
+    .await;
+
+    // the coroutine fails
+    let failed = Python::with_gil(|py| -> PyResult<_> {
+        Ok(pyo3_asyncio::call_async(
+            py,
+            test_mod.getattr(py, "fail_later")?.as_ref(py),
+            (),
+            None,
+        ))
+    })?
+    .await;
+
+    Python::with_gil(|py| {
+        assert!(wrong_args
+            .unwrap_err()
+            .is_instance::<pyo3::exceptions::PyTypeError>(py));
+        assert!(not_awaitable
+            .unwrap_err()
+            .is_instance::<pyo3::exceptions::PyTypeError>(py));
+        assert!(failed
+            .unwrap_err()
+            .is_instance::<pyo3::exceptions::PyValueError>(py));
+    });
+
+    Ok(())
+}
+
+/// `into_coroutine` must call the runtime's `into_coroutine_eager`
+pub(super) async fn test_await_released(
+    into_coroutine: impl FnOnce(Python, BoxFuture<'static, PyResult<PyObject>>) -> PyResult<PyObject>,
+) -> PyResult<()> {
+    use futures::FutureExt;
+    use pyo3_asyncio::PyFutureExt;
+
+    let fut = Python::with_gil(|py| {
+        // the first poll happens right here, while this thread holds the GIL. It blocks on a
+        // thread that needs the GIL too, which would deadlock if the GIL weren't released.
+        let coro = into_coroutine(
+            py,
+            async {
+                let value = thread::spawn(|| Python::with_gil(|py| 42.into_py(py)))
+                    .join()
+                    .unwrap();
+                Ok(value)
+            }
+            .await_released()
+            .boxed(),
+        )?;
+
+        pyo3_asyncio::into_future(coro.as_ref(py))
+    })?;
+
+    let value = fut.await?;
+    assert_eq!(Python::with_gil(|py| value.extract::<i32>(py))?, 42);
+
+    Ok(())
+}
+
+const TASK_NAMES_MOD: &str = r#"
+import asyncio
+
+async def task_name():
+    return asyncio.current_task().get_name()
+
+async def call(f):
+    return f()
+"#;
+
+#[pyfunction]
+fn rust_task_name(py: Python) -> PyResult<Option<String>> {
+    pyo3_asyncio::current_task_name(py)
+}
+
+pub(super) async fn test_task_names() -> PyResult<()> {
+    let test_mod = Python::with_gil(|py| -> PyResult<PyObject> {
+        Ok(PyModule::from_code(
+            py,
+            TASK_NAMES_MOD,
+            "test_task_names/test_mod.py",
+            "test_mod",
+        )?
+        .into())
+    })?;
+
+    let derived = Python::with_gil(|py| {
+        pyo3_asyncio::into_future(test_mod.call_method0(py, "task_name")?.as_ref(py))
+    })?;
+    let named = Python::with_gil(|py| {
+        pyo3_asyncio::into_future_named(
+            test_mod.call_method0(py, "task_name")?.as_ref(py),
+            "custom",
+        )
+    })?;
+
+    // the name of the calling task is visible from Rust
+    let from_rust = Python::with_gil(|py| {
+        let rust_task_name = wrap_pyfunction!(rust_task_name, test_mod.as_ref(py).downcast()?)?;
+        pyo3_asyncio::into_future_named(
+            test_mod
+                .call_method1(py, "call", (rust_task_name,))?
+                .as_ref(py),
+            "calls rust",
+        )
+    })?;
+
+    let (derived, named, from_rust) = (derived.await?, named.await?, from_rust.await?);
+
+    Python::with_gil(|py| -> PyResult<()> {
+        assert_eq!(
+            derived.extract::<String>(py)?,
+            "pyo3_asyncio::into_future(task_name)"
+        );
+        assert_eq!(named.extract::<String>(py)?, "custom");
+        assert_eq!(from_rust.extract::<String>(py)?, "calls rust");
+
+        // no task is running on this thread
+        assert_eq!(pyo3_asyncio::current_task_name(py)?, None);
+
+        Ok(())
+    })
+}
+
+const DEBUG_MODE_MOD: &str = r#"
+import asyncio
+import logging
+
+class Records(logging.Handler):
+    def __init__(self):
+        super().__init__(logging.WARNING)
+        self.messages = []
+
+    def emit(self, record):
+        self.messages.append(record.getMessage())
+
+async def capture_slow(make_coro):
+    loop = asyncio.get_running_loop()
+    loop.set_debug(True)
+    slow_callback_duration = loop.slow_callback_duration
+    loop.slow_callback_duration = 0.05
+
+    records = Records()
+    logging.getLogger("asyncio").addHandler(records)
+
+    try:
+        fast = make_coro(False)
+        slow = make_coro(True)
+        await fast
+        await slow
+    finally:
+        logging.getLogger("asyncio").removeHandler(records)
+        loop.slow_callback_duration = slow_callback_duration
+        loop.set_debug(False)
+
+    origin = fast._source_traceback[-1]
+    return origin.filename, origin.name, records.messages
+"#;
+
+#[pyclass]
+struct MakeDebugCoro {
+    into_coroutine: fn(Python, BoxFuture<'static, PyResult<PyObject>>) -> PyResult<PyObject>,
+}
+
+#[pymethods]
+impl MakeDebugCoro {
+    #[call]
+    fn __call__(&self, py: Python, slow: bool) -> PyResult<PyObject> {
+        (self.into_coroutine)(
+            py,
+            Box::pin(async move {
+                if slow {
+                    // blocks the first poll
+                    thread::sleep(Duration::from_millis(100));
+                }
+
+                Python::with_gil(|py| Ok(py.None()))
+            }),
+        )
+    }
+}
+
+/// `into_coroutine` must call the runtime's `into_coroutine_eager`
+pub(super) async fn test_debug_mode(
+    into_coroutine: fn(Python, BoxFuture<'static, PyResult<PyObject>>) -> PyResult<PyObject>,
+) -> PyResult<()> {
+    let fut = Python::with_gil(|py| {
+        let test_mod = PyModule::from_code(
+            py,
+            DEBUG_MODE_MOD,
+            "test_debug_mode/test_mod.py",
+            "test_mod",
+        )?;
+
+        pyo3_asyncio::into_future(
+            test_mod.call_method1("capture_slow", (MakeDebugCoro { into_coroutine },))?,
+        )
+    })?;
+
+    let result = fut.await?;
+    let (filename, name, messages) =
+        Python::with_gil(|py| result.extract::<(String, String, Vec<String>)>(py))?;
+
+    // the location of the caller, not of the crate internals
+    assert_eq!(name, "<rust future>");
+    assert!(filename.starts_with("pytests"));
+    assert_eq!(messages.len(), 1);
+    assert!(messages[0].starts_with("Executing Rust future created at"));
+
+    Ok(())
+}
+
+const DEBUG_ORIGIN_MOD: &str = r#"
+import asyncio
+
+async def capture_origins(make_coros):
+    loop = asyncio.get_running_loop()
+    futures = []
+    create_future = loop.create_future
+
+    def record():
+        fut = create_future()
+        futures.append(fut)
+        return fut
+
+    loop.create_future = record
+    loop.set_debug(True)
+
+    try:
+        for coro in make_coros(loop):
+            await coro
+    finally:
+        loop.set_debug(False)
+        del loop.create_future
+
+    return [
+        fut._source_traceback[-1].filename
+        for fut in futures
+        if fut._source_traceback[-1].name == "<rust future>"
+    ]
+"#;
+
+#[pyclass]
+struct MakeOriginCoros {
+    make_coros: fn(Python, &PyAny) -> PyResult<Vec<PyObject>>,
+}
+
+#[pymethods]
+impl MakeOriginCoros {
+    #[call]
+    fn __call__(&self, py: Python, event_loop: &PyAny) -> PyResult<Vec<PyObject>> {
+        (self.make_coros)(py, event_loop)
+    }
+}
+
+fn make_origin_coros<R>(py: Python, event_loop: &PyAny) -> PyResult<Vec<PyObject>>
+where
+    R: pyo3_asyncio::generic::Runtime,
+{
+    let stream = futures::stream::iter(vec![Ok(py.None())]);
+
+    Ok(vec![
+        pyo3_asyncio::generic::into_coroutine_scoped_with_loop::<R, _>(py, event_loop, async {
+            Ok(Python::with_gil(|py| py.None()))
+        })?,
+        pyo3_asyncio::generic::into_coroutine_deferred::<R, _>(py, async {
+            Ok(Python::with_gil(|py| py.None()))
+        })?,
+        PyCell::new(py, pyo3_asyncio::generic::PyAsyncIter::new::<R, _>(stream))?
+            .call_method0("__anext__")?
+            .into(),
+    ])
+}
+
+/// The deferred and scoped conversions are attributed to their caller in debug mode too
+pub(super) async fn test_debug_origin<R>() -> PyResult<()>
+where
+    R: pyo3_asyncio::generic::Runtime,
+{
+    let fut = Python::with_gil(|py| {
+        let test_mod = PyModule::from_code(
+            py,
+            DEBUG_ORIGIN_MOD,
+            "test_debug_origin/test_mod.py",
+            "test_mod",
+        )?;
+
+        pyo3_asyncio::into_future(test_mod.call_method1(
+            "capture_origins",
+            (MakeOriginCoros {
+                make_coros: make_origin_coros::<R>,
+            },),
+        )?)
+    })?;
+
+    let result = fut.await?;
+    let filenames = Python::with_gil(|py| result.extract::<Vec<String>>(py))?;
+
+    // the location of the caller, not of the crate internals
+    assert_eq!(filenames.len(), 3);
+    assert!(filenames
+        .iter()
+        .all(|filename| filename.starts_with("pytests")));
+
+    Ok(())
+}
+
+const BLOCKING_LOOP_MOD: &str = r#"
+async def call(f):
+    return f()
+"#;
+
+#[pyfunction]
+fn run_forever(py: Python) -> PyResult<()> {
+    pyo3_asyncio::run_forever(py)
+}
+
+/// `run_until_complete` must call the runtime's `run_until_complete` on the given event loop
+pub(super) async fn test_blocking_entry_points(
+    run_until_complete: fn(Python, &PyAny) -> PyResult<()>,
+) -> PyResult<()> {
+    use pyo3::exceptions::PyRuntimeError;
+
+    // this test runs on a thread of the Rust runtime
+    Python::with_gil(|py| -> PyResult<()> {
+        let other_loop = py.import("asyncio")?.call_method0("new_event_loop")?;
+        let err = run_until_complete(py, other_loop).unwrap_err();
+        other_loop.call_method0("close")?;
+
+        assert!(err.is_instance::<PyRuntimeError>(py));
+        assert!(err.to_string().contains("can't block a thread of the"));
+
+        Ok(())
+    })?;
+
+    // this one runs on the event loop thread
+    let fut = Python::with_gil(|py| {
+        let test_mod = PyModule::from_code(
+            py,
+            BLOCKING_LOOP_MOD,
+            "test_blocking_entry_points/test_mod.py",
+            "test_mod",
+        )?;
+        let run_forever = wrap_pyfunction!(run_forever, test_mod)?;
+
+        pyo3_asyncio::into_future(test_mod.call_method1("call", (run_forever,))?)
+    })?;
+
+    let err = fut.await.unwrap_err();
+    Python::with_gil(|py| {
+        assert!(err.is_instance::<PyRuntimeError>(py));
+        assert!(err
+            .to_string()
+            .contains("can't be called from a thread that is running an event loop"));
+    });
+
+    Ok(())
+}
+
+pub(super) async fn test_into_future_of() -> PyResult<()> {
+    let text = Python::with_gil(|py| {
+        pyo3_asyncio::into_future_of::<String>(
+            py.import("asyncio")?
+                .call_method1("sleep", (0.01, "hello"))?,
+        )
+    })?
+    .await?;
+    assert_eq!(text, "hello");
+
+    let bytes = Python::with_gil(|py| {
+        pyo3_asyncio::into_future_of::<Vec<u8>>(
+            py.import("asyncio")?
+                .call_method1("sleep", (0.01, vec![1u8, 2, 3]))?,
+        )
+    })?
+    .await?;
+    assert_eq!(bytes, [1, 2, 3]);
+
+    // the extraction fails, but the awaitable still ran to completion
+    let wrong_type = Python::with_gil(|py| {
+        pyo3_asyncio::into_future_of::<i32>(
+            py.import("asyncio")?
+                .call_method1("sleep", (0.01, "not a number"))?,
+        )
+    })?
+    .await;
+
+    Python::with_gil(|py| {
+        assert!(wrong_type
+            .unwrap_err()
+            .is_instance::<pyo3::exceptions::PyTypeError>(py));
+    });
+
+    Ok(())
+}
+
+const BUDGET_MOD: &str = r#"
+import asyncio
+
+async def heartbeats_during(compute):
+    beats = 0
+    done = False
+
+    async def heartbeat():
+        nonlocal beats
+        while not done:
+            beats += 1
+            await asyncio.sleep(0.005)
+
+    task = asyncio.ensure_future(heartbeat())
+    await asyncio.sleep(0)
+
+    # the conversion starts on the event loop, like a call from Python would
+    await compute()
+    done = True
+    await task
+
+    return beats
+"#;
+
+type IntoCoroutine = fn(Python, BoxFuture<'static, PyResult<PyObject>>) -> PyResult<PyObject>;
+
+#[pyclass]
+struct BusyCompute {
+    into_coroutine: IntoCoroutine,
+}
+
+#[pymethods]
+impl BusyCompute {
+    #[call]
+    fn __call__(&self, py: Python) -> PyResult<PyObject> {
+        use futures::FutureExt;
+
+        (self.into_coroutine)(
+            py,
+            async {
+                let mut budget = pyo3_asyncio::Budget::new(Duration::from_millis(5));
+                let start = Instant::now();
+
+                while start.elapsed() < Duration::from_millis(200) {
+                    budget.tick().await;
+                    std::hint::spin_loop();
+                }
+
+                Ok(Python::with_gil(|py| py.None()))
+            }
+            .boxed(),
+        )
+    }
+}
+
+pub(super) async fn test_budget(into_coroutine: IntoCoroutine) -> PyResult<()> {
+    let fut = Python::with_gil(|py| {
+        let test_mod = PyModule::from_code(py, BUDGET_MOD, "test_budget/test_mod.py", "test_mod")?;
+
+        pyo3_asyncio::into_future(
+            test_mod.call_method1("heartbeats_during", (BusyCompute { into_coroutine },))?,
+        )
+    })?;
+
+    // without yielding, the whole computation would run on the event loop and block the heartbeat
+    let beats = fut.await?;
+    assert!(Python::with_gil(|py| beats.extract::<u32>(py))? >= 5);
+
+    Ok(())
+}
+
+pub(super) async fn test_yield_now() -> PyResult<()> {
+    let ran = Python::with_gil(|py| -> PyResult<PyObject> {
+        let ran = py.import("threading")?.call_method0("Event")?;
+        pyo3_asyncio::get_event_loop(py)
+            .call_method1("call_soon_threadsafe", (ran.getattr("set")?,))?;
+
+        Ok(ran.into())
+    })?;
+
+    // the yield goes through the event loop, so the callback queued before it has run
+    pyo3_asyncio::yield_now().await;
+    assert!(Python::with_gil(|py| ran
+        .call_method0(py, "is_set")?
+        .extract::<bool>(py))?);
+
+    Ok(())
+}
+
+const DEADLINE_MOD: &str = r#"
+import asyncio
+import sys
+
+# asyncio.timeout was added in Python 3.11, and wait_for is built on it since Python 3.12
+HAS_TIMEOUT = sys.version_info >= (3, 11)
+WAIT_FOR_HAS_TIMEOUT = sys.version_info >= (3, 12)
+
+async def call(f):
+    return f()
+
+async def in_timeouts(f):
+    async with asyncio.timeout(10):
+        async with asyncio.timeout(0.5):
+            return f()
+
+async def rescheduled(f):
+    async with asyncio.timeout(10) as timeout:
+        timeout.reschedule(asyncio.get_running_loop().time() + 0.5)
+        return f()
+
+async def in_child_task(f):
+    # the timeout belongs to this task, not to the tasks it creates
+    async with asyncio.timeout(0.5):
+        return await asyncio.create_task(call(f))
+
+async def after_timeout(f):
+    async with asyncio.timeout(0.5):
+        pass
+    return f()
+
+async def in_wait_for(f):
+    return await asyncio.wait_for(call(f), 0.5)
+"#;
+
+#[pyfunction]
+fn rust_deadline(py: Python) -> PyResult<Option<f64>> {
+    Ok(pyo3_asyncio::current_deadline(py)?.map(|deadline| {
+        deadline
+            .saturating_duration_since(Instant::now())
+            .as_secs_f64()
+    }))
+}
+
+pub(super) async fn test_current_deadline() -> PyResult<()> {
+    let (test_mod, has_timeout, wait_for_has_timeout) =
+        Python::with_gil(|py| -> PyResult<(PyObject, bool, bool)> {
+            let test_mod = PyModule::from_code(
+                py,
+                DEADLINE_MOD,
+                "test_current_deadline/test_mod.py",
+                "test_mod",
+            )?;
+
+            Ok((
+                test_mod.into(),
+                test_mod.getattr("HAS_TIMEOUT")?.extract()?,
+                test_mod.getattr("WAIT_FOR_HAS_TIMEOUT")?.extract()?,
+            ))
+        })?;
+
+    let mut cases = vec![("call", None), ("in_wait_for", None)];
+    if has_timeout {
+        cases.extend(vec![
+            ("in_timeouts", Some(0.5)),
+            ("rescheduled", Some(0.5)),
+            ("in_child_task", None),
+            ("after_timeout", None),
+        ]);
+    }
+    if wait_for_has_timeout {
+        cases[1].1 = Some(0.5);
+    }
+
+    for (helper, expected) in cases {
+        let fut = Python::with_gil(|py| {
+            let rust_deadline = wrap_pyfunction!(rust_deadline, test_mod.as_ref(py).downcast()?)?;
+            pyo3_asyncio::into_future_of::<Option<f64>>(
+                test_mod
+                    .call_method1(py, helper, (rust_deadline,))?
+                    .as_ref(py),
+            )
+        })?;
+
+        match (fut.await?, expected) {
+            (None, None) => (),
+            // the earliest of the timeouts around the task, minus the time it took to get there
+            (Some(remaining), Some(expected)) => {
+                assert!(
+                    remaining <= expected && remaining > expected - 0.2,
+                    "{}",
+                    helper
+                )
+            }
+            (remaining, _) => panic!("{}: unexpected deadline {:?}", helper, remaining),
+        }
+    }
+
+    // no task is running on this thread
+    Python::with_gil(|py| {
+        assert_eq!(pyo3_asyncio::current_deadline(py)?, None);
+        Ok(())
+    })
+}
+
+#[cfg(feature = "async-channel")]
+const CHANNEL_MOD: &str = r#"
+import asyncio
+
+def fill(tx):
+    tx.put_nowait(0)
+    try:
+        tx.put_nowait(1)
+    except asyncio.QueueFull:
+        return "full"
+
+async def produce(tx):
+    for item in range(1, 4):
+        await tx.put(item)
+
+    tx.close()
+    try:
+        await tx.put(4)
+    except RuntimeError:
+        return "closed"
+
+async def consume(rx):
+    # a cancelled get leaves the next item in the channel
+    get = asyncio.ensure_future(rx.get())
+    await asyncio.sleep(0.01)
+    get.cancel()
+    try:
+        await get
+    except asyncio.CancelledError:
+        pass
+
+    items = []
+    while True:
+        try:
+            async for item in rx:
+                items.append(item)
+            break
+        except ValueError as e:
+            items.append(str(e))
+
+    try:
+        rx.get_nowait()
+    except RuntimeError:
+        items.append("closed")
+
+    return items
+
+async def numbers(n):
+    for i in range(n):
+        yield i
+
+async def deliver(sender):
+    await asyncio.sleep(0.01)
+    sender.set_result("done")
+    try:
+        sender.set_result("again")
+    except asyncio.InvalidStateError:
+        return "delivered"
+"#;
+
+#[cfg(feature = "async-channel")]
+pub(super) async fn test_channel<R>() -> PyResult<()>
+where
+    R: pyo3_asyncio::generic::Runtime,
+{
+    use futures::StreamExt;
+    use pyo3::exceptions::{PyRuntimeError, PyValueError};
+    use pyo3_asyncio::generic::channel::{self, PyReceiver, PySender};
+
+    let test_mod: PyObject = Python::with_gil(|py| -> PyResult<PyObject> {
+        Ok(PyModule::from_code(py, CHANNEL_MOD, "test_channel/test_mod.py", "test_mod")?.into())
+    })?;
+
+    // Python -> Rust queue
+    let (tx, rx) = async_channel::bounded::<PyObject>(1);
+    let produced = Python::with_gil(|py| {
+        let tx = PyCell::new(py, PySender::new::<R>(tx))?;
+        assert_eq!(
+            test_mod
+                .call_method1(py, "fill", (tx,))?
+                .extract::<String>(py)?,
+            "full"
+        );
+
+        pyo3_asyncio::into_future(test_mod.call_method1(py, "produce", (tx,))?.as_ref(py))
+    })?;
+
+    let (produced, items) = futures::join!(produced, rx.collect::<Vec<_>>());
+    Python::with_gil(|py| -> PyResult<()> {
+        assert_eq!(produced?.extract::<String>(py)?, "closed");
+        let items = items
+            .iter()
+            .map(|item| item.extract(py))
+            .collect::<PyResult<Vec<i32>>>()?;
+        assert_eq!(items, vec![0, 1, 2, 3]);
+        Ok(())
+    })?;
+
+    // Rust -> Python queue
+    let (tx, rx) = async_channel::bounded::<PyResult<PyObject>>(4);
+    let consumed = Python::with_gil(|py| {
+        let rx = PyCell::new(py, PyReceiver::new::<R>(rx))?;
+        pyo3_asyncio::into_future(test_mod.call_method1(py, "consume", (rx,))?.as_ref(py))
+    })?;
+
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        Python::with_gil(|py| {
+            tx.send_blocking(Ok(1.into_py(py))).unwrap();
+            tx.send_blocking(Err(PyValueError::new_err("oops")))
+                .unwrap();
+            tx.send_blocking(Ok(2.into_py(py))).unwrap();
+        });
+    });
+
+    let consumed = consumed.await?;
+    Python::with_gil(|py| -> PyResult<()> {
+        assert_eq!(
+            consumed.as_ref(py).repr()?.to_str()?,
+            "[1, 'oops', 2, 'closed']"
+        );
+        Ok(())
+    })?;
+
+    // a Python async iterator shared by several Rust consumers
+    let rx = Python::with_gil(|py| {
+        channel::into_receiver::<R>(
+            test_mod.call_method1(py, "numbers", (10,))?.as_ref(py),
+            BridgeConfig {
+                buffer_size: 2,
+                overflow_policy: OverflowPolicy::Block,
+            },
+        )
+    })?;
+
+    let (left, right) = futures::join!(rx.clone().collect::<Vec<_>>(), rx.collect::<Vec<_>>());
+    let mut numbers = Python::with_gil(|py| {
+        left.into_iter()
+            .chain(right)
+            .map(|item| item?.extract::<i32>(py))
+            .collect::<PyResult<Vec<_>>>()
+    })?;
+    numbers.sort_unstable();
+    assert_eq!(numbers, (0..10).collect::<Vec<_>>());
+
+    let sleep = |secs: f64| {
+        Python::with_gil(|py| {
+            pyo3_asyncio::into_future(py.import("asyncio")?.call_method1("sleep", (secs,))?)
+        })
+    };
+
+    // a consumer that falls behind loses the oldest items
+    let rx = Python::with_gil(|py| {
+        channel::into_receiver::<R>(
+            test_mod.call_method1(py, "numbers", (10,))?.as_ref(py),
+            BridgeConfig {
+                buffer_size: 2,
+                overflow_policy: OverflowPolicy::DropOldest,
+            },
+        )
+    })?;
+    while !rx.is_closed() {
+        sleep(0.01)?.await?;
+    }
+    let items = rx.collect::<Vec<_>>().await;
+    let numbers = Python::with_gil(|py| {
+        items
+            .into_iter()
+            .map(|item| item?.extract::<i32>(py))
+            .collect::<PyResult<Vec<_>>>()
+    })?;
+    assert_eq!(numbers, vec![8, 9]);
+
+    // or gets an error after the items that were buffered
+    let rx = Python::with_gil(|py| {
+        channel::into_receiver::<R>(
+            test_mod.call_method1(py, "numbers", (10,))?.as_ref(py),
+            BridgeConfig {
+                buffer_size: 2,
+                overflow_policy: OverflowPolicy::Error,
+            },
+        )
+    })?;
+    while !rx.is_full() {
+        sleep(0.01)?.await?;
+    }
+    sleep(0.05)?.await?;
+    let items = rx.collect::<Vec<_>>().await;
+    Python::with_gil(|py| -> PyResult<()> {
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0].as_ref().unwrap().extract::<i32>(py)?, 0);
+        assert_eq!(items[1].as_ref().unwrap().extract::<i32>(py)?, 1);
+        assert!(items[2]
+            .as_ref()
+            .unwrap_err()
+            .is_instance::<pyo3_asyncio::BufferOverflowError>(py));
+        Ok(())
+    })?;
+
+    // Rust -> Python oneshot
+    let (sender, awaitable) = Python::with_gil(channel::oneshot::<R>)?;
+    let result = Python::with_gil(|py| pyo3_asyncio::into_future(awaitable.as_ref(py)))?;
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        Python::with_gil(|py| sender.send(Ok("hi".into_py(py))).unwrap());
+    });
+    let result = result.await?;
+    assert_eq!(Python::with_gil(|py| result.extract::<String>(py))?, "hi");
+
+    let (sender, awaitable) = Python::with_gil(channel::oneshot::<R>)?;
+    drop(sender);
+    let result = Python::with_gil(|py| pyo3_asyncio::into_future(awaitable.as_ref(py)))?.await;
+    Python::with_gil(|py| assert!(result.unwrap_err().is_instance::<PyRuntimeError>(py)));
+
+    // Python -> Rust oneshot
+    let (sender, result) = Python::with_gil(channel::py_oneshot)?;
+    let delivered = Python::with_gil(|py| {
+        pyo3_asyncio::into_future(test_mod.call_method1(py, "deliver", (sender,))?.as_ref(py))
+    })?;
+
+    let (delivered, result) = futures::join!(delivered, result);
+    Python::with_gil(|py| -> PyResult<()> {
+        assert_eq!(delivered?.extract::<String>(py)?, "delivered");
+        assert_eq!(result?.extract::<String>(py)?, "done");
+        Ok(())
+    })?;
+
+    let (sender, result) = Python::with_gil(channel::py_oneshot)?;
+    drop(sender);
+    Python::with_gil(|py| {
+        assert!(futures::executor::block_on(result)
+            .unwrap_err()
+            .is_instance::<PyRuntimeError>(py))
+    });
 
     Ok(())
 }
@@ -1,9 +1,17 @@
 mod common;
 
-use std::time::Duration;
+use std::{
+    any::Any,
+    cell::RefCell,
+    future::Future,
+    panic::{self, AssertUnwindSafe},
+    rc::Rc,
+    time::Duration,
+};
 
 use async_std::task;
 use pyo3::{prelude::*, wrap_pyfunction};
+use pyo3_asyncio::generic::{JoinError, Runtime, SpawnBlockingExt, UnwindSafeExt};
 
 #[pyfunction]
 fn sleep_for(py: Python, secs: &PyAny) -> PyResult<PyObject> {
@@ -41,8 +49,7 @@ async fn test_into_coroutine() -> PyResult<()> {
 
 #[pyo3_asyncio::async_std::test]
 async fn test_async_sleep() -> PyResult<()> {
-    let asyncio =
-        Python::with_gil(|py| py.import("asyncio").map(|asyncio| PyObject::from(asyncio)))?;
+    let asyncio = Python::with_gil(|py| py.import("asyncio").map(PyObject::from))?;
 
     task::sleep(Duration::from_secs(1)).await;
 
@@ -69,12 +76,362 @@ async fn test_other_awaitables() -> PyResult<()> {
     common::test_other_awaitables().await
 }
 
+#[pyo3_asyncio::async_std::test]
+async fn test_awaitable_kinds() -> PyResult<()> {
+    common::test_awaitable_kinds().await
+}
+
+#[pyo3_asyncio::async_std::test]
+async fn test_eager_task_factory() -> PyResult<()> {
+    common::test_eager_task_factory().await
+}
+
+#[pyo3_asyncio::async_std::test]
+async fn test_panic() -> PyResult<()> {
+    let coro = Python::with_gil(|py| {
+        pyo3_asyncio::async_std::into_coroutine(py, async {
+            panic!("oh no");
+        })
+    })?;
+
+    common::test_panic(coro).await
+}
+
+#[pyo3_asyncio::async_std::test]
+async fn test_panic_error_converter() -> PyResult<()> {
+    let fut = Python::with_gil(|py| {
+        let coro = pyo3_asyncio::async_std::into_coroutine_with_error_converter(
+            py,
+            async {
+                panic!("oh no");
+            },
+            |_py, failure| {
+                pyo3::exceptions::PyValueError::new_err(failure.panic_message().unwrap())
+            },
+        )?;
+
+        pyo3_asyncio::into_future(coro.as_ref(py))
+    })?;
+
+    let err = fut.await.unwrap_err();
+    Python::with_gil(|py| {
+        assert!(err.is_instance::<pyo3::exceptions::PyValueError>(py));
+        assert_eq!(err.pvalue(py).to_string(), "oh no");
+    });
+
+    Ok(())
+}
+
+#[pyo3_asyncio::async_std::test]
+async fn test_await_error() -> PyResult<()> {
+    common::test_await_error().await
+}
+
+#[pyo3_asyncio::async_std::test]
+async fn test_stats() -> PyResult<()> {
+    let coro = Python::with_gil(|py| {
+        pyo3_asyncio::async_std::into_coroutine(py, async {
+            task::sleep(Duration::from_millis(10)).await;
+            Python::with_gil(|py| Ok(py.None()))
+        })
+    })?;
+
+    common::test_stats(coro, "AsyncStdRuntime").await
+}
+
+#[pyo3_asyncio::async_std::test]
+async fn test_conversion_observer() -> PyResult<()> {
+    common::test_conversion_observer(|py| {
+        pyo3_asyncio::async_std::into_coroutine(py, async {
+            Err(pyo3::exceptions::PyValueError::new_err("oh no"))
+        })
+    })
+    .await
+}
+
+#[pyo3_asyncio::async_std::test]
+async fn test_cancel_pending_io() -> PyResult<()> {
+    common::test_cancel_pending_io().await
+}
+
+#[cfg(windows)]
+#[pyo3_asyncio::async_std::test]
+fn test_proactor_event_loop() -> PyResult<()> {
+    common::test_proactor_event_loop()
+}
+
+#[cfg(windows)]
+#[pyo3_asyncio::async_std::test]
+async fn test_proactor_conversions() -> PyResult<()> {
+    // the proactor runs on a blocking thread while the PyO3 Asyncio loop keeps running
+    async_std::task::spawn_blocking(
+        common::test_proactor_conversions::<pyo3_asyncio::async_std::AsyncStdRuntime>,
+    )
+    .await
+}
+
+#[pyo3_asyncio::async_std::test]
+async fn test_destroyed_task() -> PyResult<()> {
+    common::test_destroyed_task().await
+}
+
+#[pyo3_asyncio::async_std::test]
+fn test_nested_with_runtime() -> PyResult<()> {
+    common::test_nested_with_runtime(|py| {
+        pyo3_asyncio::async_std::with_runtime_async(py, async { Ok(()) })
+    })
+}
+
+#[pyo3_asyncio::async_std::test]
+async fn test_closed_event_loop() -> PyResult<()> {
+    common::test_closed_event_loop(|py, event_loop| {
+        pyo3_asyncio::async_std::into_coroutine_with_loop(py, event_loop, async {
+            async_std::task::sleep(Duration::from_millis(10)).await;
+            Python::with_gil(|py| Ok(py.None()))
+        })
+    })
+    .await
+}
+
+#[pyo3_asyncio::async_std::test]
+async fn test_async_context_manager() -> PyResult<()> {
+    common::test_async_context_manager(pyo3_asyncio::async_std::into_async_context_manager).await
+}
+
+#[pyo3_asyncio::async_std::test]
+async fn test_async_iter() -> PyResult<()> {
+    common::test_async_iter(pyo3_asyncio::async_std::into_async_iter).await
+}
+
+#[pyo3_asyncio::async_std::test]
+async fn test_async_gen() -> PyResult<()> {
+    common::test_async_gen(pyo3_asyncio::async_std::into_async_gen).await
+}
+
+#[pyo3_asyncio::async_std::test]
+async fn test_bridge_config() -> PyResult<()> {
+    common::test_bridge_config(
+        pyo3_asyncio::async_std::into_async_iter_with_config,
+        pyo3_asyncio::async_std::into_stream,
+    )
+    .await
+}
+
+#[pyo3_asyncio::async_std::test]
+async fn test_call_async() -> PyResult<()> {
+    common::test_call_async().await
+}
+
+#[pyo3_asyncio::async_std::test]
+async fn test_await_released() -> PyResult<()> {
+    common::test_await_released(pyo3_asyncio::async_std::into_coroutine_eager).await
+}
+
+#[pyo3_asyncio::async_std::test]
+async fn test_task_names() -> PyResult<()> {
+    common::test_task_names().await
+}
+
+#[pyo3_asyncio::async_std::test]
+async fn test_debug_mode() -> PyResult<()> {
+    common::test_debug_mode(|py, fut| pyo3_asyncio::async_std::into_coroutine_eager(py, fut)).await
+}
+
+#[pyo3_asyncio::async_std::test]
+async fn test_debug_origin() -> PyResult<()> {
+    common::test_debug_origin::<pyo3_asyncio::async_std::AsyncStdRuntime>().await
+}
+
+#[pyo3_asyncio::async_std::test]
+async fn test_blocking_entry_points() -> PyResult<()> {
+    common::test_blocking_entry_points(|py, event_loop| {
+        pyo3_asyncio::async_std::run_until_complete_with_loop(py, event_loop, async { Ok(()) })
+    })
+    .await
+}
+
+#[pyo3_asyncio::async_std::test]
+async fn test_into_future_of() -> PyResult<()> {
+    common::test_into_future_of().await
+}
+
+#[pyo3_asyncio::async_std::test]
+async fn test_budget() -> PyResult<()> {
+    common::test_budget(pyo3_asyncio::async_std::into_coroutine).await
+}
+
+#[pyo3_asyncio::async_std::test]
+async fn test_yield_now() -> PyResult<()> {
+    common::test_yield_now().await
+}
+
+#[pyo3_asyncio::async_std::test]
+async fn test_current_deadline() -> PyResult<()> {
+    common::test_current_deadline().await
+}
+
+#[cfg(feature = "async-channel")]
+#[pyo3_asyncio::async_std::test]
+async fn test_channel() -> PyResult<()> {
+    common::test_channel::<pyo3_asyncio::async_std::AsyncStdRuntime>().await
+}
+
+#[pyo3_asyncio::async_std::test]
+async fn test_scope() -> PyResult<()> {
+    common::test_scope().await
+}
+
+#[pyo3_asyncio::async_std::test]
+async fn test_scope_failure_cancels_siblings() -> PyResult<()> {
+    common::test_scope_failure_cancels_siblings().await
+}
+
+#[pyo3_asyncio::async_std::test]
+async fn test_into_coroutine_named() -> PyResult<()> {
+    let fut = Python::with_gil(|py| {
+        let coro = pyo3_asyncio::async_std::into_coroutine_named(py, "named_task", async {
+            let name = task::current().name().map(|name| name.to_string());
+            Python::with_gil(|py| Ok(name.into_py(py)))
+        })?;
+
+        pyo3_asyncio::into_future(coro.as_ref(py))
+    })?;
+
+    let name = fut.await?;
+    assert_eq!(
+        Python::with_gil(|py| name.extract::<Option<String>>(py))?,
+        Some("named_task".to_string())
+    );
+
+    Ok(())
+}
+
 #[pyo3_asyncio::async_std::test]
 fn test_init_twice() -> PyResult<()> {
     common::test_init_twice()
 }
 
+#[cfg(unix)]
+#[pyo3_asyncio::async_std::test]
+async fn test_add_reader() -> PyResult<()> {
+    common::test_add_reader().await
+}
+
+#[cfg(unix)]
+#[pyo3_asyncio::async_std::test]
+async fn test_add_reader_bad_fd() -> PyResult<()> {
+    common::test_add_reader_bad_fd().await
+}
+
+#[cfg(unix)]
+#[pyo3_asyncio::async_std::test]
+async fn test_add_reader_dropped() -> PyResult<()> {
+    common::test_add_reader_dropped().await
+}
+
+#[pyo3_asyncio::async_std::test]
+async fn test_into_coroutine_local() -> PyResult<()> {
+    // spawn_local needs a thread that runs async-std tasks, so wait for the test to be moved off of
+    // the main thread
+    let sleep = Python::with_gil(|py| {
+        pyo3_asyncio::into_future(py.import("asyncio")?.call_method1("sleep", (0,))?)
+    })?;
+    sleep.await?;
+
+    let coro = Python::with_gil(|py| {
+        // the test itself must be Send, so the Rc only lives in the local future
+        let answer = Rc::new(RefCell::new(0));
+
+        pyo3_asyncio::async_std::into_coroutine_local(py, async move {
+            task::yield_now().await;
+            *answer.borrow_mut() = 42;
+            Ok(Python::with_gil(|py| (*answer.borrow()).into_py(py)))
+        })
+    })?;
+
+    let value = Python::with_gil(|py| pyo3_asyncio::into_future(coro.as_ref(py)))?.await?;
+    assert_eq!(Python::with_gil(|py| value.extract::<i32>(py))?, 42);
+
+    Ok(())
+}
+
+struct CustomJoinError(Box<dyn Any + Send + 'static>);
+
+impl JoinError for CustomJoinError {
+    fn is_panic(&self) -> bool {
+        true
+    }
+    fn into_panic(self) -> Box<dyn Any + Send + 'static> {
+        self.0
+    }
+}
+
+/// A third-party style runtime on top of async-std, checked with the conformance tests
+struct CustomRuntime;
+
+impl Runtime for CustomRuntime {
+    type JoinError = CustomJoinError;
+    type JoinHandle = task::JoinHandle<Result<(), CustomJoinError>>;
+
+    fn spawn<F>(fut: F) -> Self::JoinHandle
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        task::spawn(async move { Self::catch_unwind(fut).await.map_err(CustomJoinError) })
+    }
+
+    fn is_runtime_thread() -> bool {
+        task::try_current().is_some()
+    }
+}
+
+/// A runtime that leaves the panics of its tasks alone, like runtimes written before the
+/// conversions caught their own
+struct UncaughtRuntime;
+
+impl Runtime for UncaughtRuntime {
+    type JoinError = CustomJoinError;
+    type JoinHandle = task::JoinHandle<Result<(), CustomJoinError>>;
+
+    fn spawn<F>(fut: F) -> Self::JoinHandle
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        task::spawn(async move {
+            fut.await;
+            Ok(())
+        })
+    }
+}
+
+impl UnwindSafeExt for CustomRuntime {}
+
+impl SpawnBlockingExt for CustomRuntime {
+    fn spawn_blocking<F>(f: F) -> Self::JoinHandle
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        task::spawn_blocking(move || {
+            panic::catch_unwind(AssertUnwindSafe(f)).map_err(CustomJoinError)
+        })
+    }
+}
+
 #[pyo3_asyncio::async_std::main]
 async fn main() -> pyo3::PyResult<()> {
-    pyo3_asyncio::testing::main().await
+    use pyo3_asyncio::{generic::testing, testing::Test};
+
+    let mut tests = testing::tests::<CustomRuntime>();
+    tests.extend(testing::unwind_safe_tests::<CustomRuntime>());
+    tests.extend(testing::spawn_blocking_tests::<CustomRuntime>());
+    tests.extend(
+        testing::tests::<UncaughtRuntime>()
+            .into_iter()
+            .map(|test| Test {
+                name: format!("uncaught::{}", test.name),
+                ..test
+            }),
+    );
+
+    pyo3_asyncio::testing::main_with(tests).await
 }
@@ -1,6 +1,9 @@
-use std::time::Duration;
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
 
-use pyo3::prelude::*;
+use pyo3::{prelude::*, AsPyPointer};
 
 fn dump_err(py: Python<'_>) -> impl FnOnce(PyErr) + '_ {
     move |e| {
@@ -10,9 +13,24 @@ fn dump_err(py: Python<'_>) -> impl FnOnce(PyErr) + '_ {
     }
 }
 
+static LOOPS_CREATED: AtomicUsize = AtomicUsize::new(0);
+
 fn main() {
+    pyo3_asyncio::set_event_loop_factory(|py| {
+        LOOPS_CREATED.fetch_add(1, Ordering::SeqCst);
+
+        let event_loop = py.import("asyncio")?.call_method0("new_event_loop")?;
+        event_loop.setattr("created_by_factory", true)?;
+        Ok(event_loop.into())
+    });
+
     Python::with_gil(|py| {
         pyo3_asyncio::with_runtime(py, || {
+            assert!(pyo3_asyncio::get_event_loop(py)
+                .getattr("created_by_factory")?
+                .is_true()?);
+            println!("test test_event_loop_factory ... ok");
+
             async_std::task::spawn(async move {
                 async_std::task::sleep(Duration::from_secs(1)).await;
 
@@ -32,9 +50,141 @@ fn main() {
             pyo3_asyncio::run_forever(py)?;
 
             println!("test test_run_forever ... ok");
+
+            let stop = pyo3_asyncio::StopHandle::new(py);
+            let trigger = stop.clone();
+
+            async_std::task::spawn(async move {
+                async_std::task::sleep(Duration::from_millis(100)).await;
+                trigger.stop().unwrap();
+            });
+
+            pyo3_asyncio::run_forever_with_stop_handle(py, &stop)?;
+            assert!(stop.is_stopped());
+
+            // triggering the handle again after the event loop has stopped is harmless
+            stop.stop()?;
+
+            println!("test test_run_forever_with_stop_handle ... ok");
+            Ok(())
+        })
+        .map_err(dump_err(py))
+        .unwrap();
+
+        // the event loop was closed by with_runtime, closing it again is a no-op
+        pyo3_asyncio::try_close(py).map_err(dump_err(py)).unwrap();
+        println!("test test_close_twice ... ok");
+
+        // a closed event loop is replaced by a new one
+        pyo3_asyncio::async_std::with_runtime_async(py, async {
+            async_std::task::sleep(Duration::from_millis(10)).await;
+
+            let sleep = Python::with_gil(|py| {
+                pyo3_asyncio::into_future(py.import("asyncio")?.call_method1("sleep", (0.01,))?)
+            })?;
+            sleep.await?;
+
             Ok(())
         })
         .map_err(dump_err(py))
         .unwrap();
+        println!("test test_with_runtime_async_after_close ... ok");
+
+        // closed event loops are replaced with the factory as well
+        assert_eq!(LOOPS_CREATED.load(Ordering::SeqCst), 2);
+        println!("test test_event_loop_factory_after_close ... ok");
+
+        test_runner(py).map_err(dump_err(py)).unwrap();
+        println!("test test_runner ... ok");
+
+        test_refresh_event_loop(py).map_err(dump_err(py)).unwrap();
+        println!("test test_refresh_event_loop ... ok");
     })
 }
+
+const RUNNER_MOD: &str = r#"
+async def get_var(var):
+    return var.get(None)
+"#;
+
+fn test_runner(py: Python) -> PyResult<()> {
+    let test_mod: PyObject =
+        PyModule::from_code(py, RUNNER_MOD, "test_runner/test_mod.py", "test_mod")?.into();
+    let var: PyObject = py
+        .import("contextvars")?
+        .call_method1("ContextVar", ("pyo3_asyncio_runner",))?
+        .into();
+
+    let mut runner = pyo3_asyncio::async_std::PyRunner::new(py)?;
+    runner
+        .context(py)
+        .call_method1("run", (var.getattr(py, "set")?, 42))?;
+
+    // the runner's context is shared by every run
+    for _ in 0..2 {
+        let test_mod = test_mod.clone();
+        let var = var.clone();
+
+        let value = runner.run(py, async move {
+            async_std::task::sleep(Duration::from_millis(10)).await;
+
+            Python::with_gil(|py| {
+                pyo3_asyncio::into_future_of::<Option<i32>>(
+                    test_mod.call_method1(py, "get_var", (var,))?.as_ref(py),
+                )
+            })?
+            .await
+        })?;
+        assert_eq!(value, Some(42));
+    }
+
+    // but the variable isn't set outside of it
+    assert!(var.call_method1(py, "get", (py.None(),))?.is_none(py));
+
+    runner.close(py)?;
+    assert!(pyo3_asyncio::get_event_loop(py)
+        .call_method0("is_closed")?
+        .is_true()?);
+    assert!(runner.run(py, async { Ok(()) }).is_err());
+
+    Ok(())
+}
+
+fn test_refresh_event_loop(py: Python) -> PyResult<()> {
+    // the runner closed the event loop, so conversions can't be scheduled on it anymore
+    assert!(pyo3_asyncio::get_event_loop(py)
+        .call_method0("is_closed")?
+        .is_true()?);
+    let err =
+        pyo3_asyncio::async_std::into_coroutine(py, async { Ok(Python::with_gil(|py| py.None())) })
+            .unwrap_err();
+    assert!(err.is_instance::<pyo3_asyncio::EventLoopClosedError>(py));
+
+    // i.e. a test runner that creates a new event loop for each test
+    let asyncio = py.import("asyncio")?;
+    let new_loop = asyncio.call_method0("new_event_loop")?;
+    asyncio.call_method1("set_event_loop", (new_loop,))?;
+
+    let event_loop = pyo3_asyncio::refresh_event_loop(py)?;
+    assert_eq!(
+        pyo3_asyncio::get_event_loop(py).as_ptr(),
+        event_loop.as_ptr()
+    );
+    // the open loop set by the framework is picked up, even though a factory has been set
+    assert_eq!(event_loop.as_ptr(), new_loop.as_ptr());
+    assert!(!event_loop.hasattr("created_by_factory")?);
+
+    let coro = pyo3_asyncio::async_std::into_coroutine(py, async {
+        async_std::task::sleep(Duration::from_millis(10)).await;
+        Ok(Python::with_gil(|py| 42.into_py(py)))
+    })?;
+    let value: i32 = event_loop
+        .call_method1("run_until_complete", (coro,))?
+        .extract()?;
+    assert_eq!(value, 42);
+
+    pyo3_asyncio::try_close(py)?;
+    new_loop.call_method0("close")?;
+
+    Ok(())
+}
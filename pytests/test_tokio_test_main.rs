@@ -0,0 +1,73 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use pyo3::prelude::*;
+
+static SET_UP: AtomicBool = AtomicBool::new(false);
+
+const SHARED_MOD: &str = r#"
+async def answer():
+    return 42
+"#;
+
+fn setup(py: Python) -> PyResult<()> {
+    assert!(!SET_UP.swap(true, Ordering::SeqCst), "setup ran twice");
+
+    let shared = PyModule::from_code(py, SHARED_MOD, "shared.py", "shared")?;
+    py.import("sys")?
+        .getattr("modules")?
+        .set_item("shared", shared)?;
+
+    Ok(())
+}
+
+#[pyo3_asyncio::tokio::test]
+fn test_plain() {
+    assert!(SET_UP.load(Ordering::SeqCst));
+}
+
+#[pyo3_asyncio::tokio::test]
+fn test_result() -> Result<(), String> {
+    "42".parse::<i32>().map(drop).map_err(|e| e.to_string())
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_async_plain() {
+    tokio::time::sleep(Duration::from_millis(10)).await;
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_shared_setup() -> PyResult<()> {
+    let answer = Python::with_gil(|py| {
+        pyo3_asyncio::into_future_of::<i32>(py.import("shared")?.call_method0("answer")?)
+    })?
+    .await?;
+    assert_eq!(answer, 42);
+
+    Ok(())
+}
+
+#[pyo3_asyncio::tokio::test]
+fn test_test_result() {
+    use pyo3_asyncio::testing::TestResult;
+
+    assert!(().into_test_result().is_ok());
+    assert!(Ok::<(), String>(()).into_test_result().is_ok());
+
+    Python::with_gil(|py| {
+        // other errors become an Exception with their Debug representation
+        let err = Err::<(), _>("boom").into_test_result().unwrap_err();
+        assert!(err.is_instance::<pyo3::exceptions::PyException>(py));
+        assert_eq!(err.instance(py).str().unwrap().to_string(), "\"boom\"");
+
+        // but Python exceptions are kept as-is
+        let err = Err::<(), _>(pyo3::exceptions::PyValueError::new_err("boom"))
+            .into_test_result()
+            .unwrap_err();
+        assert!(err.is_instance::<pyo3::exceptions::PyValueError>(py));
+    });
+}
+
+pyo3_asyncio::tokio::test_main!(setup);
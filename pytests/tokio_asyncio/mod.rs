@@ -1,4 +1,12 @@
-use std::time::Duration;
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 
 use pyo3::{prelude::*, wrap_pyfunction};
 
@@ -40,8 +48,7 @@ async fn test_into_coroutine() -> PyResult<()> {
 
 #[pyo3_asyncio::tokio::test]
 async fn test_async_sleep() -> PyResult<()> {
-    let asyncio =
-        Python::with_gil(|py| py.import("asyncio").map(|asyncio| PyObject::from(asyncio)))?;
+    let asyncio = Python::with_gil(|py| py.import("asyncio").map(PyObject::from))?;
 
     tokio::time::sleep(Duration::from_secs(1)).await;
 
@@ -68,11 +75,283 @@ async fn test_other_awaitables() -> PyResult<()> {
     common::test_other_awaitables().await
 }
 
+#[pyo3_asyncio::tokio::test]
+async fn test_awaitable_kinds() -> PyResult<()> {
+    common::test_awaitable_kinds().await
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_eager_task_factory() -> PyResult<()> {
+    common::test_eager_task_factory().await
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_panic() -> PyResult<()> {
+    let coro = Python::with_gil(|py| {
+        pyo3_asyncio::tokio::into_coroutine(py, async {
+            panic!("oh no");
+        })
+    })?;
+
+    common::test_panic(coro).await
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_panic_error_converter() -> PyResult<()> {
+    let fut = Python::with_gil(|py| {
+        let coro = pyo3_asyncio::tokio::into_coroutine_with_error_converter(
+            py,
+            async {
+                panic!("oh no");
+            },
+            |_py, failure| {
+                pyo3::exceptions::PyValueError::new_err(failure.panic_message().unwrap())
+            },
+        )?;
+
+        pyo3_asyncio::into_future(coro.as_ref(py))
+    })?;
+
+    let err = fut.await.unwrap_err();
+    Python::with_gil(|py| {
+        assert!(err.is_instance::<pyo3::exceptions::PyValueError>(py));
+        assert_eq!(err.pvalue(py).to_string(), "oh no");
+    });
+
+    Ok(())
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_await_error() -> PyResult<()> {
+    common::test_await_error().await
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_stats() -> PyResult<()> {
+    let coro = Python::with_gil(|py| {
+        pyo3_asyncio::tokio::into_coroutine(py, async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Python::with_gil(|py| Ok(py.None()))
+        })
+    })?;
+
+    common::test_stats(coro, "TokioRuntime").await
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_conversion_observer() -> PyResult<()> {
+    common::test_conversion_observer(|py| {
+        pyo3_asyncio::tokio::into_coroutine(py, async {
+            Err(pyo3::exceptions::PyValueError::new_err("oh no"))
+        })
+    })
+    .await
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_cancel_pending_io() -> PyResult<()> {
+    common::test_cancel_pending_io().await
+}
+
+#[cfg(windows)]
+#[pyo3_asyncio::tokio::test]
+fn test_proactor_event_loop() -> PyResult<()> {
+    common::test_proactor_event_loop()
+}
+
+#[cfg(windows)]
+#[pyo3_asyncio::tokio::test]
+async fn test_proactor_conversions() -> PyResult<()> {
+    // the proactor runs on a blocking thread while the PyO3 Asyncio loop keeps running
+    pyo3_asyncio::tokio::spawn_blocking(
+        common::test_proactor_conversions::<pyo3_asyncio::tokio::TokioRuntime>,
+    )
+    .await
+    .unwrap()
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_destroyed_task() -> PyResult<()> {
+    common::test_destroyed_task().await
+}
+
+#[pyo3_asyncio::tokio::test]
+fn test_nested_with_runtime() -> PyResult<()> {
+    common::test_nested_with_runtime(|py| {
+        pyo3_asyncio::tokio::with_runtime_async(py, async { Ok(()) })
+    })
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_closed_event_loop() -> PyResult<()> {
+    common::test_closed_event_loop(|py, event_loop| {
+        pyo3_asyncio::tokio::into_coroutine_with_loop(py, event_loop, async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Python::with_gil(|py| Ok(py.None()))
+        })
+    })
+    .await
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_async_context_manager() -> PyResult<()> {
+    common::test_async_context_manager(pyo3_asyncio::tokio::into_async_context_manager).await
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_async_iter() -> PyResult<()> {
+    common::test_async_iter(pyo3_asyncio::tokio::into_async_iter).await
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_async_gen() -> PyResult<()> {
+    common::test_async_gen(pyo3_asyncio::tokio::into_async_gen).await
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_bridge_config() -> PyResult<()> {
+    common::test_bridge_config(
+        pyo3_asyncio::tokio::into_async_iter_with_config,
+        pyo3_asyncio::tokio::into_stream,
+    )
+    .await
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_call_async() -> PyResult<()> {
+    common::test_call_async().await
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_await_released() -> PyResult<()> {
+    common::test_await_released(pyo3_asyncio::tokio::into_coroutine_eager).await
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_task_names() -> PyResult<()> {
+    common::test_task_names().await
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_debug_mode() -> PyResult<()> {
+    common::test_debug_mode(|py, fut| pyo3_asyncio::tokio::into_coroutine_eager(py, fut)).await
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_debug_origin() -> PyResult<()> {
+    common::test_debug_origin::<pyo3_asyncio::tokio::TokioRuntime>().await
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_blocking_entry_points() -> PyResult<()> {
+    common::test_blocking_entry_points(|py, event_loop| {
+        pyo3_asyncio::tokio::run_until_complete_with_loop(py, event_loop, async { Ok(()) })
+    })
+    .await
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_blocking_in_plain_task() -> PyResult<()> {
+    fn run_other_loop() -> PyResult<()> {
+        Python::with_gil(|py| {
+            let other_loop = py.import("asyncio")?.call_method0("new_event_loop")?;
+            let result =
+                pyo3_asyncio::tokio::run_until_complete_with_loop(py, other_loop, async { Ok(()) });
+            other_loop.call_method0("close")?;
+
+            result
+        })
+    }
+
+    // a task that PyO3 Asyncio didn't spawn is still a task of the runtime
+    let err = tokio::spawn(async { run_other_loop() })
+        .await
+        .unwrap()
+        .unwrap_err();
+    assert!(Python::with_gil(
+        |py| err.is_instance::<pyo3::exceptions::PyRuntimeError>(py)
+    ));
+
+    // and so is a task of a runtime that the blocking work drives itself
+    let err = pyo3_asyncio::tokio::spawn_blocking(|| {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        rt.block_on(tokio::spawn(async { run_other_loop() }))
+            .unwrap()
+    })
+    .await
+    .unwrap()
+    .unwrap_err();
+    assert!(Python::with_gil(
+        |py| err.is_instance::<pyo3::exceptions::PyRuntimeError>(py)
+    ));
+
+    // but the blocking work may block
+    pyo3_asyncio::tokio::spawn_blocking(run_other_loop)
+        .await
+        .unwrap()
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_into_future_of() -> PyResult<()> {
+    common::test_into_future_of().await
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_budget() -> PyResult<()> {
+    common::test_budget(pyo3_asyncio::tokio::into_coroutine).await
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_yield_now() -> PyResult<()> {
+    common::test_yield_now().await
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_current_deadline() -> PyResult<()> {
+    common::test_current_deadline().await
+}
+
+#[cfg(feature = "async-channel")]
+#[pyo3_asyncio::tokio::test]
+async fn test_channel() -> PyResult<()> {
+    common::test_channel::<pyo3_asyncio::tokio::TokioRuntime>().await
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_scope() -> PyResult<()> {
+    common::test_scope().await
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_scope_failure_cancels_siblings() -> PyResult<()> {
+    common::test_scope_failure_cancels_siblings().await
+}
+
 #[pyo3_asyncio::tokio::test]
 fn test_init_twice() -> PyResult<()> {
     common::test_init_twice()
 }
 
+#[cfg(unix)]
+#[pyo3_asyncio::tokio::test]
+async fn test_add_reader() -> PyResult<()> {
+    common::test_add_reader().await
+}
+
+#[cfg(unix)]
+#[pyo3_asyncio::tokio::test]
+async fn test_add_reader_bad_fd() -> PyResult<()> {
+    common::test_add_reader_bad_fd().await
+}
+
+#[cfg(unix)]
+#[pyo3_asyncio::tokio::test]
+async fn test_add_reader_dropped() -> PyResult<()> {
+    common::test_add_reader_dropped().await
+}
+
 #[pyo3_asyncio::tokio::test]
 fn test_init_tokio_twice() -> PyResult<()> {
     // tokio has already been initialized in test main. call these functions to
@@ -82,3 +361,1054 @@ fn test_init_tokio_twice() -> PyResult<()> {
 
     Ok(())
 }
+
+#[cfg(unix)]
+#[pyo3_asyncio::tokio::test]
+async fn test_readable() -> PyResult<()> {
+    let (sock, fd) = Python::with_gil(|py| -> PyResult<_> {
+        let pair = py.import("socket")?.call_method0("socketpair")?;
+        let (tx, rx): (&PyAny, &PyAny) = pair.extract()?;
+
+        tx.call_method1("send", (pyo3::types::PyBytes::new(py, b"ping"),))?;
+        let fd: i32 = rx.call_method0("fileno")?.extract()?;
+
+        Ok((PyObject::from(pair), fd))
+    })?;
+
+    pyo3_asyncio::tokio::readable(fd).await?;
+    pyo3_asyncio::tokio::writable(fd).await?;
+
+    // keep the sockets open until we're done with the fd
+    drop(sock);
+
+    Ok(())
+}
+
+#[cfg(feature = "anyhow")]
+#[pyo3_asyncio::tokio::test]
+async fn test_anyhow_error_chain() -> PyResult<()> {
+    use anyhow::Context;
+
+    let fut = Python::with_gil(|py| {
+        let coro = pyo3_asyncio::tokio::into_coroutine(
+            py,
+            pyo3_asyncio::anyhow::into_py_result(async {
+                Err::<(), _>(anyhow::anyhow!("root cause")).context("outer")
+            }),
+        )?;
+
+        pyo3_asyncio::into_future(coro.as_ref(py))
+    })?;
+
+    let err = fut.await.unwrap_err();
+    Python::with_gil(|py| -> PyResult<()> {
+        assert!(err.is_instance::<pyo3::exceptions::PyRuntimeError>(py));
+        assert_eq!(err.pvalue(py).to_string(), "outer: root cause");
+
+        let cause = err.instance(py).getattr("__cause__")?;
+        assert_eq!(cause.str()?.to_string(), "root cause");
+
+        Ok(())
+    })?;
+
+    Ok(())
+}
+
+const SCOPED_MOD: &str = r#"
+import asyncio
+
+async def cancel_scoped(make_coro):
+    task = asyncio.ensure_future(make_coro())
+    await asyncio.sleep(0.1)
+    task.cancel()
+
+    try:
+        await task
+    except asyncio.CancelledError:
+        return True
+
+    return False
+"#;
+
+#[pyclass]
+struct ScopedSleep {
+    dropped: Arc<AtomicBool>,
+}
+
+#[pymethods]
+impl ScopedSleep {
+    #[call]
+    fn __call__(&self, py: Python) -> PyResult<PyObject> {
+        let guard = SetOnDrop(self.dropped.clone());
+
+        pyo3_asyncio::tokio::into_coroutine_scoped(py, async move {
+            let _guard = guard;
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            Python::with_gil(|py| Ok(py.None()))
+        })
+    }
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_into_coroutine_scoped() -> PyResult<()> {
+    let dropped = Arc::new(AtomicBool::new(false));
+
+    let fut = Python::with_gil(|py| {
+        let test_mod = PyModule::from_code(
+            py,
+            SCOPED_MOD,
+            "test_into_coroutine_scoped/test_mod.py",
+            "test_mod",
+        )?;
+
+        pyo3_asyncio::into_future(test_mod.call_method1(
+            "cancel_scoped",
+            (ScopedSleep {
+                dropped: dropped.clone(),
+            },),
+        )?)
+    })?;
+
+    let was_cancelled = fut.await?;
+    assert!(Python::with_gil(|py| was_cancelled.extract::<bool>(py))?);
+
+    // the rust future must already be gone by the time the coroutine finishes
+    assert!(dropped.load(Ordering::SeqCst));
+
+    Ok(())
+}
+
+#[pyclass]
+struct ShieldedCommit {
+    committed: Arc<AtomicBool>,
+}
+
+#[pymethods]
+impl ShieldedCommit {
+    #[call]
+    fn __call__(&self, py: Python) -> PyResult<PyObject> {
+        let committed = self.committed.clone();
+
+        pyo3_asyncio::tokio::into_coroutine_scoped(py, async move {
+            pyo3_asyncio::tokio::shield(async move {
+                tokio::time::sleep(Duration::from_millis(300)).await;
+                committed.store(true, Ordering::SeqCst);
+                Ok(())
+            })
+            .await?;
+
+            Python::with_gil(|py| Ok(py.None()))
+        })
+    }
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_shield() -> PyResult<()> {
+    let committed = Arc::new(AtomicBool::new(false));
+
+    let fut = Python::with_gil(|py| {
+        let test_mod = PyModule::from_code(py, SCOPED_MOD, "test_shield/test_mod.py", "test_mod")?;
+
+        pyo3_asyncio::into_future(test_mod.call_method1(
+            "cancel_scoped",
+            (ShieldedCommit {
+                committed: committed.clone(),
+            },),
+        )?)
+    })?;
+
+    // the awaiting coroutine is cancelled right away
+    let was_cancelled = fut.await?;
+    assert!(Python::with_gil(|py| was_cancelled.extract::<bool>(py))?);
+    assert!(!committed.load(Ordering::SeqCst));
+
+    // but the shielded future still runs to completion
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert!(committed.load(Ordering::SeqCst));
+
+    Ok(())
+}
+
+const TASK_GROUP_MOD: &str = r#"
+import asyncio
+
+async def fail_group(spawn):
+    try:
+        async with asyncio.TaskGroup() as tg:
+            spawn(tg)
+
+            async def fail():
+                await asyncio.sleep(0.1)
+                raise ValueError("boom")
+
+            tg.create_task(fail())
+    except BaseException as e:
+        return e
+"#;
+
+struct SetOnDrop(Arc<AtomicBool>);
+
+impl Drop for SetOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+#[pyclass]
+struct SleepInGroup {
+    dropped: Arc<AtomicBool>,
+}
+
+#[pymethods]
+impl SleepInGroup {
+    #[call]
+    fn __call__(&self, py: Python, task_group: &PyAny) -> PyResult<PyObject> {
+        let guard = SetOnDrop(self.dropped.clone());
+
+        pyo3_asyncio::tokio::create_task_in_group(py, task_group, async move {
+            let _guard = guard;
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            Python::with_gil(|py| Ok(py.None()))
+        })
+    }
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_create_task_in_group() -> PyResult<()> {
+    let has_task_group = Python::with_gil(|py| py.import("asyncio")?.hasattr("TaskGroup"))?;
+    if !has_task_group {
+        return Err(pyo3_asyncio::testing::skip(
+            "TaskGroup was added in Python 3.11",
+        ));
+    }
+
+    let dropped = Arc::new(AtomicBool::new(false));
+
+    let fut = Python::with_gil(|py| {
+        let spawn = SleepInGroup {
+            dropped: dropped.clone(),
+        };
+
+        let test_mod = PyModule::from_code(
+            py,
+            TASK_GROUP_MOD,
+            "test_create_task_in_group/test_mod.py",
+            "test_mod",
+        )?;
+
+        pyo3_asyncio::into_future(test_mod.call_method1("fail_group", (spawn,))?)
+    })?;
+
+    let err = fut.await?;
+    Python::with_gil(|py| -> PyResult<()> {
+        // the failing sibling should be reported in an ExceptionGroup
+        assert!(err.as_ref(py).str()?.to_string().contains("sub-exception"));
+        Ok(())
+    })?;
+
+    // the group must not exit before the rust future has been dropped
+    assert!(dropped.load(Ordering::SeqCst));
+
+    Ok(())
+}
+
+#[cfg(feature = "tracing")]
+const TRACING_MOD: &str = r#"
+import contextvars
+
+request_id = contextvars.ContextVar("request_id")
+
+async def with_request_id(make_coro):
+    request_id.set("abc")
+    return await make_coro()
+"#;
+
+#[cfg(feature = "tracing")]
+#[pyclass]
+struct CurrentSpanName {}
+
+#[cfg(feature = "tracing")]
+#[pymethods]
+impl CurrentSpanName {
+    #[call]
+    fn __call__(&self, py: Python) -> PyResult<PyObject> {
+        pyo3_asyncio::tokio::into_coroutine(py, async move {
+            tokio::task::yield_now().await;
+
+            let name = tracing::Span::current().metadata().map(|meta| meta.name());
+            Python::with_gil(|py| Ok(name.into_py(py)))
+        })
+    }
+}
+
+#[cfg(feature = "tracing")]
+#[pyo3_asyncio::tokio::test]
+async fn test_tracing_span_hook() -> PyResult<()> {
+    use tracing_subscriber::layer::SubscriberExt;
+
+    static REQUEST_ID: once_cell::sync::OnceCell<PyObject> = once_cell::sync::OnceCell::new();
+
+    tracing::subscriber::set_global_default(
+        tracing_subscriber::registry().with(tracing_subscriber::filter::LevelFilter::TRACE),
+    )
+    .unwrap();
+
+    pyo3_asyncio::tracing::set_span_hook(|py| {
+        let var = REQUEST_ID.get()?.as_ref(py);
+        let request_id = pyo3_asyncio::tracing::get_context_var(py, var).ok()??;
+
+        Some(tracing::info_span!("request", id = %request_id.as_ref(py)))
+    });
+
+    let fut = Python::with_gil(|py| {
+        let test_mod = PyModule::from_code(
+            py,
+            TRACING_MOD,
+            "test_tracing_span_hook/test_mod.py",
+            "test_mod",
+        )?;
+        REQUEST_ID.get_or_init(|| test_mod.getattr("request_id").unwrap().into());
+
+        pyo3_asyncio::into_future(test_mod.call_method1("with_request_id", (CurrentSpanName {},))?)
+    })?;
+
+    let name = fut.await?;
+    assert_eq!(
+        Python::with_gil(|py| name.extract::<Option<String>>(py))?,
+        Some("request".to_string())
+    );
+
+    Ok(())
+}
+
+#[cfg(any(feature = "log", feature = "tracing"))]
+const LOGGING_MOD: &str = r#"
+import asyncio
+
+def report(handler):
+    loop = asyncio.new_event_loop()
+    loop.set_exception_handler(handler)
+
+    try:
+        raise ValueError("oh no")
+    except ValueError as e:
+        exc = e
+
+    try:
+        loop.call_exception_handler({"message": "callback failed", "exception": exc})
+        loop.call_exception_handler({"message": "no exception"})
+    finally:
+        loop.close()
+"#;
+
+#[cfg(any(feature = "log", feature = "tracing"))]
+#[pyo3_asyncio::tokio::test]
+fn test_log_exception_handler() -> PyResult<()> {
+    Python::with_gil(|py| {
+        let test_mod = PyModule::from_code(
+            py,
+            LOGGING_MOD,
+            "test_log_exception_handler/test_mod.py",
+            "test_mod",
+        )?;
+
+        test_mod.call_method1("report", (pyo3_asyncio::logging::exception_handler(py)?,))?;
+
+        pyo3_asyncio::logging::set_exception_handler(py)?;
+        assert!(!pyo3_asyncio::get_event_loop(py)
+            .call_method0("get_exception_handler")?
+            .is_none());
+
+        Ok(())
+    })
+}
+
+const EAGER_MOD: &str = r#"
+async def check_eager(make_ready, make_pending):
+    ready = make_ready()
+    pending = make_pending()
+
+    assert ready.done()
+    assert not pending.done()
+
+    return await ready + await pending
+"#;
+
+#[pyclass]
+struct MakeCoroutine {
+    ready: bool,
+}
+
+#[pymethods]
+impl MakeCoroutine {
+    #[call]
+    fn __call__(&self, py: Python) -> PyResult<PyObject> {
+        let ready = self.ready;
+
+        pyo3_asyncio::tokio::into_coroutine_eager(py, async move {
+            if !ready {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+
+            Python::with_gil(|py| Ok(1.into_py(py)))
+        })
+    }
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_eager_completion() -> PyResult<()> {
+    let fut = Python::with_gil(|py| {
+        let test_mod = PyModule::from_code(
+            py,
+            EAGER_MOD,
+            "test_eager_completion/test_mod.py",
+            "test_mod",
+        )?;
+
+        pyo3_asyncio::into_future(test_mod.call_method1(
+            "check_eager",
+            (
+                MakeCoroutine { ready: true },
+                MakeCoroutine { ready: false },
+            ),
+        )?)
+    })?;
+
+    let total = fut.await?;
+    assert_eq!(Python::with_gil(|py| total.extract::<i32>(py))?, 2);
+
+    Ok(())
+}
+
+const DEFERRED_MOD: &str = r#"
+import asyncio
+
+def run_in_new_loop(coro):
+    return asyncio.run(coro)
+"#;
+
+#[pyo3_asyncio::tokio::test]
+async fn test_into_coroutine_deferred() -> PyResult<()> {
+    // created off the event loop thread and awaited on a loop PyO3 Asyncio knows nothing about
+    let result = tokio::task::spawn_blocking(|| {
+        Python::with_gil(|py| -> PyResult<i32> {
+            let test_mod = PyModule::from_code(
+                py,
+                DEFERRED_MOD,
+                "test_into_coroutine_deferred/test_mod.py",
+                "test_mod",
+            )?;
+
+            let coro = pyo3_asyncio::tokio::into_coroutine_deferred(py, async {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                Python::with_gil(|py| Ok(42.into_py(py)))
+            })?;
+
+            test_mod.call_method1("run_in_new_loop", (coro,))?.extract()
+        })
+    })
+    .await
+    .unwrap()?;
+
+    assert_eq!(result, 42);
+
+    Ok(())
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_with_loop() -> PyResult<()> {
+    // drive a second event loop on a blocking thread while the PyO3 Asyncio loop keeps running
+    pyo3_asyncio::tokio::spawn_blocking(|| {
+        Python::with_gil(|py| -> PyResult<()> {
+            let asyncio = py.import("asyncio")?;
+            let other_loop = asyncio.call_method0("new_event_loop")?;
+
+            let sleep = PyObject::from(asyncio.call_method1("sleep", (0.01, 1))?);
+            let other_loop_ref = PyObject::from(other_loop);
+
+            pyo3_asyncio::tokio::run_until_complete_with_loop(py, other_loop, async move {
+                let fut = Python::with_gil(|py| {
+                    pyo3_asyncio::into_future_with_loop(other_loop_ref.as_ref(py), sleep.as_ref(py))
+                })?;
+                let val = fut.await?;
+                assert_eq!(Python::with_gil(|py| val.extract::<i32>(py))?, 1);
+
+                let fut = Python::with_gil(|py| {
+                    let other_loop = other_loop_ref.as_ref(py);
+                    let scoped = pyo3_asyncio::tokio::into_coroutine_scoped_with_loop(
+                        py,
+                        other_loop,
+                        async { Ok(Python::with_gil(|py| 2.into_py(py))) },
+                    )?;
+
+                    pyo3_asyncio::into_future_of_with_loop::<i32>(other_loop, scoped.as_ref(py))
+                })?;
+                assert_eq!(fut.await?, 2);
+
+                let fut = Python::with_gil(|py| {
+                    let other_loop = other_loop_ref.as_ref(py);
+                    let named = pyo3_asyncio::tokio::into_coroutine_named_with_loop(
+                        py,
+                        other_loop,
+                        "with_loop",
+                        async { Ok(Python::with_gil(|py| 3.into_py(py))) },
+                    )?;
+
+                    pyo3_asyncio::into_future_named_with_loop(
+                        other_loop,
+                        named.as_ref(py),
+                        "with_loop",
+                    )
+                })?;
+                let val = fut.await?;
+                assert_eq!(Python::with_gil(|py| val.extract::<i32>(py))?, 3);
+
+                Ok(())
+            })?;
+
+            other_loop.call_method0("close")?;
+
+            Ok(())
+        })
+    })
+    .await
+    .unwrap()
+}
+
+fn thread_name() -> String {
+    std::thread::current()
+        .name()
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_bridge() -> PyResult<()> {
+    use pyo3_asyncio::tokio::Bridge;
+
+    static RUNTIME: once_cell::sync::OnceCell<tokio::runtime::Runtime> =
+        once_cell::sync::OnceCell::new();
+
+    let runtime = RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("bridge-worker")
+            .enable_all()
+            .build()
+            .unwrap()
+    });
+    let bridge = Bridge::new(runtime.handle().clone());
+
+    let fut = Python::with_gil(|py| {
+        let inner_bridge = bridge.clone();
+        let coro = bridge.into_coroutine(py, async move {
+            // conversions made with the module functions inside a bridged task use the global
+            // runtime, the bridge has to be passed along explicitly
+            let (global, bridged) = Python::with_gil(|py| {
+                let global = pyo3_asyncio::tokio::into_coroutine(py, async {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    Python::with_gil(|py| Ok(thread_name().into_py(py)))
+                })?;
+                let bridged = inner_bridge.into_coroutine(py, async {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    Python::with_gil(|py| Ok(thread_name().into_py(py)))
+                })?;
+
+                PyResult::Ok((
+                    pyo3_asyncio::into_future(global.as_ref(py))?,
+                    pyo3_asyncio::into_future(bridged.as_ref(py))?,
+                ))
+            })?;
+            let (global, bridged) = (global.await?, bridged.await?);
+
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            let outer = thread_name();
+
+            Python::with_gil(|py| {
+                Ok((
+                    outer,
+                    global.extract::<String>(py)?,
+                    bridged.extract::<String>(py)?,
+                )
+                    .into_py(py))
+            })
+        })?;
+
+        pyo3_asyncio::into_future(coro.as_ref(py))
+    })?;
+
+    let names = fut.await?;
+    let (outer, global, bridged) =
+        Python::with_gil(|py| names.extract::<(String, String, String)>(py))?;
+    assert_eq!(outer, "bridge-worker");
+    assert_ne!(global, "bridge-worker");
+    assert_eq!(bridged, "bridge-worker");
+
+    // a bridge can deliver its results to an event loop of its own
+    let name = Python::with_gil(|py| {
+        let other_loop = py.import("asyncio")?.call_method0("new_event_loop")?;
+        let bridge = Bridge::with_loop(runtime.handle().clone(), other_loop);
+        assert!(std::ptr::eq(bridge.event_loop(py), other_loop));
+
+        let coro = bridge.into_coroutine(py, async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Python::with_gil(|py| Ok(thread_name().into_py(py)))
+        })?;
+        let name = other_loop.call_method1("run_until_complete", (coro,))?;
+        other_loop.call_method0("close")?;
+
+        name.extract::<String>()
+    })?;
+    assert_eq!(name, "bridge-worker");
+
+    // the global runtime is still used outside of the bridge
+    assert_ne!(thread_name(), "bridge-worker");
+
+    Ok(())
+}
+
+#[cfg(feature = "trio")]
+const TRIO_MOD: &str = r#"
+import trio
+
+async def value_after(delay, value):
+    await trio.sleep(delay)
+    return value
+
+async def fail_after(delay):
+    await trio.sleep(delay)
+    raise ValueError("trio failure")
+
+async def main(sleep_then, round_trip):
+    assert await sleep_then(42) == 42
+    assert await round_trip(value_after(0.01, 7)) == 7
+
+    try:
+        await round_trip(fail_after(0.01))
+    except ValueError as e:
+        assert str(e) == "trio failure"
+    else:
+        raise AssertionError("expected ValueError")
+
+    return "done"
+
+def run(sleep_then, round_trip):
+    return trio.run(main, sleep_then, round_trip)
+"#;
+
+#[cfg(feature = "trio")]
+#[pyclass]
+struct SleepThen {}
+
+#[cfg(feature = "trio")]
+#[pymethods]
+impl SleepThen {
+    #[call]
+    fn __call__(&self, py: Python, value: i32) -> PyResult<PyObject> {
+        pyo3_asyncio::tokio::into_trio_coroutine(py, async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Python::with_gil(|py| Ok(value.into_py(py)))
+        })
+    }
+}
+
+#[cfg(feature = "trio")]
+#[pyclass]
+struct RoundTrip {}
+
+#[cfg(feature = "trio")]
+#[pymethods]
+impl RoundTrip {
+    #[call]
+    fn __call__(&self, py: Python, awaitable: &PyAny) -> PyResult<PyObject> {
+        // called from a Trio task, so the awaitable runs in the same Trio run
+        let fut = pyo3_asyncio::trio::into_future(awaitable)?;
+
+        pyo3_asyncio::tokio::into_trio_coroutine(py, fut)
+    }
+}
+
+#[cfg(feature = "trio")]
+#[pyo3_asyncio::tokio::test]
+async fn test_trio() -> PyResult<()> {
+    let installed = Python::with_gil(|py| py.import("trio").is_ok());
+    if !installed {
+        return Err(pyo3_asyncio::testing::skip("trio isn't installed"));
+    }
+
+    let result = tokio::task::spawn_blocking(|| {
+        Python::with_gil(|py| -> PyResult<String> {
+            let test_mod = PyModule::from_code(py, TRIO_MOD, "test_trio/test_mod.py", "test_mod")?;
+
+            test_mod
+                .call_method1("run", (SleepThen {}, RoundTrip {}))?
+                .extract()
+        })
+    })
+    .await
+    .unwrap()?;
+
+    assert_eq!(result, "done");
+
+    Ok(())
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_run_blocking_py() -> PyResult<()> {
+    let ticker = tokio::spawn(async {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        Instant::now()
+    });
+
+    let finished = pyo3_asyncio::tokio::run_blocking_py(|py| {
+        py.import("time")?.call_method1("sleep", (0.5,))?;
+        Ok(Instant::now())
+    })
+    .await?;
+
+    // the runtime kept running other tasks while Python was blocking
+    assert!(ticker.await.unwrap() < finished);
+
+    let panicked =
+        pyo3_asyncio::tokio::run_blocking_py(|_py| -> PyResult<()> { panic!("blocking panic") })
+            .await;
+
+    Python::with_gil(|py| {
+        assert!(panicked
+            .unwrap_err()
+            .is_instance::<pyo3::exceptions::PyRuntimeError>(py));
+    });
+
+    Ok(())
+}
+
+const BLOCKING_EXECUTOR_MOD: &str = r#"
+import asyncio
+
+async def run_in(executor, default_executor, thread_name):
+    # asyncio only accepts ThreadPoolExecutors as the default executor
+    other_loop = asyncio.new_event_loop()
+    other_loop.set_default_executor(default_executor)
+    other_loop.close()
+
+    loop = asyncio.get_running_loop()
+    names = await asyncio.gather(*[loop.run_in_executor(executor, thread_name) for _ in range(4)])
+
+    try:
+        await loop.run_in_executor(executor, int, "not a number")
+    except ValueError:
+        names.append("failed")
+
+    executor.shutdown(wait=True)
+
+    try:
+        executor.submit(thread_name)
+    except RuntimeError:
+        names.append("shut down")
+
+    return names
+"#;
+
+#[pyfunction]
+fn rust_thread_name() -> Option<String> {
+    std::thread::current().name().map(|name| name.to_string())
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_blocking_executor() -> PyResult<()> {
+    let fut = Python::with_gil(|py| {
+        let test_mod = PyModule::from_code(
+            py,
+            BLOCKING_EXECUTOR_MOD,
+            "test_blocking_executor/test_mod.py",
+            "test_mod",
+        )?;
+
+        pyo3_asyncio::into_future(test_mod.call_method1(
+            "run_in",
+            (
+                pyo3_asyncio::tokio::blocking_executor(py)?,
+                pyo3_asyncio::tokio::blocking_executor(py)?,
+                wrap_pyfunction!(rust_thread_name, test_mod)?,
+            ),
+        )?)
+    })?;
+
+    let names = fut.await?;
+
+    // the work ran on the same threads as Rust's blocking work
+    let blocking_name = tokio::task::spawn_blocking(rust_thread_name)
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        Python::with_gil(|py| names.extract::<Vec<String>>(py))?,
+        vec![
+            blocking_name.as_str(),
+            blocking_name.as_str(),
+            blocking_name.as_str(),
+            blocking_name.as_str(),
+            "failed",
+            "shut down"
+        ]
+    );
+
+    Ok(())
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn test_into_coroutine_local() -> PyResult<()> {
+    let outside = Python::with_gil(|py| {
+        pyo3_asyncio::tokio::into_coroutine_local(py, async {
+            Python::with_gil(|py| Ok(py.None()))
+        })
+        .is_err()
+    });
+    assert!(
+        outside,
+        "into_coroutine_local was allowed outside of a LocalSet"
+    );
+
+    // a LocalSet can't be driven by the test's task, so it gets a thread of its own
+    tokio::task::spawn_blocking(|| {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let local = tokio::task::LocalSet::new();
+
+        local.block_on(&rt, async {
+            let answer = Rc::new(RefCell::new(0));
+            let answer_tx = answer.clone();
+
+            let coro = Python::with_gil(|py| {
+                pyo3_asyncio::tokio::into_coroutine_local(py, async move {
+                    tokio::task::yield_now().await;
+                    *answer_tx.borrow_mut() = 42;
+
+                    // local tasks are tasks of the runtime like any other
+                    let refused = Python::with_gil(|py| -> PyResult<bool> {
+                        let other_loop = py.import("asyncio")?.call_method0("new_event_loop")?;
+                        let result = pyo3_asyncio::tokio::run_until_complete_with_loop(
+                            py,
+                            other_loop,
+                            async { Ok(()) },
+                        );
+                        other_loop.call_method0("close")?;
+
+                        Ok(result.is_err())
+                    })?;
+                    assert!(refused);
+
+                    Ok(Python::with_gil(|py| (*answer_tx.borrow()).into_py(py)))
+                })
+            })?;
+
+            let value = Python::with_gil(|py| pyo3_asyncio::into_future(coro.as_ref(py)))?.await?;
+
+            assert_eq!(Python::with_gil(|py| value.extract::<i32>(py))?, 42);
+            assert_eq!(*answer.borrow(), 42);
+
+            Ok(())
+        })
+    })
+    .await
+    .unwrap()
+}
+
+#[cfg(feature = "tokio-util")]
+const TOKEN_MOD: &str = r#"
+import asyncio
+
+async def cancel_soon(make_coro):
+    task = asyncio.ensure_future(make_coro())
+    await asyncio.sleep(0.05)
+    task.cancel()
+
+    try:
+        await task
+    except asyncio.CancelledError:
+        return True
+
+    return False
+
+async def await_cancelled(coro):
+    try:
+        await coro
+    except asyncio.CancelledError:
+        return True
+
+    return False
+
+async def cancel_self():
+    asyncio.current_task().cancel()
+    await asyncio.sleep(10)
+"#;
+
+#[cfg(feature = "tokio-util")]
+fn sleep_until_cancelled(
+    py: Python,
+    token: tokio_util::sync::CancellationToken,
+) -> PyResult<PyObject> {
+    pyo3_asyncio::tokio::into_coroutine_with_token(py, token.clone(), async move {
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_secs(10)) => (),
+            _ = token.cancelled() => (),
+        }
+
+        Python::with_gil(|py| Ok(py.None()))
+    })
+}
+
+#[cfg(feature = "tokio-util")]
+#[pyclass]
+struct TokenSleep {
+    token: tokio_util::sync::CancellationToken,
+}
+
+#[cfg(feature = "tokio-util")]
+#[pymethods]
+impl TokenSleep {
+    #[call]
+    fn __call__(&self, py: Python) -> PyResult<PyObject> {
+        sleep_until_cancelled(py, self.token.clone())
+    }
+}
+
+#[cfg(feature = "tokio-util")]
+#[pyo3_asyncio::tokio::test]
+async fn test_cancellation_token() -> PyResult<()> {
+    use tokio_util::sync::CancellationToken;
+
+    let test_mod: PyObject = Python::with_gil(|py| -> PyResult<_> {
+        Ok(PyModule::from_code(
+            py,
+            TOKEN_MOD,
+            "test_cancellation_token/test_mod.py",
+            "test_mod",
+        )?
+        .into())
+    })?;
+
+    // cancelling the Python task trips the token
+    let token = CancellationToken::new();
+    let was_cancelled = Python::with_gil(|py| {
+        pyo3_asyncio::into_future(
+            test_mod
+                .call_method1(
+                    py,
+                    "cancel_soon",
+                    (TokenSleep {
+                        token: token.clone(),
+                    },),
+                )?
+                .as_ref(py),
+        )
+    })?
+    .await?;
+    assert!(Python::with_gil(|py| was_cancelled.extract::<bool>(py))?);
+    assert!(token.is_cancelled());
+
+    // cancelling the token cancels the Python task
+    let token = CancellationToken::new();
+    let was_cancelled = Python::with_gil(|py| {
+        let coro = sleep_until_cancelled(py, token.clone())?;
+        pyo3_asyncio::into_future(
+            test_mod
+                .call_method1(py, "await_cancelled", (coro,))?
+                .as_ref(py),
+        )
+    })?;
+    let trigger = token.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        trigger.cancel();
+    });
+    let was_cancelled = was_cancelled.await?;
+    assert!(Python::with_gil(|py| was_cancelled.extract::<bool>(py))?);
+
+    // cancelling the token cancels the task that runs a Python awaitable
+    let token = CancellationToken::new();
+    let start = Instant::now();
+    let sleep = Python::with_gil(|py| {
+        pyo3_asyncio::tokio::into_future_with_token(
+            py.import("asyncio")?.call_method1("sleep", (10,))?,
+            token.clone(),
+        )
+    })?;
+    let trigger = token.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        trigger.cancel();
+    });
+    let err = sleep.await.unwrap_err();
+    Python::with_gil(|py| {
+        let cancelled = py.import("asyncio")?.getattr("CancelledError")?;
+        assert!(err.matches(py, cancelled));
+
+        PyResult::Ok(())
+    })?;
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    // and a Python awaitable that is cancelled from Python trips the token
+    let token = CancellationToken::new();
+    let cancel_self = Python::with_gil(|py| {
+        pyo3_asyncio::tokio::into_future_with_token(
+            test_mod.call_method0(py, "cancel_self")?.as_ref(py),
+            token.clone(),
+        )
+    })?;
+    assert!(cancel_self.await.is_err());
+    assert!(token.is_cancelled());
+
+    Ok(())
+}
+
+#[pyo3_asyncio::tokio::test(start_paused = true)]
+async fn test_start_paused() -> PyResult<()> {
+    let start = Instant::now();
+    tokio::time::sleep(Duration::from_secs(3600)).await;
+    assert!(start.elapsed() < Duration::from_secs(60));
+
+    // conversions bridged onto the test's runtime, so they see its paused clock too
+    let slept = Python::with_gil(|py| {
+        let coro = pyo3_asyncio::tokio::Bridge::new(tokio::runtime::Handle::current())
+            .into_coroutine(py, async {
+                let start = tokio::time::Instant::now();
+                tokio::time::sleep(Duration::from_secs(3600)).await;
+                Ok(Python::with_gil(|py| start.elapsed().as_secs().into_py(py)))
+            })?;
+
+        pyo3_asyncio::into_future(coro.as_ref(py))
+    })?
+    .await?;
+    assert!(Python::with_gil(|py| slept.extract::<u64>(py))? >= 3600);
+    assert!(start.elapsed() < Duration::from_secs(60));
+
+    Ok(())
+}
+
+#[pyo3_asyncio::tokio::test(flavor = "current_thread")]
+async fn test_current_thread_flavor() -> PyResult<()> {
+    let test_thread = std::thread::current().id();
+
+    // the bridged conversion is spawned onto the test's runtime, which only runs on the test's thread
+    let thread = Python::with_gil(|py| {
+        let coro = pyo3_asyncio::tokio::Bridge::new(tokio::runtime::Handle::current())
+            .into_coroutine(py, async {
+                tokio::task::yield_now().await;
+                let thread = format!("{:?}", std::thread::current().id());
+                Ok(Python::with_gil(|py| thread.into_py(py)))
+            })?;
+
+        pyo3_asyncio::into_future(coro.as_ref(py))
+    })?
+    .await?;
+    assert_eq!(
+        Python::with_gil(|py| thread.extract::<String>(py))?,
+        format!("{:?}", test_thread)
+    );
+
+    Ok(())
+}
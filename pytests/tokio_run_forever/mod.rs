@@ -1,6 +1,6 @@
 use std::time::Duration;
 
-use pyo3::prelude::*;
+use pyo3::{prelude::*, AsPyPointer};
 
 fn dump_err(py: Python<'_>) -> impl FnOnce(PyErr) + '_ {
     move |e| {
@@ -13,7 +13,7 @@ fn dump_err(py: Python<'_>) -> impl FnOnce(PyErr) + '_ {
 pub(super) fn test_main() {
     Python::with_gil(|py| {
         pyo3_asyncio::with_runtime(py, || {
-            pyo3_asyncio::tokio::get_runtime().spawn(async move {
+            pyo3_asyncio::tokio::get_handle().spawn(async move {
                 tokio::time::sleep(Duration::from_secs(1)).await;
 
                 Python::with_gil(|py| {
@@ -32,9 +32,222 @@ pub(super) fn test_main() {
             pyo3_asyncio::run_forever(py)?;
 
             println!("test test_run_forever ... ok");
+
+            let stop = pyo3_asyncio::StopHandle::new(py);
+            let trigger = stop.clone();
+
+            pyo3_asyncio::tokio::get_handle().spawn(async move {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                trigger.stop().unwrap();
+            });
+
+            pyo3_asyncio::run_forever_with_stop_handle(py, &stop)?;
+            assert!(stop.is_stopped());
+
+            // triggering the handle again after the event loop has stopped is harmless
+            stop.stop()?;
+
+            println!("test test_run_forever_with_stop_handle ... ok");
             Ok(())
         })
         .map_err(dump_err(py))
         .unwrap();
+
+        // the event loop was closed by with_runtime, closing it again is a no-op
+        pyo3_asyncio::try_close(py).map_err(dump_err(py)).unwrap();
+        println!("test test_close_twice ... ok");
+
+        // a closed event loop is replaced by a new one
+        pyo3_asyncio::tokio::with_runtime_async(py, async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+
+            let sleep = Python::with_gil(|py| {
+                pyo3_asyncio::into_future(py.import("asyncio")?.call_method1("sleep", (0.01,))?)
+            })?;
+            sleep.await?;
+
+            Ok(())
+        })
+        .map_err(dump_err(py))
+        .unwrap();
+        println!("test test_with_runtime_async_after_close ... ok");
+
+        test_runner(py).map_err(dump_err(py)).unwrap();
+        println!("test test_runner ... ok");
+
+        test_refresh_event_loop(py).map_err(dump_err(py)).unwrap();
+        println!("test test_refresh_event_loop ... ok");
+
+        #[cfg(windows)]
+        {
+            test_proactor_shutdown(py).map_err(dump_err(py)).unwrap();
+            println!("test test_proactor_shutdown ... ok");
+        }
     })
 }
+
+const RUNNER_MOD: &str = r#"
+async def get_var(var):
+    return var.get(None)
+"#;
+
+fn test_runner(py: Python) -> PyResult<()> {
+    let test_mod: PyObject =
+        PyModule::from_code(py, RUNNER_MOD, "test_runner/test_mod.py", "test_mod")?.into();
+    let var: PyObject = py
+        .import("contextvars")?
+        .call_method1("ContextVar", ("pyo3_asyncio_runner",))?
+        .into();
+
+    let mut runner = pyo3_asyncio::tokio::PyRunner::new(py)?;
+    runner
+        .context(py)
+        .call_method1("run", (var.getattr(py, "set")?, 42))?;
+
+    // the runner's context is shared by every run
+    for _ in 0..2 {
+        let test_mod = test_mod.clone();
+        let var = var.clone();
+
+        let value = runner.run(py, async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+
+            Python::with_gil(|py| {
+                pyo3_asyncio::into_future_of::<Option<i32>>(
+                    test_mod.call_method1(py, "get_var", (var,))?.as_ref(py),
+                )
+            })?
+            .await
+        })?;
+        assert_eq!(value, Some(42));
+    }
+
+    // but the variable isn't set outside of it
+    assert!(var.call_method1(py, "get", (py.None(),))?.is_none(py));
+
+    runner.close(py)?;
+    assert!(pyo3_asyncio::get_event_loop(py)
+        .call_method0("is_closed")?
+        .is_true()?);
+    assert!(runner.run(py, async { Ok(()) }).is_err());
+
+    Ok(())
+}
+
+fn test_refresh_event_loop(py: Python) -> PyResult<()> {
+    // the runner closed the event loop, so conversions can't be scheduled on it anymore
+    assert!(pyo3_asyncio::get_event_loop(py)
+        .call_method0("is_closed")?
+        .is_true()?);
+    let err =
+        pyo3_asyncio::tokio::into_coroutine(py, async { Ok(Python::with_gil(|py| py.None())) })
+            .unwrap_err();
+    assert!(err.is_instance::<pyo3_asyncio::EventLoopClosedError>(py));
+
+    // i.e. a test runner that creates a new event loop for each test
+    let asyncio = py.import("asyncio")?;
+    let new_loop = asyncio.call_method0("new_event_loop")?;
+    asyncio.call_method1("set_event_loop", (new_loop,))?;
+
+    let event_loop = pyo3_asyncio::refresh_event_loop(py)?;
+    assert_eq!(
+        pyo3_asyncio::get_event_loop(py).as_ptr(),
+        event_loop.as_ptr()
+    );
+    assert_eq!(event_loop.as_ptr(), new_loop.as_ptr());
+
+    // refreshing again replaces the default executor and shuts the previous one down
+    let executor = event_loop.getattr("_default_executor")?;
+    pyo3_asyncio::refresh_event_loop(py)?;
+    assert!(executor
+        .call_method1("submit", (py.eval("int", None, None)?,))
+        .is_err());
+    assert_ne!(
+        event_loop.getattr("_default_executor")?.as_ptr(),
+        executor.as_ptr()
+    );
+
+    let coro = pyo3_asyncio::tokio::into_coroutine(py, async {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        Ok(Python::with_gil(|py| 42.into_py(py)))
+    })?;
+    let value: i32 = event_loop
+        .call_method1("run_until_complete", (coro,))?
+        .extract()?;
+    assert_eq!(value, 42);
+
+    pyo3_asyncio::try_close(py)?;
+    new_loop.call_method0("close")?;
+
+    Ok(())
+}
+
+#[cfg(windows)]
+const PROACTOR_SHUTDOWN_MOD: &str = r#"
+import asyncio
+
+lost = False
+finalized = False
+suspended = None
+
+class Client(asyncio.Protocol):
+    def connection_lost(self, exc):
+        global lost
+        lost = True
+
+async def agen():
+    global finalized
+    try:
+        yield
+    finally:
+        finalized = True
+
+async def leave_open():
+    global suspended
+    loop = asyncio.get_running_loop()
+    server = await loop.create_server(asyncio.Protocol, "127.0.0.1", 0)
+    port = server.sockets[0].getsockname()[1]
+    transport, _ = await loop.create_connection(Client, "127.0.0.1", port)
+
+    # closing only schedules connection_lost, and the generator is left suspended
+    suspended = agen()
+    await suspended.__anext__()
+    transport.close()
+    server.close()
+"#;
+
+/// Close a `ProactorEventLoop` that still has a transport and an async generator to clean up
+#[cfg(windows)]
+fn test_proactor_shutdown(py: Python) -> PyResult<()> {
+    let asyncio = py.import("asyncio")?;
+    asyncio.call_method1(
+        "set_event_loop",
+        (asyncio.call_method0("ProactorEventLoop")?,),
+    )?;
+    let event_loop = pyo3_asyncio::refresh_event_loop(py)?;
+
+    let test_mod: PyObject = PyModule::from_code(
+        py,
+        PROACTOR_SHUTDOWN_MOD,
+        "test_proactor_shutdown/test_mod.py",
+        "test_mod",
+    )?
+    .into();
+
+    let leave_open = test_mod.clone_ref(py);
+    pyo3_asyncio::tokio::with_runtime_async(py, async move {
+        Python::with_gil(|py| {
+            pyo3_asyncio::into_future(leave_open.call_method0(py, "leave_open")?.as_ref(py))
+        })?
+        .await?;
+
+        Ok(())
+    })?;
+
+    // with_runtime_async owned the event loop, so it was drained and closed
+    assert!(event_loop.call_method0("is_closed")?.is_true()?);
+    assert!(test_mod.getattr(py, "finalized")?.extract::<bool>(py)?);
+    assert!(test_mod.getattr(py, "lost")?.extract::<bool>(py)?);
+
+    Ok(())
+}
@@ -1,9 +1,16 @@
-use std::future::Future;
+use std::{
+    any::Any,
+    future::Future,
+    panic::{self, AssertUnwindSafe},
+};
 
 use async_std::task;
 use pyo3::prelude::*;
 
-use crate::generic::{self, JoinError, Runtime};
+use crate::{
+    generic::{self, JoinError, Runtime, SpawnBlockingExt, SpawnLocalExt, UnwindSafeExt},
+    BridgeConfig,
+};
 
 /// <span class="module-item stab portability" style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"><code>attributes</code></span>
 /// re-exports for macros
@@ -23,15 +30,82 @@ pub use pyo3_asyncio_macros::async_std_main as main;
 #[cfg(all(feature = "attributes", feature = "testing"))]
 pub use pyo3_asyncio_macros::async_std_test as test;
 
-struct AsyncStdJoinError;
+/// <span class="module-item stab portability" style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"><code>attributes</code></span>
+/// <span class="module-item stab portability" style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"><code>testing</code></span>
+/// Provides the `main` function of a test binary that runs every test registered with
+/// [`test`]
+///
+/// Python is initialized once and the tests share a single `async-std` runtime, like with
+/// [`main`] and [`crate::testing::main`]. Since test functions may return `()` (see
+/// [`crate::testing::TestResult`]), synchronous tests written for the default harness can live in
+/// the same binary as the async ones by swapping `#[test]` for [`test`].
+///
+/// An optional `setup` function is called with the GIL held before any test runs, i.e. to add
+/// directories to `sys.path` or register Python modules that all of the tests rely on.
+///
+/// # Examples
+///
+/// ```ignore
+/// use std::time::Duration;
+///
+/// use pyo3::prelude::*;
+///
+/// fn setup(py: Python) -> PyResult<()> {
+///     py.import("sys")?.getattr("path")?.call_method1("insert", (0, "python"))?;
+///     Ok(())
+/// }
+///
+/// #[pyo3_asyncio::async_std::test]
+/// fn test_parse() {
+///     assert_eq!("42".parse::<i32>().unwrap(), 42);
+/// }
+///
+/// #[pyo3_asyncio::async_std::test]
+/// async fn test_sleep() -> PyResult<()> {
+///     async_std::task::sleep(Duration::from_millis(10)).await;
+///     Ok(())
+/// }
+///
+/// pyo3_asyncio::async_std::test_main!(setup);
+/// ```
+#[cfg(all(feature = "attributes", feature = "testing"))]
+pub use crate::__async_std_test_main as test_main;
+
+#[cfg(all(feature = "attributes", feature = "testing"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __async_std_test_main {
+    () => {
+        #[$crate::async_std::main]
+        async fn main() -> ::pyo3::PyResult<()> {
+            $crate::testing::main().await
+        }
+    };
+    ($setup:path) => {
+        #[$crate::async_std::main]
+        async fn main() -> ::pyo3::PyResult<()> {
+            ::pyo3::Python::with_gil($setup)?;
+            $crate::testing::main().await
+        }
+    };
+}
+
+/// The panic of a task spawned by [`AsyncStdRuntime`]
+pub struct AsyncStdJoinError(Box<dyn Any + Send + 'static>);
 
 impl JoinError for AsyncStdJoinError {
     fn is_panic(&self) -> bool {
-        todo!()
+        // async-std tasks can't be cancelled from the outside, so the only error is a panic
+        true
+    }
+    fn into_panic(self) -> Box<dyn Any + Send + 'static> {
+        self.0
     }
 }
 
-struct AsyncStdRuntime;
+/// The async-std [`Runtime`], for use with the runtime-generic utilities in
+/// [`crate::generic`]
+pub struct AsyncStdRuntime;
 
 impl Runtime for AsyncStdRuntime {
     type JoinError = AsyncStdJoinError;
@@ -41,9 +115,52 @@ impl Runtime for AsyncStdRuntime {
     where
         F: Future<Output = ()> + Send + 'static,
     {
-        task::spawn(async move {
-            fut.await;
-            Ok(())
+        task::spawn(catch_unwind(fut))
+    }
+
+    fn spawn_named<F>(name: &str, fut: F) -> Self::JoinHandle
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        task::Builder::new()
+            .name(name.to_string())
+            .spawn(catch_unwind(fut))
+            .expect("Couldn't spawn a named async-std task")
+    }
+
+    fn is_runtime_thread() -> bool {
+        task::try_current().is_some()
+    }
+}
+
+/// Catch a panic in `fut`, so it's reported through the `JoinHandle` of its task
+async fn catch_unwind<F>(fut: F) -> Result<(), AsyncStdJoinError>
+where
+    F: Future<Output = ()>,
+{
+    <AsyncStdRuntime as UnwindSafeExt>::catch_unwind(fut)
+        .await
+        .map_err(AsyncStdJoinError)
+}
+
+impl UnwindSafeExt for AsyncStdRuntime {}
+
+impl SpawnLocalExt for AsyncStdRuntime {
+    fn spawn_local<F>(fut: F) -> Self::JoinHandle
+    where
+        F: Future<Output = ()> + 'static,
+    {
+        task::spawn_local(catch_unwind(fut))
+    }
+}
+
+impl SpawnBlockingExt for AsyncStdRuntime {
+    fn spawn_blocking<F>(f: F) -> Self::JoinHandle
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        task::spawn_blocking(move || {
+            panic::catch_unwind(AssertUnwindSafe(f)).map_err(AsyncStdJoinError)
         })
     }
 }
@@ -87,8 +204,131 @@ where
     generic::run_until_complete::<AsyncStdRuntime, _>(py, fut)
 }
 
+/// Run the given event loop until the given Future completes
+///
+/// See [`generic::run_until_complete_with_loop`] for details.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `event_loop` - The Python event loop to run
+/// * `fut` - The future to drive to completion
+pub fn run_until_complete_with_loop<F>(py: Python, event_loop: &PyAny, fut: F) -> PyResult<()>
+where
+    F: Future<Output = PyResult<()>> + Send + 'static,
+{
+    generic::run_until_complete_with_loop::<AsyncStdRuntime, _>(py, event_loop, fut)
+}
+
+/// Initialize PyO3 Asyncio, run the event loop until `fut` completes, then close the event loop
+///
+/// See [`generic::with_runtime_async`] for details.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `fut` - The future to drive to completion
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// #
+/// # use pyo3::prelude::*;
+/// #
+/// Python::with_gil(|py| {
+///     pyo3_asyncio::async_std::with_runtime_async(py, async move {
+///         async_std::task::sleep(Duration::from_secs(1)).await;
+///         Ok(())
+///     })
+/// })
+/// .unwrap();
+/// ```
+pub fn with_runtime_async<F>(py: Python, fut: F) -> PyResult<()>
+where
+    F: Future<Output = PyResult<()>> + Send + 'static,
+{
+    generic::with_runtime_async::<AsyncStdRuntime, _>(py, fut)
+}
+
+/// A reusable runner for Rust futures, like Python's `asyncio.Runner`
+///
+/// See [`generic::PyRunner`] for details.
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// #
+/// # use pyo3::prelude::*;
+/// #
+/// Python::with_gil(|py| -> PyResult<()> {
+///     let mut runner = pyo3_asyncio::async_std::PyRunner::new(py)?;
+///
+///     for millis in 1..=3 {
+///         let slept = runner.run(py, async move {
+///             async_std::task::sleep(Duration::from_millis(millis)).await;
+///             Ok(millis)
+///         })?;
+///         assert_eq!(slept, millis);
+///     }
+///
+///     runner.close(py)
+/// })
+/// .unwrap();
+/// ```
+pub struct PyRunner(generic::PyRunner<AsyncStdRuntime>);
+
+impl PyRunner {
+    /// Initialize PyO3 Asyncio and create a runner with a copy of the current context
+    ///
+    /// # Arguments
+    /// * `py` - The current PyO3 GIL guard
+    pub fn new(py: Python) -> PyResult<Self> {
+        Ok(Self(generic::PyRunner::new(py)?))
+    }
+
+    /// Run the event loop until `fut` completes and return its output
+    ///
+    /// # Arguments
+    /// * `py` - The current PyO3 GIL guard
+    /// * `fut` - The future to drive to completion
+    pub fn run<F, T>(&self, py: Python, fut: F) -> PyResult<T>
+    where
+        F: Future<Output = PyResult<T>> + Send + 'static,
+        T: Send + 'static,
+    {
+        self.0.run(py, fut)
+    }
+
+    /// The event loop that futures are run on
+    ///
+    /// # Arguments
+    /// * `py` - The current PyO3 GIL guard
+    pub fn event_loop<'p>(&self, py: Python<'p>) -> &'p PyAny {
+        self.0.event_loop(py)
+    }
+
+    /// The `contextvars.Context` that futures are run in
+    ///
+    /// # Arguments
+    /// * `py` - The current PyO3 GIL guard
+    pub fn context<'p>(&'p self, py: Python<'p>) -> &'p PyAny {
+        self.0.context(py)
+    }
+
+    /// Close the runner, closing the event loop if the runner owns it
+    ///
+    /// # Arguments
+    /// * `py` - The current PyO3 GIL guard
+    pub fn close(&mut self, py: Python) -> PyResult<()> {
+        self.0.close(py)
+    }
+}
+
 /// Convert a Rust Future into a Python coroutine
 ///
+/// The future is always spawned, see [`into_coroutine_eager`] to skip the runtime for futures that
+/// are ready right away and [`generic::into_coroutine`] for why that's opt-in.
+///
 /// # Arguments
 /// * `py` - The current PyO3 GIL guard
 /// * `fut` - The Rust future to be converted
@@ -111,9 +351,528 @@ where
 ///     })
 /// }
 /// ```
+#[track_caller]
 pub fn into_coroutine<F>(py: Python, fut: F) -> PyResult<PyObject>
 where
     F: Future<Output = PyResult<PyObject>> + Send + 'static,
 {
     generic::into_coroutine::<AsyncStdRuntime, _>(py, fut)
 }
+
+/// Convert a Rust Future into a Python coroutine, skipping the runtime if it's ready right away
+///
+/// See [`generic::into_coroutine_eager`] for details.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `fut` - The Rust future to be converted
+#[track_caller]
+pub fn into_coroutine_eager<F>(py: Python, fut: F) -> PyResult<PyObject>
+where
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    generic::into_coroutine_eager::<AsyncStdRuntime, _>(py, fut)
+}
+
+/// Convert a `!Send` Rust Future into a Python coroutine
+///
+/// The future is spawned with `async_std::task::spawn_local`, so it runs on the calling thread.
+/// That thread must be driving async-std tasks (i.e. this is called from inside an async-std
+/// task), otherwise the future never runs.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `fut` - The Rust future to be converted
+///
+/// # Examples
+///
+/// ```
+/// use std::rc::Rc;
+///
+/// use pyo3::prelude::*;
+///
+/// async fn answer() -> PyResult<PyObject> {
+///     let coro = Python::with_gil(|py| {
+///         let answer = Rc::new(42);
+///
+///         pyo3_asyncio::async_std::into_coroutine_local(py, async move {
+///             async_std::task::yield_now().await;
+///             Ok(Python::with_gil(|py| (*answer).into_py(py)))
+///         })
+///     })?;
+///
+///     Python::with_gil(|py| pyo3_asyncio::into_future(coro.as_ref(py)))?.await
+/// }
+/// ```
+pub fn into_coroutine_local<F>(py: Python, fut: F) -> PyResult<PyObject>
+where
+    F: Future<Output = PyResult<PyObject>> + 'static,
+{
+    generic::into_coroutine_local::<AsyncStdRuntime, _>(py, fut)
+}
+
+/// Run blocking Python code on async-std's blocking thread pool
+///
+/// See [`generic::run_blocking_py`] for details.
+///
+/// # Arguments
+/// * `f` - The blocking Python code
+///
+/// # Examples
+///
+/// ```
+/// use pyo3::prelude::*;
+///
+/// async fn checksum(data: Vec<u8>) -> PyResult<String> {
+///     pyo3_asyncio::async_std::run_blocking_py(move |py| {
+///         py.import("hashlib")?
+///             .call_method1("sha256", (data.as_slice(),))?
+///             .call_method0("hexdigest")?
+///             .extract()
+///     })
+///     .await
+/// }
+/// ```
+pub async fn run_blocking_py<F, T>(f: F) -> PyResult<T>
+where
+    F: FnOnce(Python) -> PyResult<T> + Send + 'static,
+    T: Send + 'static,
+{
+    generic::run_blocking_py::<AsyncStdRuntime, _, _>(f).await
+}
+
+/// Protect a Rust future from being cancelled along with the Python code awaiting it
+///
+/// See [`generic::shield`] for details.
+///
+/// # Arguments
+/// * `fut` - The Rust future to be shielded
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use pyo3::prelude::*;
+///
+/// /// Commit a transaction, even if the Python task awaiting it is cancelled
+/// #[pyfunction]
+/// fn commit(py: Python) -> PyResult<PyObject> {
+///     pyo3_asyncio::async_std::into_coroutine_scoped(py, async move {
+///         pyo3_asyncio::async_std::shield(async move {
+///             async_std::task::sleep(Duration::from_millis(100)).await;
+///             Python::with_gil(|py| Ok(py.None()))
+///         })
+///         .await
+///     })
+/// }
+/// ```
+pub fn shield<F, T>(fut: F) -> impl Future<Output = PyResult<T>> + Send
+where
+    F: Future<Output = PyResult<T>> + Send + 'static,
+    T: Send + 'static,
+{
+    generic::shield::<AsyncStdRuntime, _, _>(fut)
+}
+
+/// Convert a Rust Future into a Python coroutine that runs in a named task
+///
+/// The name is available through `async_std::task::current().name()` while the future runs.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `name` - The name of the spawned task
+/// * `fut` - The Rust future to be converted
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use pyo3::prelude::*;
+///
+/// #[pyfunction]
+/// fn sleep_for(py: Python, secs: u64) -> PyResult<PyObject> {
+///     pyo3_asyncio::async_std::into_coroutine_named(py, "sleep_for", async move {
+///         async_std::task::sleep(Duration::from_secs(secs)).await;
+///         Python::with_gil(|py| Ok(py.None()))
+///     })
+/// }
+/// ```
+#[track_caller]
+pub fn into_coroutine_named<F>(py: Python, name: &str, fut: F) -> PyResult<PyObject>
+where
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    generic::into_coroutine_named::<AsyncStdRuntime, _>(py, name, fut)
+}
+
+/// Convert a Rust Future into a Python awaitable that belongs to the given event loop and runs in
+/// a named task
+///
+/// See [`generic::into_coroutine_named_with_loop`] for details.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `event_loop` - The Python event loop that the awaitable belongs to
+/// * `name` - The name of the spawned task
+/// * `fut` - The Rust future to be converted
+#[track_caller]
+pub fn into_coroutine_named_with_loop<F>(
+    py: Python,
+    event_loop: &PyAny,
+    name: &str,
+    fut: F,
+) -> PyResult<PyObject>
+where
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    generic::into_coroutine_named_with_loop::<AsyncStdRuntime, _>(py, event_loop, name, fut)
+}
+
+/// Convert a Rust Future into a Python coroutine with a custom error converter
+///
+/// See [`generic::into_coroutine_with_error_converter`] for details.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `fut` - The Rust future to be converted
+/// * `converter` - Converts the reason the future failed into a Python exception
+///
+/// # Examples
+///
+/// ```
+/// use pyo3::{exceptions::PyValueError, prelude::*};
+///
+/// #[pyfunction]
+/// fn validate(py: Python) -> PyResult<PyObject> {
+///     pyo3_asyncio::async_std::into_coroutine_with_error_converter(
+///         py,
+///         async move {
+///             Python::with_gil(|py| Ok(py.None()))
+///         },
+///         |_py, failure| PyValueError::new_err(failure.panic_message()),
+///     )
+/// }
+/// ```
+#[track_caller]
+pub fn into_coroutine_with_error_converter<F, C>(
+    py: Python,
+    fut: F,
+    converter: C,
+) -> PyResult<PyObject>
+where
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+    C: FnOnce(Python, generic::JoinFailure) -> PyErr + Send + 'static,
+{
+    generic::into_coroutine_with_error_converter::<AsyncStdRuntime, _, _>(py, fut, converter)
+}
+
+/// Convert a Rust Future into a Python awaitable that belongs to the given event loop, with a
+/// custom error converter
+///
+/// See [`generic::into_coroutine_with_error_converter_and_loop`] for details.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `event_loop` - The Python event loop that the awaitable belongs to
+/// * `fut` - The Rust future to be converted
+/// * `converter` - Converts the reason the future failed into a Python exception
+#[track_caller]
+pub fn into_coroutine_with_error_converter_and_loop<F, C>(
+    py: Python,
+    event_loop: &PyAny,
+    fut: F,
+    converter: C,
+) -> PyResult<PyObject>
+where
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+    C: FnOnce(Python, generic::JoinFailure) -> PyErr + Send + 'static,
+{
+    generic::into_coroutine_with_error_converter_and_loop::<AsyncStdRuntime, _, _>(
+        py, event_loop, fut, converter,
+    )
+}
+
+/// Convert a Rust Future into a Python coroutine that doesn't outlive its awaiter
+///
+/// See [`generic::into_coroutine_scoped`] for details.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `fut` - The Rust future to be converted
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use pyo3::prelude::*;
+///
+/// /// Sleep that stops sleeping as soon as the awaiting task is cancelled
+/// #[pyfunction]
+/// fn scoped_sleep(py: Python, secs: u64) -> PyResult<PyObject> {
+///     pyo3_asyncio::async_std::into_coroutine_scoped(py, async move {
+///         async_std::task::sleep(Duration::from_secs(secs)).await;
+///         Python::with_gil(|py| Ok(py.None()))
+///     })
+/// }
+/// ```
+#[track_caller]
+pub fn into_coroutine_scoped<F>(py: Python, fut: F) -> PyResult<PyObject>
+where
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    generic::into_coroutine_scoped::<AsyncStdRuntime, _>(py, fut)
+}
+
+/// Convert a Rust Future into a Python coroutine that belongs to the given event loop and doesn't
+/// outlive its awaiter
+///
+/// See [`generic::into_coroutine_scoped_with_loop`] for details.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `event_loop` - The Python event loop that the coroutine belongs to
+/// * `fut` - The Rust future to be converted
+#[track_caller]
+pub fn into_coroutine_scoped_with_loop<F>(
+    py: Python,
+    event_loop: &PyAny,
+    fut: F,
+) -> PyResult<PyObject>
+where
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    generic::into_coroutine_scoped_with_loop::<AsyncStdRuntime, _>(py, event_loop, fut)
+}
+
+/// Convert a Rust Future into a Python coroutine that binds to an event loop when it is awaited
+///
+/// See [`generic::into_coroutine_deferred`] for details.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `fut` - The Rust future to be converted
+///
+/// # Examples
+///
+/// ```
+/// use std::{thread, time::Duration};
+///
+/// use pyo3::prelude::*;
+///
+/// /// Create a sleep on a background thread, it can be awaited on whichever loop the caller uses
+/// fn sleep_from_thread(secs: u64) -> PyResult<PyObject> {
+///     thread::spawn(move || {
+///         Python::with_gil(|py| {
+///             pyo3_asyncio::async_std::into_coroutine_deferred(py, async move {
+///                 async_std::task::sleep(Duration::from_secs(secs)).await;
+///                 Python::with_gil(|py| Ok(py.None()))
+///             })
+///         })
+///     })
+///     .join()
+///     .unwrap()
+/// }
+/// ```
+#[track_caller]
+pub fn into_coroutine_deferred<F>(py: Python, fut: F) -> PyResult<PyObject>
+where
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    generic::into_coroutine_deferred::<AsyncStdRuntime, _>(py, fut)
+}
+
+/// Convert a Rust Future into a Python awaitable that belongs to the given event loop
+///
+/// See [`generic::into_coroutine_with_loop`] for details.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `event_loop` - The Python event loop that the awaitable belongs to
+/// * `fut` - The Rust future to be converted
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use pyo3::prelude::*;
+///
+/// /// Sleep on the Rust side, then wake up a task on the GUI's event loop
+/// fn sleep_for_gui(py: Python, gui_loop: &PyAny, secs: u64) -> PyResult<PyObject> {
+///     pyo3_asyncio::async_std::into_coroutine_with_loop(py, gui_loop, async move {
+///         async_std::task::sleep(Duration::from_secs(secs)).await;
+///         Python::with_gil(|py| Ok(py.None()))
+///     })
+/// }
+/// ```
+#[track_caller]
+pub fn into_coroutine_with_loop<F>(py: Python, event_loop: &PyAny, fut: F) -> PyResult<PyObject>
+where
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    generic::into_coroutine_with_loop::<AsyncStdRuntime, _>(py, event_loop, fut)
+}
+
+/// <span class="module-item stab portability" style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"><code>trio</code></span> Convert a Rust Future into a coroutine that can be awaited from Trio
+///
+/// See [`crate::trio::into_coroutine`] for details.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `fut` - The Rust future to be converted
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use pyo3::prelude::*;
+///
+/// /// Awaitable sleep function for Trio
+/// #[pyfunction]
+/// fn sleep_for(py: Python, secs: u64) -> PyResult<PyObject> {
+///     pyo3_asyncio::async_std::into_trio_coroutine(py, async move {
+///         async_std::task::sleep(Duration::from_secs(secs)).await;
+///         Python::with_gil(|py| Ok(py.None()))
+///     })
+/// }
+/// ```
+#[cfg(feature = "trio")]
+pub fn into_trio_coroutine<F>(py: Python, fut: F) -> PyResult<PyObject>
+where
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    crate::trio::into_coroutine::<AsyncStdRuntime, _>(py, fut)
+}
+
+/// Run a Rust Future as a task in a Python `asyncio.TaskGroup`
+///
+/// See [`generic::create_task_in_group`] for details.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `task_group` - The entered `asyncio.TaskGroup`
+/// * `fut` - The Rust future to be run in the group
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use pyo3::prelude::*;
+///
+/// /// Sleep as a member of the given task group
+/// #[pyfunction]
+/// fn sleep_in_group(py: Python, task_group: &PyAny, secs: u64) -> PyResult<PyObject> {
+///     pyo3_asyncio::async_std::create_task_in_group(py, task_group, async move {
+///         async_std::task::sleep(Duration::from_secs(secs)).await;
+///         Python::with_gil(|py| Ok(py.None()))
+///     })
+/// }
+/// ```
+#[track_caller]
+pub fn create_task_in_group<F>(py: Python, task_group: &PyAny, fut: F) -> PyResult<PyObject>
+where
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    generic::create_task_in_group::<AsyncStdRuntime, _>(py, task_group, fut)
+}
+
+/// Run a Rust Future as a task in a Python `asyncio.TaskGroup` that runs on the given event loop
+///
+/// See [`generic::create_task_in_group_with_loop`] for details.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `event_loop` - The Python event loop that the task group runs on
+/// * `task_group` - The entered `asyncio.TaskGroup`
+/// * `fut` - The Rust future to be run in the group
+#[track_caller]
+pub fn create_task_in_group_with_loop<F>(
+    py: Python,
+    event_loop: &PyAny,
+    task_group: &PyAny,
+    fut: F,
+) -> PyResult<PyObject>
+where
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    generic::create_task_in_group_with_loop::<AsyncStdRuntime, _>(py, event_loop, task_group, fut)
+}
+
+/// Expose a Rust resource as a Python async context manager
+///
+/// See [`generic::into_async_context_manager`] for details.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `manager` - The Rust resource
+#[track_caller]
+pub fn into_async_context_manager<M>(py: Python, manager: M) -> PyResult<PyObject>
+where
+    M: generic::AsyncContextManager,
+{
+    generic::into_async_context_manager::<AsyncStdRuntime, _>(py, manager)
+}
+
+/// Expose a Rust `Stream` as a Python async iterator
+///
+/// See [`generic::PyAsyncIter`] for details.
+///
+/// # Arguments
+/// * `stream` - The Rust stream to be iterated from Python
+#[track_caller]
+pub fn into_async_iter<S>(stream: S) -> generic::PyAsyncIter
+where
+    S: futures::Stream<Item = PyResult<PyObject>> + Send + 'static,
+{
+    generic::PyAsyncIter::new::<AsyncStdRuntime, _>(stream)
+}
+
+/// Expose a Rust `Stream` as a Python async iterator, buffering items ahead of the consumer
+///
+/// See [`generic::PyAsyncIter::with_config`] for details.
+///
+/// # Arguments
+/// * `stream` - The Rust stream to be iterated from Python
+/// * `config` - The buffering between the stream and the iterator
+#[track_caller]
+pub fn into_async_iter_with_config<S>(stream: S, config: BridgeConfig) -> generic::PyAsyncIter
+where
+    S: futures::Stream<Item = PyResult<PyObject>> + Send + 'static,
+{
+    generic::PyAsyncIter::with_config::<AsyncStdRuntime, _>(stream, config)
+}
+
+/// Convert a Python async iterator into a Rust `Stream`
+///
+/// See [`generic::into_stream`] for details.
+///
+/// # Arguments
+/// * `aiter` - The Python async iterator (or async iterable)
+/// * `config` - The buffering between the iterator and the stream
+pub fn into_stream(
+    aiter: &PyAny,
+    config: BridgeConfig,
+) -> PyResult<impl futures::Stream<Item = PyResult<PyObject>> + Send + 'static> {
+    generic::into_stream::<AsyncStdRuntime>(aiter, config)
+}
+
+/// Expose a Rust `Sink` and `Stream` pair as a Python async generator
+///
+/// See [`generic::PyAsyncGen`] for details.
+///
+/// # Arguments
+/// * `sink` - Receives the values sent and the exceptions thrown in from Python
+/// * `stream` - Produces the values yielded to Python
+#[track_caller]
+pub fn into_async_gen<Si, St>(sink: Si, stream: St) -> generic::PyAsyncGen
+where
+    Si: futures::Sink<PyResult<PyObject>, Error = PyErr> + Send + 'static,
+    St: futures::Stream<Item = PyResult<PyObject>> + Send + 'static,
+{
+    generic::PyAsyncGen::new::<AsyncStdRuntime, _, _>(sink, stream)
+}
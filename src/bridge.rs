@@ -0,0 +1,204 @@
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+use futures::{
+    future,
+    stream::{BoxStream, Stream, StreamExt},
+};
+use pyo3::prelude::*;
+
+use crate::{
+    generic::Runtime,
+    stats::{self, ConversionKind},
+    BufferOverflowError,
+};
+
+/// What a buffered stream bridge does when its buffer is full
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Stop pulling from the producer until the consumer makes room in the buffer
+    Block,
+    /// Discard the oldest buffered item to make room for the new one
+    DropOldest,
+    /// Stop the producer and raise a [`BufferOverflowError`] in the consumer once it has taken the
+    /// items that were already buffered
+    Error,
+}
+
+/// Buffering for stream bridges whose producer and consumer are on different sides of the
+/// Python/Rust boundary
+///
+/// A buffered bridge pulls from its producer as a separate task, so the producer can run ahead of
+/// a slow consumer by up to `buffer_size` items before the `overflow_policy` kicks in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BridgeConfig {
+    /// The maximum number of items held between the producer and the consumer
+    ///
+    /// A buffer size of 0 is treated as 1.
+    pub buffer_size: usize,
+    /// What to do with new items once the buffer is full
+    pub overflow_policy: OverflowPolicy,
+}
+
+impl Default for BridgeConfig {
+    fn default() -> Self {
+        Self {
+            buffer_size: 16,
+            overflow_policy: OverflowPolicy::Block,
+        }
+    }
+}
+
+struct Shared {
+    queue: VecDeque<PyResult<PyObject>>,
+    /// The producer is finished, either because it was exhausted or because it overflowed
+    closed: bool,
+    overflowed: bool,
+    /// The consumer was dropped, so the producer should stop
+    dropped: bool,
+    consumer: Option<Waker>,
+    producer: Option<Waker>,
+}
+
+impl Shared {
+    fn wake_consumer(&mut self) {
+        if let Some(waker) = self.consumer.take() {
+            waker.wake();
+        }
+    }
+
+    fn wake_producer(&mut self) {
+        if let Some(waker) = self.producer.take() {
+            waker.wake();
+        }
+    }
+}
+
+struct Receiver {
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl Stream for Receiver {
+    type Item = PyResult<PyObject>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut shared = self.shared.lock().unwrap();
+
+        if let Some(item) = shared.queue.pop_front() {
+            shared.wake_producer();
+            Poll::Ready(Some(item))
+        } else if shared.overflowed {
+            shared.overflowed = false;
+            Poll::Ready(Some(Err(overflow_error())))
+        } else if shared.closed {
+            Poll::Ready(None)
+        } else {
+            shared.consumer = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl Drop for Receiver {
+    fn drop(&mut self) {
+        let mut shared = self.shared.lock().unwrap();
+        shared.dropped = true;
+        shared.queue.clear();
+        shared.wake_producer();
+    }
+}
+
+fn overflow_error() -> PyErr {
+    BufferOverflowError::new_err("the consumer fell behind the producer of a stream bridge")
+}
+
+/// Pull `stream` as a separate task on `R`, buffering its items according to `config`
+///
+/// The task stops once the stream is exhausted, the buffer overflows with
+/// [`OverflowPolicy::Error`], or the returned stream is dropped. It's reported to the stats as a
+/// conversion of `kind` that is cancelled if the returned stream is dropped first.
+pub(crate) fn buffered<R, S>(
+    stream: S,
+    config: BridgeConfig,
+    kind: ConversionKind,
+) -> BoxStream<'static, PyResult<PyObject>>
+where
+    R: Runtime,
+    S: Stream<Item = PyResult<PyObject>> + Send + 'static,
+{
+    let buffer_size = config.buffer_size.max(1);
+    let shared = Arc::new(Mutex::new(Shared {
+        queue: VecDeque::with_capacity(buffer_size),
+        closed: false,
+        overflowed: false,
+        dropped: false,
+        consumer: None,
+        producer: None,
+    }));
+
+    let conversion = stats::Conversion::start(kind);
+    let producer = shared.clone();
+
+    stats::record_spawn(std::any::type_name::<R>());
+    drop(R::spawn(async move {
+        futures::pin_mut!(stream);
+        let mut overflowed = false;
+
+        while let Some(item) = stream.next().await {
+            let mut item = Some(item);
+
+            let pushed = future::poll_fn(|cx| {
+                let mut shared = producer.lock().unwrap();
+
+                if shared.dropped {
+                    return Poll::Ready(false);
+                }
+
+                if shared.queue.len() >= buffer_size {
+                    match config.overflow_policy {
+                        OverflowPolicy::Block => {
+                            shared.producer = Some(cx.waker().clone());
+                            return Poll::Pending;
+                        }
+                        OverflowPolicy::DropOldest => {
+                            shared.queue.pop_front();
+                        }
+                        OverflowPolicy::Error => {
+                            shared.overflowed = true;
+                            overflowed = true;
+                            return Poll::Ready(false);
+                        }
+                    }
+                }
+
+                shared.queue.push_back(item.take().unwrap());
+                shared.wake_consumer();
+                Poll::Ready(true)
+            })
+            .await;
+
+            if !pushed {
+                break;
+            }
+        }
+
+        let dropped = {
+            let mut shared = producer.lock().unwrap();
+            shared.closed = true;
+            shared.wake_consumer();
+            shared.dropped
+        };
+
+        if overflowed {
+            conversion.finish::<()>(&Err(overflow_error()));
+        } else if !dropped {
+            conversion.finish(&Ok(()));
+        }
+    }));
+
+    Receiver { shared }.boxed()
+}
@@ -0,0 +1,177 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use futures::channel::oneshot;
+use pyo3::prelude::*;
+
+use crate::{call_soon_threadsafe, try_state};
+
+/// Yield control back to the Python event loop and the runtime once
+///
+/// A Rust future only gives up its thread when it awaits something that isn't ready, so a long
+/// computation inside a converted future keeps a runtime worker busy until it's done. Awaiting
+/// `yield_now` lets the runtime poll other tasks in the meantime, and lets a future that was
+/// aborted (i.e. by [`crate::generic::into_coroutine_scoped`]) be dropped before it runs any
+/// further.
+///
+/// While the PyO3 Asyncio event loop is running, the yield is a round trip through it: a callback
+/// is scheduled with `call_soon_threadsafe` and the future resumes once the loop has run it, so
+/// the callbacks that were already queued on the loop (and the Python tasks they step) run first.
+/// The loop must not be blocked waiting on the yielding future in the meantime. Without a running
+/// event loop, this only yields to the runtime. For the first poll of [`crate::generic::into_coroutine_eager`], which runs on the
+/// thread that called into Rust (often the Python event loop), it moves the rest of the future
+/// onto the Rust runtime.
+///
+/// See [`Budget`] for yielding periodically from a loop.
+///
+/// # Examples
+///
+/// ```
+/// async fn checksum(data: Vec<u8>) -> u64 {
+///     // let the runtime poll other tasks before a long computation
+///     pyo3_asyncio::yield_now().await;
+///
+///     data.iter().fold(0u64, |sum, byte| sum.wrapping_mul(31).wrapping_add(*byte as u64))
+/// }
+/// ```
+pub fn yield_now() -> YieldNow {
+    YieldNow {
+        state: YieldState::Start,
+    }
+}
+
+/// The future returned by [`yield_now`]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct YieldNow {
+    state: YieldState,
+}
+
+enum YieldState {
+    Start,
+    /// Waiting for the event loop to run the scheduled callback
+    Looping(oneshot::Receiver<()>),
+    Yielded,
+}
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            match &mut self.state {
+                YieldState::Start => match Python::with_gil(schedule_yield) {
+                    Some(rx) => self.state = YieldState::Looping(rx),
+                    None => {
+                        self.state = YieldState::Yielded;
+                        cx.waker().wake_by_ref();
+                        return Poll::Pending;
+                    }
+                },
+                // a callback that was dropped without running (i.e. the loop was closed) is done
+                // with the loop as well
+                YieldState::Looping(rx) => return Pin::new(rx).poll(cx).map(|_| ()),
+                YieldState::Yielded => return Poll::Ready(()),
+            }
+        }
+    }
+}
+
+/// Schedule a callback on the event loop that completes the returned receiver, if it's running
+fn schedule_yield(py: Python) -> Option<oneshot::Receiver<()>> {
+    let event_loop = try_state()?.event_loop(py);
+
+    let running = event_loop
+        .call_method0("is_running")
+        .and_then(|running| running.is_true())
+        .unwrap_or(false);
+    if !running {
+        return None;
+    }
+
+    let (tx, rx) = oneshot::channel();
+    call_soon_threadsafe(event_loop, (PyYield { tx: Some(tx) },)).ok()?;
+
+    Some(rx)
+}
+
+#[pyclass]
+struct PyYield {
+    tx: Option<oneshot::Sender<()>>,
+}
+
+#[pymethods]
+impl PyYield {
+    #[call]
+    pub fn __call__(&mut self) {
+        if let Some(tx) = self.tx.take() {
+            if tx.send(()).is_err() {
+                // cancellation is not an error
+            }
+        }
+    }
+}
+
+/// Yields control back to the Python event loop and the runtime at a fixed interval during
+/// CPU-bound work
+///
+/// Calling [`yield_now`] on every iteration of a tight loop wastes most of the loop on
+/// rescheduling, and calling it too rarely starves the tasks sharing its thread. A `Budget` checks
+/// the clock instead: [`Budget::tick`] only yields once `interval` has passed since the budget was
+/// created or last yielded. The first yield is also what moves the rest of a converted future off
+/// of the Python event loop (see [`yield_now`]).
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use pyo3::prelude::*;
+/// use pyo3_asyncio::Budget;
+///
+/// async fn count_primes(limit: u64) -> PyResult<u64> {
+///     let mut budget = Budget::new(Duration::from_millis(10));
+///     let mut count = 0;
+///
+///     for n in 2..limit {
+///         budget.tick().await;
+///
+///         if (2..n).take_while(|d| d * d <= n).all(|d| n % d != 0) {
+///             count += 1;
+///         }
+///     }
+///
+///     Ok(count)
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Budget {
+    interval: Duration,
+    last_yield: Instant,
+}
+
+impl Budget {
+    /// Create a budget that yields once every `interval`
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_yield: Instant::now(),
+        }
+    }
+
+    /// Whether the budget is used up, i.e. the next call to [`Budget::tick`] yields
+    pub fn is_exhausted(&self) -> bool {
+        self.last_yield.elapsed() >= self.interval
+    }
+
+    /// Yield with [`yield_now`] if `interval` has passed since the last yield, otherwise do nothing
+    pub async fn tick(&mut self) {
+        if self.is_exhausted() {
+            yield_now().await;
+            self.last_yield = Instant::now();
+        }
+    }
+}
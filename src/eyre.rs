@@ -0,0 +1,70 @@
+use std::future::Future;
+
+use pyo3::prelude::*;
+
+use crate::{report_to_py_err, report_to_py_result, ErrorReport};
+
+impl ErrorReport for ::eyre::Report {
+    fn downcast_py_err(self) -> Result<PyErr, Self> {
+        self.downcast::<PyErr>()
+    }
+
+    fn chain(&self) -> Vec<&(dyn std::error::Error + 'static)> {
+        ::eyre::Report::chain(self).collect()
+    }
+}
+
+/// Convert an `eyre::Report` into a `PyErr`
+///
+/// If the error wraps a `PyErr`, the original Python exception is returned. Otherwise the error is
+/// raised as a `RuntimeError` whose message contains the full chain of causes, with each cause
+/// also attached as the `__cause__` of the exception above it.
+///
+/// # Arguments
+/// * `err` - The error to be converted
+pub fn into_py_err(err: ::eyre::Report) -> PyErr {
+    report_to_py_err(err)
+}
+
+/// Adapt a future that fails with an `eyre::Report` so it can be passed to `into_coroutine`
+///
+/// The successful value is converted into a `PyObject` and the error is converted with
+/// [`into_py_err`].
+///
+/// # Arguments
+/// * `fut` - The Rust future to be adapted
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "tokio-runtime")]
+/// # mod example {
+/// use eyre::{eyre, WrapErr};
+/// use pyo3::prelude::*;
+///
+/// #[pyfunction]
+/// fn load_config(py: Python, path: String) -> PyResult<PyObject> {
+///     pyo3_asyncio::tokio::into_coroutine(
+///         py,
+///         pyo3_asyncio::eyre::into_py_result(async move {
+///             let config = tokio::fs::read_to_string(&path)
+///                 .await
+///                 .wrap_err_with(|| format!("failed to load {}", path))?;
+///
+///             if config.is_empty() {
+///                 return Err(eyre!("{} is empty", path));
+///             }
+///
+///             Ok(config)
+///         }),
+///     )
+/// }
+/// # }
+/// ```
+pub async fn into_py_result<F, T>(fut: F) -> PyResult<PyObject>
+where
+    F: Future<Output = ::eyre::Result<T>>,
+    T: IntoPy<PyObject>,
+{
+    report_to_py_result(fut).await
+}
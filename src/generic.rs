@@ -1,13 +1,57 @@
-use std::future::Future;
+use std::{
+    any::Any,
+    future::Future,
+    marker::PhantomData,
+    panic::{self, AssertUnwindSafe, Location},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
 
-use pyo3::{exceptions::PyException, prelude::*};
+use futures::{
+    channel::oneshot,
+    future::{AbortHandle, Abortable, BoxFuture, CatchUnwind, FutureExt},
+    sink::{Sink, SinkExt},
+    stream::{BoxStream, Stream, StreamExt},
+    task,
+};
+use once_cell::sync::OnceCell;
+use pyo3::{
+    class::PyAsyncProtocol,
+    exceptions::{PyRuntimeError, PyStopAsyncIteration},
+    prelude::*,
+    types::PyDict,
+    AsPyPointer,
+};
 
-use crate::{dump_err, get_event_loop, CALL_SOON, CREATE_FUTURE, EXPECT_INIT};
+use crate::{
+    bridge, call_soon_threadsafe, cancelled, create_future, dump_err, get_event_loop, gil,
+    py_helpers, state, stats, BridgeConfig, ConversionKind, EventLoopClosedError,
+};
+
+/// <span class="module-item stab portability" style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"><code>testing</code></span> Conformance tests for custom [`Runtime`] implementations
+#[cfg(feature = "testing")]
+pub mod testing;
+
+/// <span class="module-item stab portability" style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"><code>async-channel</code></span> Queues, streams and oneshot results between Python and any [`Runtime`]
+#[cfg(feature = "async-channel")]
+pub mod channel;
 
 /// Generic utilities for a JoinError
 pub trait JoinError {
     /// Check if the spawned task exited because of a panic
     fn is_panic(&self) -> bool;
+    /// Consume the error and return the payload of the panic
+    ///
+    /// This is only called if [`JoinError::is_panic`] returned `true`. Runtimes that can't recover
+    /// the payload can rely on the default, which reports the panic as an "unknown panic".
+    fn into_panic(self) -> Box<dyn Any + Send + 'static>
+    where
+        Self: Sized,
+    {
+        Box::new("unknown panic")
+    }
 }
 
 /// Generic Rust async/await runtime
@@ -18,13 +62,201 @@ pub trait Runtime {
     type JoinHandle: Future<Output = Result<(), Self::JoinError>> + Send;
 
     /// Spawn a future onto this runtime's event loop
+    ///
+    /// The futures spawned by the conversions in this module catch their own panics and report
+    /// them to Python, so runtimes don't have to catch panics in `fut`. Runtimes whose
+    /// `JoinHandle`s do report panics (see [`JoinError::is_panic`]) can declare it with
+    /// [`UnwindSafeExt`].
     fn spawn<F>(fut: F) -> Self::JoinHandle
     where
         F: Future<Output = ()> + Send + 'static;
+
+    /// Spawn a named future onto this runtime's event loop
+    ///
+    /// Runtimes that can attach names to tasks for debugging tools (i.e. `tokio-console`) should
+    /// override this. By default, the name is ignored.
+    fn spawn_named<F>(name: &str, fut: F) -> Self::JoinHandle
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let _ = name;
+        Self::spawn(fut)
+    }
+
+    /// Run a closure in the context of this runtime
+    ///
+    /// Futures converted with [`into_coroutine_eager`] are polled once on the calling thread before
+    /// they are spawned. Runtimes whose futures need a thread-local context to be polled (i.e.
+    /// tokio's timers and IO) should override this to enter that context. By default, the closure
+    /// is simply called.
+    fn enter<T>(f: impl FnOnce() -> T) -> T {
+        f()
+    }
+
+    /// Whether the calling thread is driving this runtime's futures (i.e. is one of its workers)
+    ///
+    /// Blocking entry points like [`run_until_complete`] refuse to run on these threads, since the
+    /// event loop would stall the futures it's waiting for. By default, no thread is.
+    fn is_runtime_thread() -> bool {
+        false
+    }
+}
+
+/// Spawns the tasks of a conversion
+///
+/// Every [`Runtime`] spawns onto itself. Runtime modules that bind conversions to a runtime
+/// instance (i.e. [`crate::tokio::Bridge`]) implement this to pass the instance to the
+/// crate-internal functions as the `target`.
+pub(crate) trait Spawner {
+    /// The runtime that the tasks are spawned onto
+    type Runtime: Runtime;
+    /// The runtime instance to spawn onto, i.e. a runtime handle
+    type Target: Clone + Send + 'static;
+
+    /// See [`Runtime::spawn`]
+    fn spawn<F>(target: &Self::Target, fut: F) -> <Self::Runtime as Runtime>::JoinHandle
+    where
+        F: Future<Output = ()> + Send + 'static;
+
+    /// See [`Runtime::spawn_named`]
+    fn spawn_named<F>(
+        target: &Self::Target,
+        name: &str,
+        fut: F,
+    ) -> <Self::Runtime as Runtime>::JoinHandle
+    where
+        F: Future<Output = ()> + Send + 'static;
+
+    /// See [`Runtime::enter`]
+    fn enter<T>(target: &Self::Target, f: impl FnOnce() -> T) -> T;
+}
+
+impl<R> Spawner for R
+where
+    R: Runtime,
+{
+    type Runtime = R;
+    type Target = ();
+
+    fn spawn<F>(_: &(), fut: F) -> R::JoinHandle
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        R::spawn(fut)
+    }
+
+    fn spawn_named<F>(_: &(), name: &str, fut: F) -> R::JoinHandle
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        R::spawn_named(name, fut)
+    }
+
+    fn enter<T>(_: &(), f: impl FnOnce() -> T) -> T {
+        R::enter(f)
+    }
+}
+
+/// A [`Runtime`] that can run `!Send` futures on the thread that spawns them
+///
+/// This enables [`into_coroutine_local`], for futures that hold `Rc`s, `RefCell` borrows or other
+/// thread-bound state across `.await`s.
+pub trait SpawnLocalExt: Runtime {
+    /// Spawn a `!Send` future onto the calling thread
+    ///
+    /// Runtimes may require the calling thread to be set up for local tasks (i.e. inside a tokio
+    /// `LocalSet`), and may panic otherwise.
+    fn spawn_local<F>(fut: F) -> Self::JoinHandle
+    where
+        F: Future<Output = ()> + 'static;
+}
+
+/// A [`Runtime`] with a thread pool for blocking work
+///
+/// This enables [`run_blocking_py`], which keeps slow synchronous Python calls off of the threads
+/// that poll futures.
+pub trait SpawnBlockingExt: Runtime {
+    /// Run a blocking closure on this runtime's blocking thread pool
+    fn spawn_blocking<F>(f: F) -> Self::JoinHandle
+    where
+        F: FnOnce() + Send + 'static;
+}
+
+/// A [`Runtime`] whose tasks catch panics with `catch_unwind`
+///
+/// Implementing this declares that a panic in a spawned future is caught and reported through the
+/// task's `JoinHandle` (see [`JoinError::is_panic`]) rather than unwinding into the runtime.
+/// Runtimes whose tasks don't capture panics on their own can use
+/// [`UnwindSafeExt::catch_unwind`] to do it in [`Runtime::spawn`]. The conversions don't depend on
+/// it, `testing::unwind_safe_tests` checks the capability.
+pub trait UnwindSafeExt: Runtime {
+    /// Catch a panic in `fut`, returning the panic payload instead of unwinding
+    fn catch_unwind<F>(fut: F) -> CatchUnwind<AssertUnwindSafe<F>>
+    where
+        F: Future,
+    {
+        AssertUnwindSafe(fut).catch_unwind()
+    }
+}
+
+/// Catch a panic in `fut` instead of leaving it to the runtime, see [`task_failure`]
+///
+/// Runtimes don't have to catch panics in their tasks (see [`UnwindSafeExt`]), so the conversions
+/// catch the panics of the futures they spawn themselves.
+fn catch_panic<F>(fut: F) -> (impl Future<Output = ()>, oneshot::Receiver<PanicPayload>)
+where
+    F: Future<Output = ()>,
+{
+    let (tx, rx) = oneshot::channel();
+
+    let fut = async move {
+        if let Err(payload) = AssertUnwindSafe(fut).catch_unwind().await {
+            let _ = tx.send(payload);
+        }
+    };
+
+    (fut, rx)
+}
+
+/// Wait for a task spawned with [`catch_panic`] and find out how it failed, if it did
+async fn task_failure<E: JoinError>(
+    handle: impl Future<Output = Result<(), E>>,
+    panicked: oneshot::Receiver<PanicPayload>,
+) -> Option<JoinFailure> {
+    match handle.await {
+        Ok(()) => panicked.await.ok().map(JoinFailure::Panic),
+        Err(e) => Some(JoinFailure::from_join_error(e)),
+    }
+}
+
+type PanicPayload = Box<dyn Any + Send + 'static>;
+
+/// Refuse to block a thread that an event loop or `R` depends on
+///
+/// Running an event loop on such a thread doesn't fail on its own, it deadlocks (or raises a
+/// confusing error from deep inside asyncio), so this is checked up front.
+pub(crate) fn check_blocking_allowed<R: Runtime>(py: Python, entry_point: &str) -> PyResult<()> {
+    crate::check_no_running_loop(py, entry_point)?;
+
+    if R::is_runtime_thread() {
+        return Err(PyRuntimeError::new_err(format!(
+            "{} can't block a thread of the {} runtime, since the event loop would stall the Rust \
+             futures it's waiting for. Call it from a thread the runtime doesn't own, or await the \
+             Python code with pyo3_asyncio::into_future instead",
+            entry_point,
+            std::any::type_name::<R>()
+        )));
+    }
+
+    Ok(())
 }
 
 /// Run the event loop until the given Future completes
 ///
+/// This blocks the calling thread, so it fails with a `RuntimeError` on a thread that is already
+/// running an event loop or that belongs to `R` (see [`Runtime::is_runtime_thread`]) instead of
+/// deadlocking.
+///
 /// After this function returns, the event loop can be resumed with either [`run_until_complete`] or
 /// [`crate::run_forever`]
 ///
@@ -45,6 +277,9 @@ pub trait Runtime {
 /// #     fn is_panic(&self) -> bool {
 /// #         unreachable!()
 /// #     }
+/// #     fn into_panic(self) -> Box<dyn std::any::Any + Send + 'static> {
+/// #         unreachable!()
+/// #     }
 /// # }
 /// #
 /// # struct MyCustomJoinHandle;
@@ -95,39 +330,435 @@ where
     R: Runtime,
     F: Future<Output = PyResult<()>> + Send + 'static,
 {
-    let coro = into_coroutine::<R, _>(py, async move {
-        fut.await?;
-        Ok(Python::with_gil(|py| py.None()))
-    })?;
+    run_until_complete_with_loop::<R, _>(py, get_event_loop(py), fut)
+}
+
+/// Run the given event loop until the given Future completes
+///
+/// Same as [`run_until_complete`], except `event_loop` is run instead of the PyO3 Asyncio event
+/// loop.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `event_loop` - The Python event loop to run
+/// * `fut` - The future to drive to completion
+pub fn run_until_complete_with_loop<R, F>(py: Python, event_loop: &PyAny, fut: F) -> PyResult<()>
+where
+    R: Runtime,
+    F: Future<Output = PyResult<()>> + Send + 'static,
+{
+    run_until_complete_on::<R, _>(py, (), event_loop, fut)
+}
+
+/// Run `event_loop` until `fut`, spawned onto `target`, completes
+pub(crate) fn run_until_complete_on<S, F>(
+    py: Python,
+    target: S::Target,
+    event_loop: &PyAny,
+    fut: F,
+) -> PyResult<()>
+where
+    S: Spawner,
+    F: Future<Output = PyResult<()>> + Send + 'static,
+{
+    check_blocking_allowed::<S::Runtime>(py, "run_until_complete")?;
+
+    let coro = spawn_coroutine::<S, _, _>(
+        py,
+        target,
+        Some(event_loop),
+        Start::Spawn,
+        async move {
+            fut.await?;
+            Ok(Python::with_gil(|py| py.None()))
+        },
+        convert_join_failure,
+    )?;
 
-    get_event_loop(py).call_method1("run_until_complete", (coro,))?;
+    event_loop.call_method1("run_until_complete", (coro,))?;
 
     Ok(())
 }
 
+/// Initialize PyO3 Asyncio, run the event loop until `fut` completes, then close the event loop
+///
+/// This is the async counterpart of [`crate::with_runtime`]. Since the future is driven by the
+/// event loop, it can't be nested inside a running event loop (i.e. a test that is already driven
+/// by `run_until_complete`), which fails with a `RuntimeError`. Await the future with
+/// [`into_coroutine`] from Python or [`crate::into_future`] from Rust instead.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `fut` - The future to drive to completion
+pub fn with_runtime_async<R, F>(py: Python, fut: F) -> PyResult<()>
+where
+    R: Runtime,
+    F: Future<Output = PyResult<()>> + Send + 'static,
+{
+    crate::with_runtime(py, || {
+        if get_event_loop(py).call_method0("is_running")?.is_true()? {
+            return Err(PyRuntimeError::new_err(
+                "with_runtime_async can't run a future while the event loop is already running, \
+                 await it from the running event loop instead",
+            ));
+        }
+
+        run_until_complete::<R, _>(py, fut)
+    })
+}
+
+/// A reusable runner for Rust futures, like Python's `asyncio.Runner`
+///
+/// [`run_until_complete`] needs [`crate::with_runtime`] around it to initialize and close the event
+/// loop. A `PyRunner` bundles both: it initializes PyO3 Asyncio when it's created, can
+/// [`run`](PyRunner::run) any number of futures one after the other on the same event loop, and
+/// closes the event loop when it's closed or dropped. This suits REPLs and batch jobs that run
+/// many futures over time.
+///
+/// Like `asyncio.Runner`, a `PyRunner` owns a `contextvars.Context`. While a future is being run,
+/// the callbacks it schedules on the event loop run in that context, and so do the Python tasks
+/// created for it (i.e. by [`crate::into_future`]). Tasks copy the context when they're created,
+/// so context variables set on the runner's context (see [`PyRunner::context`]) are visible to
+/// every run.
+///
+/// Runners nest like [`crate::with_runtime`] calls: a runner created while PyO3 Asyncio is already
+/// initialized by an outer scope leaves the event loop open when it's closed.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::{task::{Context, Poll}, pin::Pin, future::Future};
+/// #
+/// # use pyo3_asyncio::generic::{JoinError, Runtime};
+/// #
+/// # struct MyCustomJoinError;
+/// #
+/// # impl JoinError for MyCustomJoinError {
+/// #     fn is_panic(&self) -> bool {
+/// #         unreachable!()
+/// #     }
+/// #     fn into_panic(self) -> Box<dyn std::any::Any + Send + 'static> {
+/// #         unreachable!()
+/// #     }
+/// # }
+/// #
+/// # struct MyCustomJoinHandle;
+/// #
+/// # impl Future for MyCustomJoinHandle {
+/// #     type Output = Result<(), MyCustomJoinError>;
+/// #
+/// #     fn poll(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Self::Output> {
+/// #         unreachable!()
+/// #     }
+/// # }
+/// #
+/// # struct MyCustomRuntime;
+/// #
+/// # impl Runtime for MyCustomRuntime {
+/// #     type JoinError = MyCustomJoinError;
+/// #     type JoinHandle = MyCustomJoinHandle;
+/// #
+/// #     fn spawn<F>(fut: F) -> Self::JoinHandle
+/// #     where
+/// #         F: Future<Output = ()> + Send + 'static
+/// #     {
+/// #         unreachable!()
+/// #     }
+/// # }
+/// #
+/// use pyo3::prelude::*;
+/// use pyo3_asyncio::generic::PyRunner;
+///
+/// Python::with_gil(|py| -> PyResult<()> {
+///     let mut runner = PyRunner::<MyCustomRuntime>::new(py)?;
+///
+///     for line in ["first", "second"] {
+///         let echoed: String = runner.run(py, async move { Ok(line.to_string()) })?;
+///         println!("{}", echoed);
+///     }
+///
+///     runner.close(py)
+/// })
+/// .unwrap();
+/// ```
+pub struct PyRunner<R> {
+    context: PyObject,
+    owns_loop: bool,
+    closed: bool,
+    _runtime: PhantomData<fn() -> R>,
+}
+
+impl<R: Runtime> PyRunner<R> {
+    /// Initialize PyO3 Asyncio and create a runner with a copy of the current context
+    ///
+    /// # Arguments
+    /// * `py` - The current PyO3 GIL guard
+    pub fn new(py: Python) -> PyResult<Self> {
+        let context = py.import("contextvars")?.call_method0("copy_context")?;
+
+        Ok(Self {
+            context: context.into(),
+            owns_loop: crate::enter_runtime(py)?,
+            closed: false,
+            _runtime: PhantomData,
+        })
+    }
+
+    /// Run the event loop until `fut` completes and return its output
+    ///
+    /// This blocks the calling thread like [`run_until_complete`], with the same restrictions on the
+    /// threads it can be called from.
+    ///
+    /// # Arguments
+    /// * `py` - The current PyO3 GIL guard
+    /// * `fut` - The future to drive to completion
+    pub fn run<F, T>(&self, py: Python, fut: F) -> PyResult<T>
+    where
+        F: Future<Output = PyResult<T>> + Send + 'static,
+        T: Send + 'static,
+    {
+        if self.closed {
+            return Err(PyRuntimeError::new_err("PyRunner is closed"));
+        }
+
+        check_blocking_allowed::<R>(py, "PyRunner::run")?;
+
+        let event_loop = get_event_loop(py);
+        let context = self.context.as_ref(py);
+        let (tx, rx) = oneshot::channel();
+
+        crate::with_scheduling_context(context, || -> PyResult<()> {
+            let coro = into_coroutine_with_loop::<R, _>(py, event_loop, async move {
+                let _ = tx.send(fut.await?);
+                Ok(Python::with_gil(|py| py.None()))
+            })?;
+
+            event_loop.call_method1("run_until_complete", (coro,))?;
+            Ok(())
+        })?;
+
+        // run_until_complete only returns successfully once the future has completed
+        Ok(rx
+            .now_or_never()
+            .and_then(Result::ok)
+            .expect("PyRunner future completed without a result"))
+    }
+
+    /// The event loop that futures are run on
+    ///
+    /// # Arguments
+    /// * `py` - The current PyO3 GIL guard
+    pub fn event_loop<'p>(&self, py: Python<'p>) -> &'p PyAny {
+        get_event_loop(py)
+    }
+
+    /// The `contextvars.Context` that futures are run in
+    ///
+    /// # Arguments
+    /// * `py` - The current PyO3 GIL guard
+    pub fn context<'p>(&'p self, py: Python<'p>) -> &'p PyAny {
+        self.context.as_ref(py)
+    }
+
+    /// Close the runner, closing the event loop if the runner owns it
+    ///
+    /// Closing a runner that is already closed does nothing. Runners are closed when they're
+    /// dropped as well, but errors are only reported by `close`.
+    ///
+    /// # Arguments
+    /// * `py` - The current PyO3 GIL guard
+    pub fn close(&mut self, py: Python) -> PyResult<()> {
+        if self.closed {
+            return Ok(());
+        }
+
+        self.closed = true;
+        crate::exit_runtime(py, self.owns_loop)
+    }
+}
+
+impl<R> Drop for PyRunner<R> {
+    fn drop(&mut self) {
+        if self.closed {
+            return;
+        }
+
+        self.closed = true;
+        Python::with_gil(|py| {
+            if crate::exit_runtime(py, self.owns_loop)
+                .map_err(dump_err(py))
+                .is_err()
+            {
+                // the error has been reported, there's nothing else to do while dropping
+            }
+        });
+    }
+}
+
+#[pyclass]
+struct PyCheckedCompleter {}
+
+#[pymethods]
+impl PyCheckedCompleter {
+    #[call]
+    pub fn __call__(&self, future: &PyAny, complete: &PyAny, value: &PyAny) -> PyResult<()> {
+        // the future may have been cancelled on the Python side while the Rust future was running
+        if future.call_method0("done")?.is_true()? {
+            return Ok(());
+        }
+
+        complete.call1((value,))?;
+
+        Ok(())
+    }
+}
+
 fn set_result(py: Python, future: &PyAny, result: PyResult<PyObject>) -> PyResult<()> {
+    // the future may belong to a different loop than the PyO3 Asyncio one (see into_coroutine_deferred)
+    let event_loop = future.call_method0("get_loop")?;
+
+    let (complete, value) = match result {
+        Ok(val) => (future.getattr("set_result")?, val),
+        Err(err) => (future.getattr("set_exception")?, err.into_py(py)),
+    };
+
+    match call_soon_threadsafe(event_loop, (PyCheckedCompleter {}, future, complete, value)) {
+        // nothing can be awaiting the future on a closed event loop, so the result is dropped
+        Err(e) if e.is_instance::<EventLoopClosedError>(py) => Ok(()),
+        result => result,
+    }
+}
+
+/// Set the result of a future that nothing can be awaiting yet
+///
+/// On the event loop thread the result is set immediately, so awaiting the future doesn't yield to
+/// the event loop. Anywhere else, setting the result has to be scheduled like [`set_result`].
+fn set_result_eager(py: Python, future: &PyAny, result: PyResult<PyObject>) -> PyResult<()> {
+    let on_loop = match state().asyncio.call_method0(py, "get_running_loop") {
+        Ok(running_loop) => running_loop.as_ptr() == future.call_method0("get_loop")?.as_ptr(),
+        // no event loop is running on this thread
+        Err(e) if e.is_instance::<PyRuntimeError>(py) => false,
+        Err(e) => return Err(e),
+    };
+
+    if !on_loop {
+        return set_result(py, future, result);
+    }
+
     match result {
-        Ok(val) => {
-            let set_result = future.getattr("set_result")?;
-            CALL_SOON
-                .get()
-                .expect(EXPECT_INIT)
-                .call1(py, (set_result, val))?;
+        Ok(val) => future.call_method1("set_result", (val,))?,
+        Err(err) => future.call_method1("set_exception", (err,))?,
+    };
+
+    Ok(())
+}
+
+/// The reason a spawned Rust future did not produce a result
+pub enum JoinFailure {
+    /// The future panicked with the given payload
+    Panic(Box<dyn Any + Send + 'static>),
+    /// The future was cancelled by the runtime before it could complete (i.e. during shutdown)
+    Cancelled,
+}
+
+impl JoinFailure {
+    pub(crate) fn from_join_error<E: JoinError>(e: E) -> Self {
+        if e.is_panic() {
+            JoinFailure::Panic(e.into_panic())
+        } else {
+            JoinFailure::Cancelled
         }
-        Err(err) => {
-            let set_exception = future.getattr("set_exception")?;
-            CALL_SOON
-                .get()
-                .expect(EXPECT_INIT)
-                .call1(py, (set_exception, err))?;
+    }
+
+    /// Extract the message from a panic payload
+    ///
+    /// `panic!` payloads are either a `&'static str` or a formatted `String`, anything else is
+    /// reported as an unknown error. Returns `None` if the future didn't panic.
+    pub fn panic_message(&self) -> Option<String> {
+        match self {
+            JoinFailure::Panic(payload) => {
+                Some(if let Some(message) = payload.downcast_ref::<&str>() {
+                    message.to_string()
+                } else if let Some(message) = payload.downcast_ref::<String>() {
+                    message.clone()
+                } else {
+                    "unknown error".to_string()
+                })
+            }
+            JoinFailure::Cancelled => None,
         }
     }
+}
 
-    Ok(())
+/// A function that converts a [`JoinFailure`] into the exception raised by the Python awaitable
+pub type ErrorConverter = dyn Fn(Python, JoinFailure) -> PyErr + Send + Sync;
+
+static ERROR_CONVERTER: OnceCell<Box<ErrorConverter>> = OnceCell::new();
+
+/// Set the global converter used when a future spawned by [`into_coroutine`] fails to complete
+///
+/// By default, panics are raised as a `RuntimeError` containing the panic message and
+/// cancellations are raised as an `asyncio.CancelledError`. Registering a converter lets
+/// applications raise their own exception types instead. Use
+/// [`into_coroutine_with_error_converter`] to override the converter for a single conversion.
+///
+/// # Panics
+/// This function will panic if called a second time.
+///
+/// # Examples
+///
+/// ```
+/// use pyo3::{create_exception, exceptions::PyException};
+/// use pyo3_asyncio::generic::JoinFailure;
+///
+/// create_exception!(my_service, InternalError, PyException);
+///
+/// pyo3_asyncio::generic::set_error_converter(|py, failure| match failure {
+///     JoinFailure::Panic(_) => InternalError::new_err(failure.panic_message()),
+///     JoinFailure::Cancelled => pyo3_asyncio::generic::default_error_converter(py, failure),
+/// });
+/// ```
+pub fn set_error_converter<C>(converter: C)
+where
+    C: Fn(Python, JoinFailure) -> PyErr + Send + Sync + 'static,
+{
+    if ERROR_CONVERTER.set(Box::new(converter)).is_err() {
+        panic!("PyO3 Asyncio error converter has already been set");
+    }
+}
+
+/// The converter used when no global converter has been set with [`set_error_converter`]
+pub fn default_error_converter(py: Python, failure: JoinFailure) -> PyErr {
+    match failure.panic_message() {
+        Some(message) => PyRuntimeError::new_err(format!("rust future panicked: {}", message)),
+        None => cancelled(py).unwrap_or_else(|e| e),
+    }
+}
+
+pub(crate) fn convert_join_failure(py: Python, failure: JoinFailure) -> PyErr {
+    match ERROR_CONVERTER.get() {
+        Some(converter) => converter(py, failure),
+        None => default_error_converter(py, failure),
+    }
 }
 
 /// Convert a Rust Future into a Python coroutine with a generic runtime
 ///
+/// `fut` is always spawned onto `R`, even if it's ready right away, and its result reaches the
+/// Python awaitable through `call_soon_threadsafe`. [`into_coroutine_eager`] skips both for futures
+/// that usually complete without waiting (i.e. cache hits). The fast path is opt-in because it
+/// moves the first poll onto the calling thread: it runs with the GIL held, usually on the event
+/// loop thread, and outside of any task of `R`. Futures that do expensive work before their first
+/// `.await` would stall the event loop there, and futures that use task-scoped runtime APIs before
+/// it (i.e. tokio's `task::id`) would panic, so existing callers keep the spawning behavior.
+///
+/// If `fut` panics, the panic is caught by the runtime and the Python awaitable raises a
+/// `RuntimeError` containing the panic message instead of never resolving. See
+/// [`set_error_converter`] to raise a different exception.
+///
+/// In asyncio debug mode (see `loop.set_debug`), the Rust file and line that called
+/// `into_coroutine` is added to the traceback asyncio records for the Python future.
+///
 /// # Arguments
 /// * `py` - The current PyO3 GIL guard
 /// * `fut` - The Rust future to be converted
@@ -145,6 +776,9 @@ fn set_result(py: Python, future: &PyAny, result: PyResult<PyObject>) -> PyResul
 /// #     fn is_panic(&self) -> bool {
 /// #         unreachable!()
 /// #     }
+/// #     fn into_panic(self) -> Box<dyn std::any::Any + Send + 'static> {
+/// #         unreachable!()
+/// #     }
 /// # }
 /// #
 /// # struct MyCustomJoinHandle;
@@ -192,47 +826,1390 @@ fn set_result(py: Python, future: &PyAny, result: PyResult<PyObject>) -> PyResul
 ///    })
 /// }
 /// ```
+#[track_caller]
 pub fn into_coroutine<R, F>(py: Python, fut: F) -> PyResult<PyObject>
 where
     R: Runtime,
     F: Future<Output = PyResult<PyObject>> + Send + 'static,
 {
-    let future_rx = CREATE_FUTURE.get().expect(EXPECT_INIT).call0(py)?;
-    let future_tx1 = future_rx.clone();
-    let future_tx2 = future_rx.clone();
-
-    R::spawn(async move {
-        if let Err(e) = R::spawn(async move {
-            let result = fut.await;
+    spawn_coroutine::<R, _, _>(py, (), None, Start::Spawn, fut, convert_join_failure)
+}
 
-            Python::with_gil(move |py| {
-                if set_result(py, future_tx1.as_ref(py), result)
-                    .map_err(dump_err(py))
-                    .is_err()
-                {
+/// Convert a Rust Future into a Python coroutine, skipping the runtime if it's ready right away
+///
+/// This behaves like [`into_coroutine`], but `fut` is polled once on the calling thread (inside
+/// [`Runtime::enter`]) before it is spawned. If it is already ready, it is never spawned: the
+/// result is set on the Python awaitable before this function returns, so awaiting it from the
+/// event loop thread doesn't even yield to the event loop. This suits futures that usually
+/// complete without waiting, like cache lookups.
+///
+/// The first poll runs while the caller holds the GIL, often on the event loop thread, and outside
+/// of any task of `R`, so runtime APIs that need a task context (i.e. tokio's `task_local!` or
+/// `task::id`) aren't available before the first `.await`. Any expensive work should happen after
+/// the first `.await` (or in a future wrapped with [`crate::PyFutureExt::await_released`]). In
+/// asyncio debug mode, a warning is logged on the `asyncio` logger if the first poll held the
+/// event loop for longer than its `slow_callback_duration`.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `fut` - The Rust future to be converted
+#[track_caller]
+pub fn into_coroutine_eager<R, F>(py: Python, fut: F) -> PyResult<PyObject>
+where
+    R: Runtime,
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    spawn_coroutine::<R, _, _>(py, (), None, Start::Eager, fut, convert_join_failure)
+}
 
-                    // Cancelled
-                }
-            });
-        })
-        .await
-        {
-            if e.is_panic() {
-                Python::with_gil(move |py| {
-                    if set_result(
-                        py,
-                        future_tx2.as_ref(py),
-                        Err(PyException::new_err("rust future panicked")),
-                    )
-                    .map_err(dump_err(py))
-                    .is_err()
-                    {
-                        // Cancelled
-                    }
-                });
-            }
-        }
+/// Convert a Rust Future into a Python coroutine that runs in a named task
+///
+/// This behaves like [`into_coroutine`], but the task is spawned with [`Runtime::spawn_named`] so
+/// it can be told apart from other bridged tasks in debugging tools like `tokio-console`.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `name` - The name of the spawned task
+/// * `fut` - The Rust future to be converted
+#[track_caller]
+pub fn into_coroutine_named<R, F>(py: Python, name: &str, fut: F) -> PyResult<PyObject>
+where
+    R: Runtime,
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    spawn_coroutine::<R, _, _>(py, (), None, Start::Named(name), fut, convert_join_failure)
+}
+
+/// Convert a Rust Future into a Python awaitable that belongs to the given event loop and runs in
+/// a named task
+///
+/// Same as [`into_coroutine_named`], except the Python future is created on `event_loop` (see
+/// [`into_coroutine_with_loop`]).
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `event_loop` - The Python event loop that the awaitable belongs to
+/// * `name` - The name of the spawned task
+/// * `fut` - The Rust future to be converted
+#[track_caller]
+pub fn into_coroutine_named_with_loop<R, F>(
+    py: Python,
+    event_loop: &PyAny,
+    name: &str,
+    fut: F,
+) -> PyResult<PyObject>
+where
+    R: Runtime,
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    spawn_coroutine::<R, _, _>(
+        py,
+        (),
+        Some(event_loop),
+        Start::Named(name),
+        fut,
+        convert_join_failure,
+    )
+}
+
+/// Convert a `!Send` Rust Future into a Python coroutine
+///
+/// This behaves like [`into_coroutine`], except the future is spawned with
+/// [`SpawnLocalExt::spawn_local`], so it runs on the calling thread and doesn't need to be `Send`.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `fut` - The Rust future to be converted
+pub fn into_coroutine_local<R, F>(py: Python, fut: F) -> PyResult<PyObject>
+where
+    R: SpawnLocalExt,
+    R::JoinHandle: 'static,
+    F: Future<Output = PyResult<PyObject>> + 'static,
+{
+    let future_rx = create_future(py)?;
+    let completer = FutureCompleter(future_rx.clone());
+    let completer_tx = completer.clone();
+    let conversion = stats::Conversion::start(ConversionKind::IntoCoroutine);
+
+    let (task, panicked) = catch_panic(async move {
+        let result = fut.await;
+        conversion.finish(&result);
+
+        Python::with_gil(move |py| {
+            if completer_tx
+                .complete(py, result)
+                .map_err(dump_err(py))
+                .is_err()
+            {
+                // Cancelled
+            }
+        });
     });
 
+    stats::record_spawn(std::any::type_name::<R>());
+    let handle = R::spawn_local(task);
+
+    // the local task can't be awaited from other threads, so it's supervised by a regular one
+    drop(R::spawn(async move {
+        if let Some(failure) = task_failure(handle, panicked).await {
+            Python::with_gil(move |py| {
+                let err = convert_join_failure(py, failure);
+
+                if completer
+                    .complete(py, Err(err))
+                    .map_err(dump_err(py))
+                    .is_err()
+                {
+                    // Cancelled
+                }
+            });
+        }
+    }));
+
     Ok(future_rx)
 }
+
+/// Run blocking Python code on the blocking thread pool of `R`
+///
+/// Slow synchronous Python calls (i.e. pandas or requests) hold the GIL and block whatever thread
+/// they run on, starving the other futures of that thread. `f` is moved onto a blocking thread
+/// with [`SpawnBlockingExt::spawn_blocking`] instead and called there with the GIL held.
+///
+/// Nothing runs until the returned future is first polled. Like any blocking task, `f` runs to
+/// completion even if the future is dropped. If `f` panics, the future fails with the same
+/// exception as a panicking conversion (see [`set_error_converter`]).
+///
+/// # Arguments
+/// * `f` - The blocking Python code
+pub async fn run_blocking_py<R, F, T>(f: F) -> PyResult<T>
+where
+    R: SpawnBlockingExt,
+    F: FnOnce(Python) -> PyResult<T> + Send + 'static,
+    T: Send + 'static,
+{
+    let (tx, rx) = oneshot::channel();
+
+    let handle = R::spawn_blocking(move || {
+        // like spawned futures, blocking closures catch their own panics
+        let _ = tx.send(panic::catch_unwind(AssertUnwindSafe(|| {
+            Python::with_gil(f)
+        })));
+    });
+
+    let failure = match handle.await {
+        Ok(()) => match rx.await {
+            Ok(Ok(result)) => return result,
+            Ok(Err(payload)) => JoinFailure::Panic(payload),
+            Err(_) => JoinFailure::Cancelled,
+        },
+        Err(e) => JoinFailure::from_join_error(e),
+    };
+
+    Python::with_gil(|py| Err(convert_join_failure(py, failure)))
+}
+
+/// Protect a Rust future from being cancelled along with the Python code awaiting it
+///
+/// Mirrors `asyncio.shield`: `fut` is spawned onto `R` right away and runs to completion no matter
+/// what happens to the returned future. Dropping the returned future (i.e. because the coroutine of
+/// [`into_coroutine_scoped`] or a task of [`create_task_in_group`] was cancelled from Python) only
+/// cancels the wait, so non-idempotent operations like commits aren't interrupted halfway through.
+/// If nothing is waiting for it anymore, an error returned by `fut` is printed like any other
+/// error that can't be reported to Python.
+///
+/// If `fut` panics, the returned future fails with the same exception as a panicking conversion
+/// (see [`set_error_converter`]).
+///
+/// # Arguments
+/// * `fut` - The Rust future to be shielded
+pub fn shield<R, F, T>(fut: F) -> impl Future<Output = PyResult<T>> + Send
+where
+    R: Runtime,
+    F: Future<Output = PyResult<T>> + Send + 'static,
+    T: Send + 'static,
+{
+    let (tx, rx) = oneshot::channel();
+
+    let (task, panicked) = catch_panic(async move {
+        if let Err(Err(e)) = tx.send(fut.await) {
+            Python::with_gil(|py| dump_err(py)(e));
+        }
+    });
+
+    stats::record_spawn(std::any::type_name::<R>());
+    let handle = R::spawn(task);
+
+    async move {
+        // the result is sent before the task finishes, so it's only missing if the task failed
+        if let Ok(result) = rx.await {
+            return result;
+        }
+
+        let failure = task_failure(handle, panicked)
+            .await
+            .unwrap_or(JoinFailure::Cancelled);
+
+        Python::with_gil(|py| Err(convert_join_failure(py, failure)))
+    }
+}
+
+/// Get the qualified name of the Python function that is currently running
+///
+/// When called from a `#[pyfunction]`, this is the Python function that called into Rust, which
+/// makes it a reasonable default name for tasks created on its behalf.
+#[cfg(all(feature = "tokio-console", tokio_unstable))]
+pub(crate) fn python_caller_name(py: Python) -> Option<String> {
+    let code = py
+        .import("sys")
+        .and_then(|sys| sys.call_method1("_getframe", (0,)))
+        .and_then(|frame| frame.getattr("f_code"))
+        .ok()?;
+
+    // co_qualname was added in Python 3.11
+    code.getattr("co_qualname")
+        .or_else(|_| code.getattr("co_name"))
+        .and_then(|name| name.extract())
+        .ok()
+}
+
+/// Convert a Rust Future into a Python coroutine with a custom [`ErrorConverter`]
+///
+/// This behaves like [`into_coroutine`], but `converter` is used instead of the global converter if
+/// the spawned future panics or is cancelled by the runtime.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `fut` - The Rust future to be converted
+/// * `converter` - Converts the reason the future failed into a Python exception
+#[track_caller]
+pub fn into_coroutine_with_error_converter<R, F, C>(
+    py: Python,
+    fut: F,
+    converter: C,
+) -> PyResult<PyObject>
+where
+    R: Runtime,
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+    C: FnOnce(Python, JoinFailure) -> PyErr + Send + 'static,
+{
+    spawn_coroutine::<R, _, _>(py, (), None, Start::Spawn, fut, converter)
+}
+
+/// Convert a Rust Future into a Python awaitable that belongs to the given event loop, with a
+/// custom [`ErrorConverter`]
+///
+/// Same as [`into_coroutine_with_error_converter`], except the Python future is created on
+/// `event_loop` (see [`into_coroutine_with_loop`]).
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `event_loop` - The Python event loop that the awaitable belongs to
+/// * `fut` - The Rust future to be converted
+/// * `converter` - Converts the reason the future failed into a Python exception
+#[track_caller]
+pub fn into_coroutine_with_error_converter_and_loop<R, F, C>(
+    py: Python,
+    event_loop: &PyAny,
+    fut: F,
+    converter: C,
+) -> PyResult<PyObject>
+where
+    R: Runtime,
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+    C: FnOnce(Python, JoinFailure) -> PyErr + Send + 'static,
+{
+    spawn_coroutine::<R, _, _>(py, (), Some(event_loop), Start::Spawn, fut, converter)
+}
+
+/// Convert a Rust Future into a Python awaitable that belongs to the given event loop
+///
+/// Same as [`into_coroutine`], except the Python future is created on `event_loop` instead of the
+/// PyO3 Asyncio event loop, so it can be awaited by tasks running on `event_loop`. This is useful
+/// when an application runs several event loops (i.e. a GUI loop and a worker loop) and must pick
+/// where the result is delivered. See [`into_coroutine_deferred`] to use whichever loop awaits the
+/// coroutine instead.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `event_loop` - The Python event loop that the awaitable belongs to
+/// * `fut` - The Rust future to be converted
+#[track_caller]
+pub fn into_coroutine_with_loop<R, F>(py: Python, event_loop: &PyAny, fut: F) -> PyResult<PyObject>
+where
+    R: Runtime,
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    spawn_coroutine::<R, _, _>(
+        py,
+        (),
+        Some(event_loop),
+        Start::Spawn,
+        fut,
+        convert_join_failure,
+    )
+}
+
+/// Create a Python future on `event_loop`, or on the PyO3 Asyncio event loop if there's none
+fn new_future(py: Python, event_loop: Option<&PyAny>) -> PyResult<PyObject> {
+    match event_loop {
+        Some(event_loop) => Ok(event_loop.call_method0("create_future")?.into()),
+        None => create_future(py),
+    }
+}
+
+/// Convert `fut` into a Python future on `event_loop` (see [`new_future`]), spawned onto `target`
+#[track_caller]
+pub(crate) fn spawn_coroutine<S, F, C>(
+    py: Python,
+    target: S::Target,
+    event_loop: Option<&PyAny>,
+    start: Start,
+    fut: F,
+    converter: C,
+) -> PyResult<PyObject>
+where
+    S: Spawner,
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+    C: FnOnce(Python, JoinFailure) -> PyErr + Send + 'static,
+{
+    spawn_coroutine_at::<S, _, _>(
+        py,
+        target,
+        event_loop,
+        Location::caller(),
+        start,
+        fut,
+        converter,
+    )
+}
+
+/// Same as [`spawn_coroutine`], but the conversion is attributed to `origin`
+fn spawn_coroutine_at<S, F, C>(
+    py: Python,
+    target: S::Target,
+    event_loop: Option<&PyAny>,
+    origin: &'static Location<'static>,
+    start: Start,
+    fut: F,
+    converter: C,
+) -> PyResult<PyObject>
+where
+    S: Spawner,
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+    C: FnOnce(Python, JoinFailure) -> PyErr + Send + 'static,
+{
+    let future_rx = new_future(py, event_loop)?;
+    let event_loop = event_loop.unwrap_or_else(|| crate::get_event_loop(py));
+
+    spawn_into_future::<S, _, _>(
+        py,
+        target,
+        event_loop,
+        future_rx.clone(),
+        origin,
+        start,
+        fut,
+        converter,
+    )?;
+
+    Ok(future_rx)
+}
+
+/// Drive `fut` to completion on `target` and report its result to the Python `future`, which
+/// belongs to `event_loop`
+///
+/// In asyncio debug mode, `origin` (the Rust location of the conversion) is recorded on the future
+/// and a warning is logged if an eager first poll held the event loop for longer than its
+/// `slow_callback_duration`.
+#[allow(clippy::too_many_arguments)]
+fn spawn_into_future<S, F, C>(
+    py: Python,
+    target: S::Target,
+    event_loop: &PyAny,
+    future: PyObject,
+    origin: &'static Location<'static>,
+    start: Start,
+    fut: F,
+    converter: C,
+) -> PyResult<()>
+where
+    S: Spawner,
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+    C: FnOnce(Python, JoinFailure) -> PyErr + Send + 'static,
+{
+    if !event_loop.call_method0("get_debug")?.is_true()? {
+        return spawn_with_completer::<S, _, _, _>(
+            py,
+            target,
+            FutureCompleter(future),
+            start,
+            fut,
+            converter,
+        );
+    }
+
+    record_origin(py, future.as_ref(py), origin)?;
+
+    let started = Instant::now();
+    let result = spawn_with_completer::<S, _, _, _>(
+        py,
+        target,
+        FutureCompleter(future),
+        start,
+        fut,
+        converter,
+    );
+
+    // only an eager first poll runs Rust code while the conversion starts
+    if let Start::Eager = start {
+        warn_if_slow(py, event_loop, origin, started.elapsed())?;
+    }
+
+    result
+}
+
+/// Add the Rust location that created `future` to the traceback asyncio captured in debug mode
+///
+/// This is the traceback shown in "Future exception was never retrieved" and similar messages.
+fn record_origin(py: Python, future: &PyAny, origin: &Location) -> PyResult<()> {
+    let source_traceback = match future.getattr("_source_traceback") {
+        Ok(source_traceback) if !source_traceback.is_none() => source_traceback,
+        // not an asyncio future, or it was created before debug mode was turned on
+        _ => return Ok(()),
+    };
+
+    let kwargs = PyDict::new(py);
+    kwargs.set_item("lookup_line", false)?;
+
+    let frame = py.import("traceback")?.getattr("FrameSummary")?.call(
+        (origin.file(), origin.line(), "<rust future>"),
+        Some(kwargs),
+    )?;
+    source_traceback.call_method1("append", (frame,))?;
+
+    Ok(())
+}
+
+/// Mirror asyncio's slow callback warnings for the eager first poll of a conversion
+fn warn_if_slow(
+    py: Python,
+    event_loop: &PyAny,
+    origin: &Location,
+    elapsed: Duration,
+) -> PyResult<()> {
+    let slow_callback_duration: f64 = event_loop.getattr("slow_callback_duration")?.extract()?;
+
+    if elapsed.as_secs_f64() >= slow_callback_duration {
+        py.import("asyncio.log")?.getattr("logger")?.call_method1(
+            "warning",
+            (
+                "Executing Rust future created at %s took %.3f seconds",
+                origin.to_string(),
+                elapsed.as_secs_f64(),
+            ),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Reports the result of a spawned Rust future to the Python scheduler that is waiting for it
+///
+/// This is the only part of a Rust -> Python conversion that depends on the Python scheduler. The
+/// asyncio implementation resolves an `asyncio.Future` through its event loop, other schedulers
+/// (i.e. Trio) schedule a callback through whatever thread-safe entry point they provide.
+pub(crate) trait Completer: Clone + Send + 'static {
+    /// Schedule the delivery of `result` on the Python scheduler
+    ///
+    /// This may be called from any thread.
+    fn complete(&self, py: Python, result: PyResult<PyObject>) -> PyResult<()>;
+
+    /// Deliver a `result` that was ready before anything could be awaiting it
+    ///
+    /// This is called on the thread that started the conversion. Implementations can skip the
+    /// round trip through the scheduler when that thread is the scheduler's own thread.
+    fn complete_eager(&self, py: Python, result: PyResult<PyObject>) -> PyResult<()> {
+        self.complete(py, result)
+    }
+}
+
+/// Resolves an `asyncio.Future` through the event loop it belongs to
+#[derive(Clone)]
+struct FutureCompleter(PyObject);
+
+impl Completer for FutureCompleter {
+    fn complete(&self, py: Python, result: PyResult<PyObject>) -> PyResult<()> {
+        set_result(py, self.0.as_ref(py), result)
+    }
+
+    fn complete_eager(&self, py: Python, result: PyResult<PyObject>) -> PyResult<()> {
+        set_result_eager(py, self.0.as_ref(py), result)
+    }
+}
+
+/// How [`spawn_with_completer`] starts a Rust future
+#[derive(Clone, Copy)]
+pub(crate) enum Start<'a> {
+    /// Spawn the future onto the runtime
+    Spawn,
+    /// Spawn the future onto the runtime as a task with the given name
+    Named(&'a str),
+    /// Poll the future once on the calling thread and only spawn it if it's still pending
+    Eager,
+}
+
+/// Drive `fut` to completion on `target` and report its result through the `completer`
+pub(crate) fn spawn_with_completer<S, F, C, K>(
+    py: Python,
+    target: S::Target,
+    completer: K,
+    start: Start,
+    fut: F,
+    converter: C,
+) -> PyResult<()>
+where
+    S: Spawner,
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+    C: FnOnce(Python, JoinFailure) -> PyErr + Send + 'static,
+    K: Completer,
+{
+    #[cfg(feature = "tracing")]
+    let fut = crate::tracing::instrument(py, fut);
+
+    let completer_tx = completer.clone();
+    let conversion = stats::Conversion::start(ConversionKind::IntoCoroutine);
+
+    // with an eager start, futures that are ready immediately (i.e. cache hits) don't need to be
+    // spawned at all
+    let mut fut = Box::pin(fut);
+    let first_poll = match start {
+        Start::Spawn | Start::Named(_) => Ok(Poll::Pending),
+        Start::Eager => S::enter(&target, || {
+            gil::poll_with_gil(py, || {
+                panic::catch_unwind(AssertUnwindSafe(|| {
+                    fut.as_mut()
+                        .poll(&mut Context::from_waker(task::noop_waker_ref()))
+                }))
+            })
+        }),
+    };
+
+    match first_poll {
+        Ok(Poll::Pending) => (),
+        Ok(Poll::Ready(result)) => {
+            conversion.finish(&result);
+            return completer.complete_eager(py, result);
+        }
+        Err(payload) => {
+            drop(fut);
+
+            let result = Err(converter(py, JoinFailure::Panic(payload)));
+            conversion.finish(&result);
+            return completer.complete_eager(py, result);
+        }
+    }
+
+    let (task, panicked) = catch_panic(async move {
+        let result = fut.await;
+        conversion.finish(&result);
+
+        Python::with_gil(move |py| {
+            if completer_tx
+                .complete(py, result)
+                .map_err(dump_err(py))
+                .is_err()
+            {
+                // Cancelled
+            }
+        });
+    });
+
+    let name = match start {
+        Start::Named(name) => Some(name.to_string()),
+        Start::Spawn | Start::Eager => None,
+    };
+
+    // the outer task only waits on the inner one to report failures, so it can be detached
+    let outer_target = target.clone();
+    let supervisor = async move {
+        stats::record_spawn(std::any::type_name::<S::Runtime>());
+        let handle = match name.as_ref() {
+            Some(name) => S::spawn_named(&target, name, task),
+            None => S::spawn(&target, task),
+        };
+
+        if let Some(failure) = task_failure(handle, panicked).await {
+            Python::with_gil(move |py| {
+                let err = converter(py, failure);
+
+                if completer
+                    .complete(py, Err(err))
+                    .map_err(dump_err(py))
+                    .is_err()
+                {
+                    // Cancelled
+                }
+            });
+        }
+    };
+
+    drop(S::spawn(&outer_target, supervisor));
+
+    Ok(())
+}
+
+type StartDeferred = Box<dyn FnOnce(Python, &PyAny, PyObject) -> PyResult<()> + Send>;
+
+#[pyclass]
+struct PyStartDeferred {
+    start: Option<StartDeferred>,
+}
+
+#[pymethods]
+impl PyStartDeferred {
+    #[call]
+    pub fn __call__(&mut self, py: Python, event_loop: &PyAny, future: PyObject) -> PyResult<()> {
+        match self.start.take() {
+            Some(start) => start(py, event_loop, future),
+            None => Err(PyRuntimeError::new_err(
+                "coroutine converted from a Rust future was awaited twice",
+            )),
+        }
+    }
+}
+
+/// Convert a Rust Future into a Python coroutine that binds to an event loop when it is awaited
+///
+/// [`into_coroutine`] creates its Python future on the PyO3 Asyncio event loop and spawns the Rust
+/// future immediately, so the coroutine can only be awaited on that loop. The coroutine returned by
+/// this function doesn't pick an event loop until it is awaited: it creates its future on the
+/// running loop (found with `asyncio.get_running_loop`) and only then spawns the Rust future. This
+/// means it can be created on any thread and awaited on any event loop, including loops that were
+/// not initialized by PyO3 Asyncio.
+///
+/// Since nothing runs until the coroutine is awaited, a coroutine that is never awaited simply
+/// drops the Rust future when it is garbage collected.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `fut` - The Rust future to be converted
+#[track_caller]
+pub fn into_coroutine_deferred<R, F>(py: Python, fut: F) -> PyResult<PyObject>
+where
+    R: Runtime,
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    into_coroutine_deferred_on::<R, _>(py, (), fut)
+}
+
+/// Same as [`into_coroutine_deferred`], but the Rust future is spawned onto `target`
+#[track_caller]
+pub(crate) fn into_coroutine_deferred_on<S, F>(
+    py: Python,
+    target: S::Target,
+    fut: F,
+) -> PyResult<PyObject>
+where
+    S: Spawner,
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    let origin = Location::caller();
+
+    await_deferred(
+        py,
+        Box::new(move |py, event_loop, future| {
+            spawn_into_future::<S, _, _>(
+                py,
+                target,
+                event_loop,
+                future,
+                origin,
+                Start::Spawn,
+                fut,
+                convert_join_failure,
+            )
+        }),
+    )
+}
+
+/// Wrap `start` in a coroutine that calls it with the running event loop and a new future of that
+/// loop once awaited
+fn await_deferred(py: Python, start: StartDeferred) -> PyResult<PyObject> {
+    let start = PyStartDeferred { start: Some(start) };
+
+    Ok(py_helpers(py)?
+        .call_method1("await_deferred", (start,))?
+        .into())
+}
+
+#[pyclass]
+struct PyAbortOnCancel {
+    handle: Option<AbortHandle>,
+}
+
+#[pymethods]
+impl PyAbortOnCancel {
+    #[call]
+    pub fn __call__(&mut self, future: &PyAny) -> PyResult<()> {
+        if future.call_method0("cancelled")?.is_true()? {
+            if let Some(handle) = self.handle.take() {
+                handle.abort();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Resolves a Python future once it is dropped, i.e. when the Rust future it guards is gone
+struct SetOnDrop(Option<PyObject>);
+
+impl Drop for SetOnDrop {
+    fn drop(&mut self) {
+        if let Some(future) = self.0.take() {
+            Python::with_gil(|py| {
+                if set_result(py, future.as_ref(py), Ok(py.None()))
+                    .map_err(dump_err(py))
+                    .is_err()
+                {
+                    // Cancelled
+                }
+            });
+        }
+    }
+}
+
+/// Convert a Rust Future into a Python future that aborts the Rust future when it is cancelled
+///
+/// If `finished` is given, it is resolved once the Rust future has been dropped.
+#[track_caller]
+fn into_abortable_coroutine<S, F>(
+    py: Python,
+    target: S::Target,
+    event_loop: Option<&PyAny>,
+    fut: F,
+    finished: Option<PyObject>,
+) -> PyResult<PyObject>
+where
+    S: Spawner,
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    let (handle, registration) = AbortHandle::new_pair();
+
+    let future = spawn_coroutine::<S, _, _>(
+        py,
+        target,
+        event_loop,
+        Start::Spawn,
+        async move {
+            let finished = SetOnDrop(finished);
+            let result = Abortable::new(fut, registration).await;
+            drop(finished);
+
+            match result {
+                Ok(result) => result,
+                // the Python future has already been cancelled, so this result is discarded
+                Err(_) => Python::with_gil(|py| Err(cancelled(py)?)),
+            }
+        },
+        convert_join_failure,
+    )?;
+
+    future.call_method1(
+        py,
+        "add_done_callback",
+        (PyAbortOnCancel {
+            handle: Some(handle),
+        },),
+    )?;
+
+    Ok(future)
+}
+
+/// Convert a Rust Future into a Python coroutine that doesn't outlive its awaiter
+///
+/// Unlike [`into_coroutine`], the Rust future is tied to the lifetime of the returned coroutine:
+/// if the task awaiting the coroutine is cancelled, the Rust future is aborted, and the coroutine
+/// doesn't finish until the Rust future has been dropped. This makes cleanup deterministic - by
+/// the time the awaiting Python code regains control, any resources held by the Rust future have
+/// been released.
+///
+/// The Rust future is spawned immediately, so the returned coroutine should always be awaited.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `fut` - The Rust future to be converted
+#[track_caller]
+pub fn into_coroutine_scoped<R, F>(py: Python, fut: F) -> PyResult<PyObject>
+where
+    R: Runtime,
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    spawn_scoped::<R, _>(py, (), None, fut)
+}
+
+/// Convert a Rust Future into a Python coroutine that belongs to the given event loop and doesn't
+/// outlive its awaiter
+///
+/// Same as [`into_coroutine_scoped`], except the coroutine must be awaited by a task running on
+/// `event_loop` (see [`into_coroutine_with_loop`]).
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `event_loop` - The Python event loop that the coroutine belongs to
+/// * `fut` - The Rust future to be converted
+#[track_caller]
+pub fn into_coroutine_scoped_with_loop<R, F>(
+    py: Python,
+    event_loop: &PyAny,
+    fut: F,
+) -> PyResult<PyObject>
+where
+    R: Runtime,
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    spawn_scoped::<R, _>(py, (), Some(event_loop), fut)
+}
+
+/// Convert `fut` into a coroutine on `event_loop` (see [`new_future`]) that doesn't outlive its
+/// awaiter, spawned onto `target`
+#[track_caller]
+pub(crate) fn spawn_scoped<S, F>(
+    py: Python,
+    target: S::Target,
+    event_loop: Option<&PyAny>,
+    fut: F,
+) -> PyResult<PyObject>
+where
+    S: Spawner,
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    let finished = new_future(py, event_loop)?;
+    let future =
+        into_abortable_coroutine::<S, _>(py, target, event_loop, fut, Some(finished.clone()))?;
+
+    Ok(py_helpers(py)?
+        .call_method1("await_scoped", (future, finished))?
+        .into())
+}
+
+/// Run a Rust Future as a task in a Python `asyncio.TaskGroup`
+///
+/// The future is converted with [`into_coroutine_scoped`] and passed to `task_group.create_task`,
+/// so it takes part in the structured concurrency of the group: the group doesn't exit until the
+/// Rust future has completed, an error returned by the Rust future cancels its siblings, and if
+/// the group is cancelled (i.e. because a sibling failed) the Rust future is aborted and dropped
+/// before the group exits instead of being left running in the background.
+///
+/// `TaskGroup` was added in Python 3.11, but any object with a compatible `create_task` method can
+/// be used.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `task_group` - The entered `asyncio.TaskGroup`
+/// * `fut` - The Rust future to be run in the group
+///
+/// Returns the `asyncio.Task` created by the group.
+#[track_caller]
+pub fn create_task_in_group<R, F>(py: Python, task_group: &PyAny, fut: F) -> PyResult<PyObject>
+where
+    R: Runtime,
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    let coro = into_coroutine_scoped::<R, _>(py, fut)?;
+
+    Ok(task_group.call_method1("create_task", (coro,))?.into())
+}
+
+/// Run a Rust Future as a task in a Python `asyncio.TaskGroup` that runs on the given event loop
+///
+/// Same as [`create_task_in_group`], except the future is converted with
+/// [`into_coroutine_scoped_with_loop`]. `event_loop` must be the event loop that `task_group` was
+/// entered on.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `event_loop` - The Python event loop that the task group runs on
+/// * `task_group` - The entered `asyncio.TaskGroup`
+/// * `fut` - The Rust future to be run in the group
+///
+/// Returns the `asyncio.Task` created by the group.
+#[track_caller]
+pub fn create_task_in_group_with_loop<R, F>(
+    py: Python,
+    event_loop: &PyAny,
+    task_group: &PyAny,
+    fut: F,
+) -> PyResult<PyObject>
+where
+    R: Runtime,
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    let coro = into_coroutine_scoped_with_loop::<R, _>(py, event_loop, fut)?;
+
+    Ok(task_group.call_method1("create_task", (coro,))?.into())
+}
+
+/// A Rust resource that can be used with `async with` in Python
+///
+/// See [`into_async_context_manager`].
+pub trait AsyncContextManager: Send + Sync + 'static {
+    /// Acquire the resource when the `async with` block is entered
+    ///
+    /// The result is what gets bound by `async with ... as value`.
+    fn enter(self: Arc<Self>) -> BoxFuture<'static, PyResult<PyObject>>;
+
+    /// Release the resource when the `async with` block is exited
+    ///
+    /// `err` is the exception raised inside of the block, if any. Returning `true` suppresses it.
+    fn exit(self: Arc<Self>, err: Option<PyErr>) -> BoxFuture<'static, PyResult<bool>>;
+}
+
+/// Converts the futures of the Python-facing wrappers, attributing them to the Rust location that
+/// created the wrapper
+type IntoCoroutine = fn(
+    Python,
+    &'static Location<'static>,
+    BoxFuture<'static, PyResult<PyObject>>,
+) -> PyResult<PyObject>;
+
+/// Same as [`into_coroutine`], but the conversion is attributed to `origin`
+fn into_coroutine_at<R>(
+    py: Python,
+    origin: &'static Location<'static>,
+    fut: BoxFuture<'static, PyResult<PyObject>>,
+) -> PyResult<PyObject>
+where
+    R: Runtime,
+{
+    spawn_coroutine_at::<R, _, _>(
+        py,
+        (),
+        None,
+        origin,
+        Start::Spawn,
+        fut,
+        convert_join_failure,
+    )
+}
+
+#[pyclass]
+struct PyAsyncContextManager {
+    manager: Arc<dyn AsyncContextManager>,
+    into_coroutine: IntoCoroutine,
+    origin: &'static Location<'static>,
+}
+
+#[pymethods]
+impl PyAsyncContextManager {
+    fn __aenter__(&self, py: Python) -> PyResult<PyObject> {
+        (self.into_coroutine)(py, self.origin, self.manager.clone().enter())
+    }
+
+    fn __aexit__(
+        &self,
+        py: Python,
+        _exc_type: &PyAny,
+        exc_value: &PyAny,
+        _traceback: &PyAny,
+    ) -> PyResult<PyObject> {
+        let err = if exc_value.is_none() {
+            None
+        } else {
+            Some(PyErr::from_instance(exc_value))
+        };
+        let exit = self.manager.clone().exit(err);
+
+        (self.into_coroutine)(
+            py,
+            self.origin,
+            Box::pin(async move {
+                let suppress = exit.await?;
+                Ok(Python::with_gil(|py| suppress.into_py(py)))
+            }),
+        )
+    }
+}
+
+/// Expose a Rust resource as a Python async context manager
+///
+/// The returned object implements `__aenter__` and `__aexit__`, which convert the futures returned
+/// by [`AsyncContextManager::enter`] and [`AsyncContextManager::exit`] into coroutines that run on
+/// `R`. This lets resources such as database transactions be used with `async with`:
+///
+/// ```python
+/// async with db.transaction() as tx:
+///     await tx.execute("...")
+/// ```
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `manager` - The Rust resource
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use futures::future::BoxFuture;
+/// use pyo3::prelude::*;
+/// use pyo3_asyncio::generic::AsyncContextManager;
+///
+/// struct Transaction {}
+///
+/// impl AsyncContextManager for Transaction {
+///     fn enter(self: Arc<Self>) -> BoxFuture<'static, PyResult<PyObject>> {
+///         Box::pin(async move {
+///             // begin the transaction
+///             Python::with_gil(|py| Ok(py.None()))
+///         })
+///     }
+///
+///     fn exit(self: Arc<Self>, err: Option<PyErr>) -> BoxFuture<'static, PyResult<bool>> {
+///         Box::pin(async move {
+///             // commit the transaction, or roll it back if the block raised
+///             let _rollback = err.is_some();
+///             Ok(false)
+///         })
+///     }
+/// }
+/// ```
+#[track_caller]
+pub fn into_async_context_manager<R, M>(py: Python, manager: M) -> PyResult<PyObject>
+where
+    R: Runtime,
+    M: AsyncContextManager,
+{
+    let manager = PyAsyncContextManager {
+        manager: Arc::new(manager),
+        into_coroutine: into_coroutine_at::<R>,
+        origin: Location::caller(),
+    };
+
+    Ok(PyCell::new(py, manager)?.to_object(py))
+}
+
+/// Convert a Rust Future into a Python coroutine that binds to the running event loop when it is
+/// awaited, and aborts the Rust future if the awaiting task is cancelled
+///
+/// The conversion is attributed to `origin`.
+fn into_abortable_coroutine_deferred<R, F>(
+    py: Python,
+    origin: &'static Location<'static>,
+    fut: F,
+) -> PyResult<PyObject>
+where
+    R: Runtime,
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    await_deferred(
+        py,
+        Box::new(move |py, event_loop, future| {
+            let (handle, registration) = AbortHandle::new_pair();
+
+            future.call_method1(
+                py,
+                "add_done_callback",
+                (PyAbortOnCancel {
+                    handle: Some(handle),
+                },),
+            )?;
+
+            let fut = async move {
+                match Abortable::new(fut, registration).await {
+                    Ok(result) => result,
+                    // the Python future has already been cancelled, so this result is discarded
+                    Err(_) => Python::with_gil(|py| Err(cancelled(py)?)),
+                }
+            };
+
+            spawn_into_future::<R, _, _>(
+                py,
+                (),
+                event_loop,
+                future,
+                origin,
+                Start::Spawn,
+                fut,
+                convert_join_failure,
+            )
+        }),
+    )
+}
+
+type SharedStream = Arc<futures::lock::Mutex<Option<BoxStream<'static, PyResult<PyObject>>>>>;
+
+/// A Rust `Stream` exposed as a Python async iterator
+///
+/// Unlike a generator that consumes the stream right away, a `PyAsyncIter` can be returned to
+/// Python, stored, and iterated with `async for` later on.
+///
+/// - Each `__anext__` coroutine binds to the event loop that awaits it (see
+///   [`into_coroutine_deferred`]), so the iterator can be consumed from any event loop.
+/// - Cancelling a pending `__anext__` drops the `next()` future of the stream without losing the
+///   iterator: as long as the stream is cancellation-safe, the item is yielded by the following
+///   `__anext__` instead.
+/// - Concurrent `__anext__` calls are served one at a time, in order.
+///
+/// Once the stream is exhausted, every `__anext__` raises `StopAsyncIteration`. Errors yielded by
+/// the stream are raised by `__anext__` without ending the iteration.
+///
+/// By default the stream is only polled when Python asks for the next item, so nothing is buffered
+/// in between. See [`PyAsyncIter::with_config`] to let the stream run ahead of the consumer.
+///
+/// `#[pyclass]` doesn't support generics, so the stream is boxed.
+#[pyclass]
+pub struct PyAsyncIter {
+    stream: SharedStream,
+    into_coroutine: IntoCoroutine,
+    origin: &'static Location<'static>,
+}
+
+impl PyAsyncIter {
+    /// Wrap `stream`, polling it on `R`
+    #[track_caller]
+    pub fn new<R, S>(stream: S) -> Self
+    where
+        R: Runtime,
+        S: Stream<Item = PyResult<PyObject>> + Send + 'static,
+    {
+        Self {
+            stream: Arc::new(futures::lock::Mutex::new(Some(stream.boxed()))),
+            into_coroutine: into_abortable_coroutine_deferred::<R, _>,
+            origin: Location::caller(),
+        }
+    }
+
+    /// Wrap `stream`, pulling it ahead of the Python consumer as a separate task on `R`
+    ///
+    /// Unlike [`PyAsyncIter::new`], which only polls the stream when Python asks for the next
+    /// item, the stream is drained into a buffer as fast as it produces items. `config` bounds
+    /// that buffer.
+    #[track_caller]
+    pub fn with_config<R, S>(stream: S, config: BridgeConfig) -> Self
+    where
+        R: Runtime,
+        S: Stream<Item = PyResult<PyObject>> + Send + 'static,
+    {
+        Self::new::<R, _>(bridge::buffered::<R, _>(
+            stream,
+            config,
+            ConversionKind::IntoCoroutine,
+        ))
+    }
+}
+
+#[pyproto]
+impl PyAsyncProtocol for PyAsyncIter {
+    fn __aiter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+
+    fn __anext__(slf: PyRef<Self>) -> PyResult<Option<PyObject>> {
+        let stream = slf.stream.clone();
+
+        let next = (slf.into_coroutine)(
+            slf.py(),
+            slf.origin,
+            Box::pin(async move { next_item(&mut *stream.lock().await).await }),
+        )?;
+
+        Ok(Some(next))
+    }
+}
+
+/// Convert a Python async iterator into a Rust `Stream`
+///
+/// The iterator is pulled ahead of the Rust consumer as a separate task on `R`, with each
+/// `__anext__` awaited on the PyO3 Asyncio event loop like [`crate::into_future`]. `config` bounds
+/// the items buffered between the two. The stream ends when the iterator raises
+/// `StopAsyncIteration`, or after yielding any other exception the iterator raises. Dropping the
+/// stream stops pulling from the iterator.
+///
+/// # Arguments
+/// * `aiter` - The Python async iterator (or async iterable)
+/// * `config` - The buffering between the iterator and the stream
+pub fn into_stream<R>(
+    aiter: &PyAny,
+    config: BridgeConfig,
+) -> PyResult<impl Stream<Item = PyResult<PyObject>> + Send + 'static>
+where
+    R: Runtime,
+{
+    let items = anext_items(aiter.call_method0("__aiter__")?.into());
+
+    Ok(bridge::buffered::<R, _>(
+        items,
+        config,
+        ConversionKind::IntoFuture,
+    ))
+}
+
+/// Await each `__anext__` of a Python async iterator on the PyO3 Asyncio event loop
+///
+/// The stream ends when the iterator raises `StopAsyncIteration`, or after yielding any other
+/// exception the iterator raises.
+fn anext_items(aiter: PyObject) -> impl Stream<Item = PyResult<PyObject>> + Send + 'static {
+    futures::stream::unfold(Some(aiter), |aiter| async move {
+        let aiter = aiter?;
+
+        let next = Python::with_gil(|py| {
+            crate::into_future(aiter.call_method0(py, "__anext__")?.as_ref(py))
+        });
+
+        let item = match next {
+            Ok(next) => next.await,
+            Err(e) => Err(e),
+        };
+
+        match item {
+            Ok(item) => Some((Ok(item), Some(aiter))),
+            Err(e) => {
+                if Python::with_gil(|py| e.is_instance::<PyStopAsyncIteration>(py)) {
+                    None
+                } else {
+                    Some((Err(e), None))
+                }
+            }
+        }
+    })
+}
+
+/// Get the next item of a shared stream, raising `StopAsyncIteration` once it's exhausted
+async fn next_item(
+    stream: &mut Option<BoxStream<'static, PyResult<PyObject>>>,
+) -> PyResult<PyObject> {
+    let item = match stream.as_mut() {
+        Some(stream) => stream.next().await,
+        None => None,
+    };
+
+    match item {
+        Some(item) => item,
+        None => {
+            // release the stream as soon as it is exhausted
+            *stream = None;
+            Err(PyStopAsyncIteration::new_err(()))
+        }
+    }
+}
+
+type BoxSink = Pin<Box<dyn Sink<PyResult<PyObject>, Error = PyErr> + Send>>;
+
+/// The two halves of a [`PyAsyncGen`], which are locked together so that each `asend` receives
+/// the item produced in response to its own value
+struct AsyncGenState {
+    sink: Option<BoxSink>,
+    stream: Option<BoxStream<'static, PyResult<PyObject>>>,
+}
+
+/// A Rust `Sink` and `Stream` pair exposed as a Python async generator
+///
+/// This extends [`PyAsyncIter`] to request/response protocols. Values sent with `asend(value)` are
+/// passed to the sink as `Ok(value)`, exceptions thrown in with `athrow(exc)` are passed to the
+/// sink as `Err(exc)`, and both then await the next item of the stream as their result. Iterating
+/// with `async for` only takes items from the stream. `aclose()` closes the sink and drops both
+/// halves, after which `asend` and `athrow` raise `StopAsyncIteration`.
+///
+/// Unlike Python async generators, every value passed to `asend` is sent, including the first one
+/// and `None`.
+///
+/// The `__anext__`, `asend`, `athrow` and `aclose` coroutines share the loop affinity and
+/// cancellation behaviour of [`PyAsyncIter`], and are served one at a time, in order. Since each
+/// value is exchanged in lockstep with its response, there's nothing to buffer and no
+/// [`BridgeConfig`].
+#[pyclass]
+pub struct PyAsyncGen {
+    state: Arc<futures::lock::Mutex<AsyncGenState>>,
+    into_coroutine: IntoCoroutine,
+    origin: &'static Location<'static>,
+}
+
+impl PyAsyncGen {
+    /// Wrap `sink` and `stream`, polling them on `R`
+    #[track_caller]
+    pub fn new<R, Si, St>(sink: Si, stream: St) -> Self
+    where
+        R: Runtime,
+        Si: Sink<PyResult<PyObject>, Error = PyErr> + Send + 'static,
+        St: Stream<Item = PyResult<PyObject>> + Send + 'static,
+    {
+        Self {
+            state: Arc::new(futures::lock::Mutex::new(AsyncGenState {
+                sink: Some(Box::pin(sink)),
+                stream: Some(stream.boxed()),
+            })),
+            into_coroutine: into_abortable_coroutine_deferred::<R, _>,
+            origin: Location::caller(),
+        }
+    }
+
+    /// Send `value` into the sink, then wait for the response from the stream
+    fn send(&self, py: Python, value: PyResult<PyObject>) -> PyResult<PyObject> {
+        let state = self.state.clone();
+
+        (self.into_coroutine)(
+            py,
+            self.origin,
+            Box::pin(async move {
+                let mut state = state.lock().await;
+
+                match state.sink.as_mut() {
+                    Some(sink) => sink.send(value).await?,
+                    None => return Err(PyStopAsyncIteration::new_err(())),
+                }
+
+                next_item(&mut state.stream).await
+            }),
+        )
+    }
+}
+
+#[pymethods]
+impl PyAsyncGen {
+    fn asend(&self, py: Python, value: PyObject) -> PyResult<PyObject> {
+        self.send(py, Ok(value))
+    }
+
+    fn athrow(&self, py: Python, exc: &PyAny) -> PyResult<PyObject> {
+        self.send(py, Err(PyErr::from_instance(exc)))
+    }
+
+    fn aclose(&self, py: Python) -> PyResult<PyObject> {
+        let state = self.state.clone();
+
+        (self.into_coroutine)(
+            py,
+            self.origin,
+            Box::pin(async move {
+                let mut state = state.lock().await;
+                state.stream = None;
+
+                if let Some(mut sink) = state.sink.take() {
+                    sink.close().await?;
+                }
+
+                Ok(Python::with_gil(|py| py.None()))
+            }),
+        )
+    }
+}
+
+#[pyproto]
+impl PyAsyncProtocol for PyAsyncGen {
+    fn __aiter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+
+    fn __anext__(slf: PyRef<Self>) -> PyResult<Option<PyObject>> {
+        let state = slf.state.clone();
+
+        let next = (slf.into_coroutine)(
+            slf.py(),
+            slf.origin,
+            Box::pin(async move { next_item(&mut state.lock().await.stream).await }),
+        )?;
+
+        Ok(Some(next))
+    }
+}
@@ -0,0 +1,397 @@
+//! Channels between Python and Rust built on `async-channel`
+//!
+//! Everything in this module is generic over [`Runtime`](crate::generic::Runtime) and only exposes
+//! `async-channel` and `futures` types, so a library can bridge its queues once and run on tokio,
+//! async-std or a custom runtime alike.
+//!
+//! - [`PySender`](crate::generic::channel::PySender) and
+//!   [`PyReceiver`](crate::generic::channel::PyReceiver) expose either half of a channel to Python
+//!   with an `asyncio.Queue`-like interface, while the other half stays in Rust.
+//! - [`into_receiver`](crate::generic::channel::into_receiver) pumps a Python async iterator into a
+//!   channel, which can then be consumed by any number of Rust tasks.
+//! - [`oneshot`](crate::generic::channel::oneshot) and
+//!   [`py_oneshot`](crate::generic::channel::py_oneshot) deliver a single result across the
+//!   boundary.
+//!
+//! The Python-facing coroutines bind to the event loop that awaits them, and cancelling one of them
+//! never loses an item: a cancelled `put` doesn't send its item, and a cancelled `get` leaves the
+//! next item in the channel.
+//!
+//! # Examples
+//!
+//! ```
+//! use futures::StreamExt;
+//! use pyo3::prelude::*;
+//! use pyo3_asyncio::generic::{
+//!     channel::{self, PySender},
+//!     Runtime,
+//! };
+//!
+//! /// Sum the numbers that Python puts into the returned queue until it is closed
+//! fn summer<R: Runtime>(py: Python) -> PyResult<(PySender, PyObject)> {
+//!     let (tx, rx) = async_channel::bounded::<PyObject>(16);
+//!     let (result, total) = channel::oneshot::<R>(py)?;
+//!
+//!     drop(R::spawn(async move {
+//!         let sum = rx
+//!             .map(|item| Python::with_gil(|py| item.extract::<i64>(py)))
+//!             .fold(Ok(0), |sum: PyResult<i64>, item| async move { Ok(sum? + item?) })
+//!             .await;
+//!
+//!         let _ = result.send(sum.map(|sum| Python::with_gil(|py| sum.into_py(py))));
+//!     }));
+//!
+//!     Ok((PySender::new::<R>(tx), total))
+//! }
+//! ```
+
+use std::{future::Future, panic::Location};
+
+use async_channel::{Receiver, Sender, TryRecvError, TrySendError};
+use futures::StreamExt;
+use pyo3::{class::PyAsyncProtocol, exceptions::PyStopAsyncIteration, prelude::*};
+
+use super::{anext_items, into_abortable_coroutine_deferred, IntoCoroutine, Runtime};
+use crate::{state, BridgeConfig, BufferOverflowError, ChannelClosedError, OverflowPolicy};
+
+fn closed() -> PyErr {
+    ChannelClosedError::new_err("the channel is closed")
+}
+
+/// Create an instance of one of the asyncio exceptions
+fn asyncio_err(py: Python, name: &str) -> PyResult<PyErr> {
+    Ok(PyErr::from_instance(
+        state().asyncio.call_method0(py, name)?.as_ref(py),
+    ))
+}
+
+/// The sending half of a channel exposed to Python
+///
+/// Python puts items into the channel and Rust takes them out of the matching
+/// `async_channel::Receiver`. The interface follows the producer side of `asyncio.Queue`:
+///
+/// - `await put(item)` waits for room in the channel
+/// - `put_nowait(item)` raises `asyncio.QueueFull` if there's no room
+/// - `qsize()` and `full()` report on the items waiting in the channel
+///
+/// `close()` closes the whole channel: the Rust receivers take the remaining items and then see
+/// the end of the channel. Once the channel is closed, either by `close()` or because every
+/// receiver was dropped, `put` and `put_nowait` raise [`ChannelClosedError`]. Dropping the
+/// `PySender` doesn't close the channel as long as other senders remain.
+#[pyclass]
+pub struct PySender {
+    tx: Sender<PyObject>,
+    into_coroutine: IntoCoroutine,
+    origin: &'static Location<'static>,
+}
+
+impl PySender {
+    /// Wrap `tx`, waiting for room in the channel on `R`
+    #[track_caller]
+    pub fn new<R>(tx: Sender<PyObject>) -> Self
+    where
+        R: Runtime,
+    {
+        Self {
+            tx,
+            into_coroutine: into_abortable_coroutine_deferred::<R, _>,
+            origin: Location::caller(),
+        }
+    }
+}
+
+#[pymethods]
+impl PySender {
+    fn put(&self, py: Python, item: PyObject) -> PyResult<PyObject> {
+        let tx = self.tx.clone();
+
+        (self.into_coroutine)(
+            py,
+            self.origin,
+            Box::pin(async move {
+                tx.send(item).await.map_err(|_| closed())?;
+                Ok(Python::with_gil(|py| py.None()))
+            }),
+        )
+    }
+
+    fn put_nowait(&self, py: Python, item: PyObject) -> PyResult<()> {
+        match self.tx.try_send(item) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(_)) => Err(asyncio_err(py, "QueueFull")?),
+            Err(TrySendError::Closed(_)) => Err(closed()),
+        }
+    }
+
+    fn qsize(&self) -> usize {
+        self.tx.len()
+    }
+
+    fn full(&self) -> bool {
+        self.tx.is_full()
+    }
+
+    fn close(&self) -> bool {
+        self.tx.close()
+    }
+
+    fn is_closed(&self) -> bool {
+        self.tx.is_closed()
+    }
+}
+
+/// The receiving half of a channel exposed to Python
+///
+/// Rust sends items into the matching `async_channel::Sender` and Python takes them out. The
+/// interface follows the consumer side of `asyncio.Queue`:
+///
+/// - `await get()` waits for the next item
+/// - `get_nowait()` raises `asyncio.QueueEmpty` if there's no item waiting
+/// - `qsize()` and `empty()` report on the items waiting in the channel
+///
+/// Items sent as `Err` are raised by `get` and `get_nowait`. Once the channel is closed and the
+/// remaining items were taken, they raise [`ChannelClosedError`] instead. The receiver is also an
+/// async iterator, which ends when the channel does.
+///
+/// `close()` closes the whole channel, so the Rust senders fail from then on.
+#[pyclass]
+pub struct PyReceiver {
+    rx: Receiver<PyResult<PyObject>>,
+    into_coroutine: IntoCoroutine,
+    origin: &'static Location<'static>,
+}
+
+impl PyReceiver {
+    /// Wrap `rx`, waiting for items on `R`
+    #[track_caller]
+    pub fn new<R>(rx: Receiver<PyResult<PyObject>>) -> Self
+    where
+        R: Runtime,
+    {
+        Self {
+            rx,
+            into_coroutine: into_abortable_coroutine_deferred::<R, _>,
+            origin: Location::caller(),
+        }
+    }
+}
+
+#[pymethods]
+impl PyReceiver {
+    fn get(&self, py: Python) -> PyResult<PyObject> {
+        let rx = self.rx.clone();
+
+        (self.into_coroutine)(
+            py,
+            self.origin,
+            Box::pin(async move { rx.recv().await.map_err(|_| closed())? }),
+        )
+    }
+
+    fn get_nowait(&self, py: Python) -> PyResult<PyObject> {
+        match self.rx.try_recv() {
+            Ok(item) => item,
+            Err(TryRecvError::Empty) => Err(asyncio_err(py, "QueueEmpty")?),
+            Err(TryRecvError::Closed) => Err(closed()),
+        }
+    }
+
+    fn qsize(&self) -> usize {
+        self.rx.len()
+    }
+
+    fn empty(&self) -> bool {
+        self.rx.is_empty()
+    }
+
+    fn close(&self) -> bool {
+        self.rx.close()
+    }
+
+    fn is_closed(&self) -> bool {
+        self.rx.is_closed()
+    }
+}
+
+#[pyproto]
+impl PyAsyncProtocol for PyReceiver {
+    fn __aiter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+
+    fn __anext__(slf: PyRef<Self>) -> PyResult<Option<PyObject>> {
+        let rx = slf.rx.clone();
+
+        let next = (slf.into_coroutine)(
+            slf.py(),
+            slf.origin,
+            Box::pin(async move {
+                rx.recv()
+                    .await
+                    .map_err(|_| PyStopAsyncIteration::new_err(()))?
+            }),
+        )?;
+
+        Ok(Some(next))
+    }
+}
+
+/// Pump a Python async iterator into a channel
+///
+/// Each `__anext__` is awaited on the PyO3 Asyncio event loop like [`crate::into_future`], from a
+/// separate task on `R`. Up to `config.buffer_size` items wait in the channel, and once it's full,
+/// the `config.overflow_policy` decides what happens to the next item:
+///
+/// - [`OverflowPolicy::Block`] waits for room before pulling from the iterator again.
+/// - [`OverflowPolicy::DropOldest`] discards the item that has been waiting the longest.
+/// - [`OverflowPolicy::Error`] stops pulling from the iterator and sends a [`BufferOverflowError`]
+///   once there's room for it, after the items that are already waiting.
+///
+/// The channel is closed once the iterator raises `StopAsyncIteration`, or after sending any other
+/// exception the iterator raises. Dropping every receiver stops pulling from the iterator.
+///
+/// Unlike [`super::into_stream`], the receiver can be cloned to share the items between several
+/// consumers.
+///
+/// # Arguments
+/// * `aiter` - The Python async iterator (or async iterable)
+/// * `config` - The buffering of the channel
+pub fn into_receiver<R>(
+    aiter: &PyAny,
+    config: BridgeConfig,
+) -> PyResult<Receiver<PyResult<PyObject>>>
+where
+    R: Runtime,
+{
+    let items = anext_items(aiter.call_method0("__aiter__")?.into());
+    let (tx, rx) = async_channel::bounded(config.buffer_size.max(1));
+
+    drop(R::spawn(async move {
+        futures::pin_mut!(items);
+
+        while let Some(item) = items.next().await {
+            let sent = match config.overflow_policy {
+                OverflowPolicy::Block => tx.send(item).await.is_ok(),
+                OverflowPolicy::DropOldest => tx.force_send(item).is_ok(),
+                OverflowPolicy::Error => match tx.try_send(item) {
+                    Ok(()) => true,
+                    Err(TrySendError::Full(_)) => {
+                        let _ = tx
+                            .send(Err(BufferOverflowError::new_err(
+                                "the consumer fell behind the producer of a stream bridge",
+                            )))
+                            .await;
+                        false
+                    }
+                    Err(TrySendError::Closed(_)) => false,
+                },
+            };
+
+            if !sent {
+                break;
+            }
+        }
+    }));
+
+    Ok(rx)
+}
+
+/// The Rust half of a [`oneshot`] channel
+#[derive(Debug)]
+pub struct OneshotSender {
+    tx: Sender<PyResult<PyObject>>,
+}
+
+impl OneshotSender {
+    /// Deliver `result` to the Python awaitable
+    ///
+    /// The result is handed back if the awaitable was already dropped.
+    pub fn send(self, result: PyResult<PyObject>) -> Result<(), PyResult<PyObject>> {
+        self.tx.try_send(result).map_err(TrySendError::into_inner)
+    }
+
+    /// Whether the Python awaitable was dropped, so a result would go nowhere
+    pub fn is_closed(&self) -> bool {
+        self.tx.is_closed()
+    }
+}
+
+/// Create a channel that delivers a single result from Rust to a Python awaitable
+///
+/// The awaitable returns the value sent with [`OneshotSender::send`], or raises its error. It
+/// raises [`ChannelClosedError`] if the sender is dropped without sending anything. Like a Python
+/// coroutine, the awaitable is only awaited once, but it can be awaited on any event loop.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+#[track_caller]
+pub fn oneshot<R>(py: Python) -> PyResult<(OneshotSender, PyObject)>
+where
+    R: Runtime,
+{
+    let (tx, rx) = async_channel::bounded(1);
+
+    let awaitable =
+        into_abortable_coroutine_deferred::<R, _>(py, Location::caller(), async move {
+            rx.recv().await.map_err(|_| {
+                ChannelClosedError::new_err("the sender was dropped without sending a result")
+            })?
+        })?;
+
+    Ok((OneshotSender { tx }, awaitable))
+}
+
+/// The Python half of a [`py_oneshot`] channel
+///
+/// Python delivers the result with `set_result(value)` or `set_exception(exc)`, like it would for
+/// an `asyncio.Future`. Both return whether the result was delivered, which is `False` once the
+/// Rust future was dropped, and raise `asyncio.InvalidStateError` if a result was already set.
+#[pyclass]
+pub struct PyOneshotSender {
+    tx: Option<Sender<PyResult<PyObject>>>,
+}
+
+impl PyOneshotSender {
+    fn send(&mut self, py: Python, result: PyResult<PyObject>) -> PyResult<bool> {
+        match self.tx.take() {
+            Some(tx) => Ok(tx.try_send(result).is_ok()),
+            None => Err(asyncio_err(py, "InvalidStateError")?),
+        }
+    }
+}
+
+#[pymethods]
+impl PyOneshotSender {
+    fn set_result(&mut self, py: Python, value: PyObject) -> PyResult<bool> {
+        self.send(py, Ok(value))
+    }
+
+    fn set_exception(&mut self, py: Python, exc: &PyAny) -> PyResult<bool> {
+        self.send(py, Err(PyErr::from_instance(exc)))
+    }
+}
+
+/// Create a channel that delivers a single result from Python to a Rust future
+///
+/// The future completes with the result set on the [`PyOneshotSender`], or with a
+/// [`ChannelClosedError`] if the sender is garbage collected without a result. Unlike
+/// [`crate::into_future`], no event loop is involved, so the sender can be passed to callback-based
+/// Python APIs and completed from any thread.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+pub fn py_oneshot(
+    py: Python,
+) -> PyResult<(
+    Py<PyOneshotSender>,
+    impl Future<Output = PyResult<PyObject>> + Send + 'static,
+)> {
+    let (tx, rx) = async_channel::bounded(1);
+    let sender = Py::new(py, PyOneshotSender { tx: Some(tx) })?;
+
+    Ok((sender, async move {
+        rx.recv().await.map_err(|_| {
+            ChannelClosedError::new_err("the sender was dropped without setting a result")
+        })?
+    }))
+}
@@ -0,0 +1,371 @@
+//! # Conformance Tests for Custom Runtimes
+//!
+//! Every [`Runtime`] has to uphold the same contract for the conversions in [`crate::generic`] to
+//! work: spawned futures run to completion, blocking entry points can drive an event loop, and
+//! aborted futures are dropped. This module packages the
+//! tests PyO3 Asyncio uses to check its own runtimes so a custom [`Runtime`] can be checked the
+//! same way.
+//!
+//! Tests for the optional capabilities of a runtime, like [`SpawnBlockingExt`] or
+//! [`UnwindSafeExt`], are provided
+//! separately so they're only run against runtimes that declare those capabilities. The tests are
+//! regular [`Test`](crate::testing::Test)s, so they can be run with the rest of your tests by the
+//! [`crate::testing`] harness:
+//!
+//! ```no_run
+//! # #[cfg(feature = "async-std-runtime")]
+//! # mod custom {
+//! # use std::{any::Any, future::Future, panic::AssertUnwindSafe};
+//! # use futures::FutureExt;
+//! # use pyo3_asyncio::generic::{JoinError, Runtime};
+//! # pub struct MyCustomJoinError(Box<dyn Any + Send + 'static>);
+//! # impl JoinError for MyCustomJoinError {
+//! #     fn is_panic(&self) -> bool {
+//! #         true
+//! #     }
+//! #     fn into_panic(self) -> Box<dyn Any + Send + 'static> {
+//! #         self.0
+//! #     }
+//! # }
+//! # pub struct MyCustomRuntime;
+//! # impl Runtime for MyCustomRuntime {
+//! #     type JoinError = MyCustomJoinError;
+//! #     type JoinHandle = async_std::task::JoinHandle<Result<(), MyCustomJoinError>>;
+//! #     fn spawn<F>(fut: F) -> Self::JoinHandle
+//! #     where
+//! #         F: Future<Output = ()> + Send + 'static,
+//! #     {
+//! #         async_std::task::spawn(async move {
+//! #             AssertUnwindSafe(fut).catch_unwind().await.map_err(MyCustomJoinError)
+//! #         })
+//! #     }
+//! # }
+//! # }
+//! # #[cfg(feature = "async-std-runtime")]
+//! # use custom::MyCustomRuntime;
+//! #
+//! # #[cfg(all(feature = "async-std-runtime", feature = "attributes"))]
+//! #[pyo3_asyncio::async_std::main]
+//! async fn main() -> pyo3::PyResult<()> {
+//!     use pyo3_asyncio::generic::testing;
+//!
+//!     let tests = testing::tests::<MyCustomRuntime>();
+//!
+//!     pyo3_asyncio::testing::main_with(tests).await
+//! }
+//! # #[cfg(not(all(feature = "async-std-runtime", feature = "attributes")))]
+//! # fn main() {}
+//! ```
+//!
+//! The tests only rely on PyO3 Asyncio and `R` itself, so the harness can be driven by any
+//! runtime, although it's usually simplest to drive it with the one under test.
+
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+use futures::{channel::oneshot, future};
+use pyo3::{prelude::*, types::PyModule};
+
+use crate::{
+    generic::{self, JoinError, JoinFailure, Runtime, SpawnBlockingExt, UnwindSafeExt},
+    testing::Test,
+    yield_now,
+};
+
+const CONFORMANCE_CODE: &str = r#"
+import asyncio
+
+async def cancel_soon(coro):
+    task = asyncio.ensure_future(coro)
+    await asyncio.sleep(0.01)
+    task.cancel()
+
+    try:
+        await task
+    except asyncio.CancelledError:
+        return True
+
+    return False
+"#;
+
+/// All of the conformance tests for `R`, ready to be passed to the test harness
+///
+/// The tests are named after the functions in this module, i.e.
+/// `pyo3_asyncio::generic::testing::test_spawn`.
+pub fn tests<R>() -> Vec<Test>
+where
+    R: Runtime + 'static,
+{
+    vec![
+        test("test_spawn", || Box::pin(test_spawn::<R>())),
+        test("test_spawn_named", || Box::pin(test_spawn_named::<R>())),
+        test("test_into_coroutine", || {
+            Box::pin(test_into_coroutine::<R>())
+        }),
+        test("test_panic_propagation", || {
+            Box::pin(test_panic_propagation::<R>())
+        }),
+        test("test_run_until_complete", || {
+            Box::pin(test_run_until_complete::<R>())
+        }),
+        test("test_cancellation", || Box::pin(test_cancellation::<R>())),
+    ]
+}
+
+type TestFuture = Pin<Box<dyn Future<Output = PyResult<()>> + Send>>;
+
+/// The conformance tests for the [`UnwindSafeExt`] capability of `R`
+pub fn unwind_safe_tests<R>() -> Vec<Test>
+where
+    R: UnwindSafeExt + 'static,
+{
+    vec![test("test_join_handle_panic", || {
+        Box::pin(test_join_handle_panic::<R>())
+    })]
+}
+
+/// The conformance tests for the [`SpawnBlockingExt`] capability of `R`
+pub fn spawn_blocking_tests<R>() -> Vec<Test>
+where
+    R: SpawnBlockingExt + 'static,
+{
+    vec![test("test_run_blocking_py", || {
+        Box::pin(test_run_blocking_py::<R>())
+    })]
+}
+
+fn test(name: &str, test_fn: fn() -> TestFuture) -> Test {
+    Test {
+        name: format!("{}::{}", module_path!(), name),
+        // the harness expects a 'static test fn, so the boxed fn lives for the rest of the program
+        test_fn: Box::leak(Box::new(test_fn)),
+    }
+}
+
+/// Check that [`Runtime::spawn`] runs the future to completion and resolves its `JoinHandle`
+pub async fn test_spawn<R: Runtime>() -> PyResult<()> {
+    let (tx, rx) = oneshot::channel();
+
+    let handle = R::spawn(async move {
+        yield_now().await;
+        tx.send(42).unwrap();
+    });
+
+    assert!(
+        handle.await.is_ok(),
+        "the spawned future failed to complete"
+    );
+    assert_eq!(rx.await, Ok(42));
+
+    Ok(())
+}
+
+/// Check that [`Runtime::spawn_named`] runs the future to completion and resolves its `JoinHandle`
+pub async fn test_spawn_named<R: Runtime>() -> PyResult<()> {
+    let (tx, rx) = oneshot::channel();
+
+    let handle = R::spawn_named("pyo3_asyncio::generic::testing", async move {
+        yield_now().await;
+        tx.send(42).unwrap();
+    });
+
+    assert!(
+        handle.await.is_ok(),
+        "the spawned future failed to complete"
+    );
+    assert_eq!(rx.await, Ok(42));
+
+    Ok(())
+}
+
+/// Check that a future converted with [`generic::into_coroutine`] can be awaited from Rust
+pub async fn test_into_coroutine<R: Runtime>() -> PyResult<()> {
+    let fut = Python::with_gil(|py| {
+        let coro = generic::into_coroutine::<R, _>(py, async move {
+            yield_now().await;
+            Ok(Python::with_gil(|py| 42.into_py(py)))
+        })?;
+
+        crate::into_future(coro.as_ref(py))
+    })?;
+
+    let value = fut.await?;
+    assert_eq!(Python::with_gil(|py| value.extract::<i32>(py))?, 42);
+
+    Ok(())
+}
+
+/// Check that a panic in a spawned future is reported through its `JoinHandle`
+pub async fn test_join_handle_panic<R: UnwindSafeExt>() -> PyResult<()> {
+    let handle = R::spawn(async move {
+        yield_now().await;
+        panic!("conformance panic");
+    });
+
+    match handle.await {
+        Ok(()) => panic!("the panicking future completed"),
+        Err(e) => {
+            assert!(e.is_panic(), "the panic was reported as a cancellation");
+            assert_eq!(
+                JoinFailure::from_join_error(e).panic_message().as_deref(),
+                Some("conformance panic")
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that a panic in a converted future is raised by the coroutine, whether or not `R` catches
+/// it
+pub async fn test_panic_propagation<R: Runtime>() -> PyResult<()> {
+    let fut = Python::with_gil(|py| {
+        let coro = generic::into_coroutine_with_error_converter::<R, _, _>(
+            py,
+            async move {
+                yield_now().await;
+                panic!("conformance panic");
+            },
+            |_py, failure| {
+                pyo3::exceptions::PyRuntimeError::new_err(format!(
+                    "converted: {}",
+                    failure
+                        .panic_message()
+                        .unwrap_or_else(|| "cancelled".to_string())
+                ))
+            },
+        )?;
+
+        crate::into_future(coro.as_ref(py))
+    })?;
+
+    match fut.await {
+        Ok(_) => panic!("the coroutine should have raised an exception"),
+        Err(e) => Python::with_gil(|py| {
+            assert_eq!(
+                e.pvalue(py).to_string(),
+                "converted: conformance panic",
+                "the panic wasn't passed to the error converter"
+            );
+        }),
+    }
+
+    Ok(())
+}
+
+/// Check that [`generic::run_blocking_py`] runs the closure off of the calling thread and reports
+/// its panics
+pub async fn test_run_blocking_py<R: SpawnBlockingExt>() -> PyResult<()> {
+    let caller = thread::current().id();
+
+    let other = generic::run_blocking_py::<R, _, _>(move |_py| Ok(thread::current().id())).await?;
+    assert_ne!(other, caller, "the closure ran on the calling thread");
+
+    let value = generic::run_blocking_py::<R, _, _>(|py| {
+        py.eval("sum(range(10))", None, None)?.extract::<i32>()
+    })
+    .await?;
+    assert_eq!(value, 45);
+
+    let result = generic::run_blocking_py::<R, _, _>(|_py| -> PyResult<()> {
+        panic!("conformance panic");
+    })
+    .await;
+    assert!(result.is_err(), "the panic wasn't reported");
+
+    Ok(())
+}
+
+/// Check that [`generic::run_until_complete_with_loop`] can drive futures spawned on `R`
+///
+/// The event loop is run on a new thread, since the thread running the test might belong to the
+/// runtime.
+pub async fn test_run_until_complete<R: Runtime>() -> PyResult<()> {
+    let (tx, rx) = oneshot::channel();
+
+    thread::spawn(move || {
+        let result = Python::with_gil(|py| -> PyResult<()> {
+            let asyncio = py.import("asyncio")?;
+            let event_loop = crate::new_event_loop(py)?;
+
+            let sleep = PyObject::from(asyncio.call_method1("sleep", (0.01, 1))?);
+            let event_loop_ref = PyObject::from(event_loop);
+
+            let result =
+                generic::run_until_complete_with_loop::<R, _>(py, event_loop, async move {
+                    let (tx, rx) = oneshot::channel();
+                    drop(R::spawn(async move {
+                        yield_now().await;
+                        tx.send(42).unwrap();
+                    }));
+                    assert_eq!(rx.await, Ok(42));
+
+                    let fut = Python::with_gil(|py| {
+                        crate::into_future_with_loop(event_loop_ref.as_ref(py), sleep.as_ref(py))
+                    })?;
+                    let value = fut.await?;
+                    assert_eq!(Python::with_gil(|py| value.extract::<i32>(py))?, 1);
+
+                    Ok(())
+                });
+
+            event_loop.call_method0("close")?;
+
+            result
+        });
+
+        let _ = tx.send(result);
+    });
+
+    rx.await.expect("run_until_complete panicked")
+}
+
+/// Check that an aborted future is dropped once the coroutine awaiting it is cancelled
+pub async fn test_cancellation<R: Runtime>() -> PyResult<()> {
+    struct SetOnDrop(Arc<AtomicBool>);
+
+    impl Drop for SetOnDrop {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    let dropped = Arc::new(AtomicBool::new(false));
+    let guard = SetOnDrop(dropped.clone());
+
+    let fut = Python::with_gil(|py| {
+        let conformance = PyModule::from_code(
+            py,
+            CONFORMANCE_CODE,
+            "pyo3_asyncio/generic_testing.py",
+            "pyo3_asyncio_generic_testing",
+        )?;
+
+        let coro = generic::into_coroutine_scoped::<R, _>(py, async move {
+            let _guard = guard;
+            future::pending::<()>().await;
+            Ok(Python::with_gil(|py| py.None()))
+        })?;
+
+        crate::into_future(conformance.call_method1("cancel_soon", (coro,))?)
+    })?;
+
+    let cancelled = fut.await?;
+    assert!(
+        Python::with_gil(|py| cancelled.extract::<bool>(py))?,
+        "the coroutine wasn't cancelled"
+    );
+    assert!(
+        dropped.load(Ordering::SeqCst),
+        "the future wasn't dropped before the cancelled coroutine returned"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use std::{
+    cell::Cell,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use pyo3::prelude::*;
+
+thread_local! {
+    /// Whether PyO3 Asyncio is polling a future on this thread while holding the GIL
+    static POLLING_WITH_GIL: Cell<bool> = const { Cell::new(false) };
+}
+
+struct RestorePolling(bool);
+
+impl Drop for RestorePolling {
+    fn drop(&mut self) {
+        POLLING_WITH_GIL.with(|polling| polling.set(self.0));
+    }
+}
+
+/// Poll futures inside `f` while holding the GIL, letting [`AwaitReleased`] release it
+pub(crate) fn poll_with_gil<T>(_py: Python, f: impl FnOnce() -> T) -> T {
+    let _restore = RestorePolling(POLLING_WITH_GIL.with(|polling| polling.replace(true)));
+    f()
+}
+
+/// Extension trait for awaiting futures without holding the GIL
+pub trait PyFutureExt: Future {
+    /// Await this future with the GIL released
+    ///
+    /// Holding the GIL across an `.await` is the classic way to deadlock a PyO3 Asyncio program:
+    /// the task keeps the GIL while it waits, and whatever it waits for needs the event loop
+    /// thread, which in turn needs the GIL. `await_released` rules this out at the type level by
+    /// requiring the future to be `Send`, which `Python` tokens, `GILGuard`s and borrowed
+    /// references like `&PyAny` are not, so none of them can be held across one of its `.await`
+    /// points. Acquire the GIL with `Python::with_gil` between `.await`s and keep owned `PyObject`s
+    /// across them instead.
+    ///
+    /// Futures converted with [`crate::generic::into_coroutine_eager`] are polled once on the
+    /// calling thread while it holds the GIL. When that first poll reaches a future wrapped with
+    /// `await_released`, the GIL is released for the duration of its poll, so it can block on
+    /// other threads that need the GIL without deadlocking.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pyo3::prelude::*;
+    /// use pyo3_asyncio::PyFutureExt;
+    ///
+    /// async fn py_sleep(seconds: f32) -> PyResult<()> {
+    ///     let sleep = Python::with_gil(|py| {
+    ///         pyo3_asyncio::into_future(py.import("asyncio")?.call_method1("sleep", (seconds,))?)
+    ///     })?;
+    ///
+    ///     sleep.await_released().await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// Holding a `Python` token across an `.await` doesn't compile:
+    ///
+    /// ```compile_fail
+    /// use pyo3::prelude::*;
+    /// use pyo3_asyncio::PyFutureExt;
+    ///
+    /// fn hold_gil(py: Python<'static>) {
+    ///     let _ = async move {
+    ///         std::future::ready(()).await;
+    ///         py.None()
+    ///     }
+    ///     .await_released();
+    /// }
+    /// ```
+    fn await_released(self) -> AwaitReleased<Self>
+    where
+        Self: Sized + Send,
+        Self::Output: Send,
+    {
+        AwaitReleased {
+            fut: Box::pin(self),
+        }
+    }
+}
+
+impl<F> PyFutureExt for F where F: Future {}
+
+/// A future awaited with the GIL released, see [`PyFutureExt::await_released`]
+pub struct AwaitReleased<F> {
+    fut: Pin<Box<F>>,
+}
+
+impl<F> Future for AwaitReleased<F>
+where
+    F: Future + Send,
+    F::Output: Send,
+{
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // the GIL may have been released since (i.e. by a nested allow_threads)
+        if !POLLING_WITH_GIL.with(|polling| polling.get())
+            || unsafe { pyo3::ffi::PyGILState_Check() } == 0
+        {
+            return self.fut.as_mut().poll(cx);
+        }
+
+        // Context isn't Send, so it's rebuilt from the waker on the other side of allow_threads
+        let waker = cx.waker().clone();
+        let fut = &mut self.fut;
+
+        // SAFETY: the flag is only set by poll_with_gil, which holds a GIL token, and the GIL
+        // hasn't been released since
+        let py = unsafe { Python::assume_gil_acquired() };
+
+        py.allow_threads(move || {
+            let _restore = RestorePolling(POLLING_WITH_GIL.with(|polling| polling.replace(false)));
+            fut.as_mut().poll(&mut Context::from_waker(&waker))
+        })
+    }
+}
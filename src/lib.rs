@@ -37,10 +37,7 @@
 //! ## Features
 //!
 //! Items marked with
-//! <span
-//!   class="module-item stab portability"
-//!   style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"
-//! ><code>attributes</code></span>
+//! <span class="module-item stab portability" style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"><code>attributes</code></span>
 //! are only available when the `attributes` Cargo feature is enabled:
 //!
 //! ```toml
@@ -50,10 +47,7 @@
 //! ```
 //!
 //! Items marked with
-//! <span
-//!   class="module-item stab portability"
-//!   style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"
-//! ><code>async-std-runtime</code></span>
+//! <span class="module-item stab portability" style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"><code>async-std-runtime</code></span>
 //! are only available when the `async-std-runtime` Cargo feature is enabled:
 //!
 //! ```toml
@@ -63,10 +57,7 @@
 //! ```
 //!
 //! Items marked with
-//! <span
-//!   class="module-item stab portability"
-//!   style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"
-//! ><code>tokio-runtime</code></span>
+//! <span class="module-item stab portability" style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"><code>tokio-runtime</code></span>
 //! are only available when the `tokio-runtime` Cargo feature is enabled:
 //!
 //! ```toml
@@ -76,10 +67,17 @@
 //! ```
 //!
 //! Items marked with
-//! <span
-//!   class="module-item stab portability"
-//!   style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"
-//! ><code>testing</code></span>
+//! <span class="module-item stab portability" style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"><code>trio</code></span>
+//! are only available when the `trio` Cargo feature is enabled:
+//!
+//! ```toml
+//! [dependencies.pyo3-asyncio]
+//! version = "0.13.0"
+//! features = ["trio"]
+//! ```
+//!
+//! Items marked with
+//! <span class="module-item stab portability" style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"><code>testing</code></span>
 //! are only available when the `testing` Cargo feature is enabled:
 //!
 //! ```toml
@@ -87,35 +85,169 @@
 //! version = "0.13.0"
 //! features = ["testing"]
 //! ```
+//!
+//! Items marked with
+//! <span class="module-item stab portability" style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"><code>anyhow</code></span>
+//! or
+//! <span class="module-item stab portability" style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"><code>eyre</code></span>
+//! are only available when the `anyhow` or `eyre` Cargo feature is enabled:
+//!
+//! ```toml
+//! [dependencies.pyo3-asyncio]
+//! version = "0.13.0"
+//! features = ["anyhow", "eyre"]
+//! ```
+//!
+//! Items marked with
+//! <span class="module-item stab portability" style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"><code>tracing</code></span>
+//! are only available when the `tracing` Cargo feature is enabled:
+//!
+//! ```toml
+//! [dependencies.pyo3-asyncio]
+//! version = "0.13.0"
+//! features = ["tracing"]
+//! ```
+//!
+//! Items marked with
+//! <span class="module-item stab portability" style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"><code>log</code></span>
+//! are only available when the `log` (or `tracing`) Cargo feature is enabled:
+//!
+//! ```toml
+//! [dependencies.pyo3-asyncio]
+//! version = "0.13.0"
+//! features = ["log"]
+//! ```
+//!
+//! The `tokio-console` Cargo feature names the tasks spawned by the tokio runtime so they can be
+//! identified in [`tokio-console`](https://github.com/tokio-rs/console). Like `tokio-console`
+//! itself, this requires building with `RUSTFLAGS="--cfg tokio_unstable"`:
+//!
+//! ```toml
+//! [dependencies.pyo3-asyncio]
+//! version = "0.13.0"
+//! features = ["tokio-runtime", "tokio-console"]
+//! ```
+//!
+//! ## Stable ABI
+//!
+//! PyO3 Asyncio only uses the Python limited API, so it works with pyo3's `abi3` features. An
+//! extension module can be built once per platform and loaded by every Python version from its
+//! minimum onwards:
+//!
+//! ```toml
+//! [dependencies.pyo3]
+//! version = "0.13.0"
+//! features = ["extension-module", "abi3-py37"]
+//! ```
 
 /// <span class="module-item stab portability" style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"><code>testing</code></span> Utilities for writing PyO3 Asyncio tests
 #[cfg(feature = "testing")]
-#[doc(inline)]
 pub mod testing;
 
 /// <span class="module-item stab portability" style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"><code>async-std-runtime</code></span> PyO3 Asyncio functions specific to the async-std runtime
 #[cfg(feature = "async-std")]
-#[doc(inline)]
 pub mod async_std;
 
 /// <span class="module-item stab portability" style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"><code>tokio-runtime</code></span> PyO3 Asyncio functions specific to the tokio runtime
 #[cfg(feature = "tokio-runtime")]
-#[doc(inline)]
 pub mod tokio;
 
+/// <span class="module-item stab portability" style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"><code>trio</code></span> Conversions between Rust futures and Trio awaitables
+#[cfg(feature = "trio")]
+pub mod trio;
+
+/// <span class="module-item stab portability" style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"><code>anyhow</code></span> Conversions for futures that fail with an `anyhow::Error`
+#[cfg(feature = "anyhow")]
+pub mod anyhow;
+
+/// <span class="module-item stab portability" style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"><code>eyre</code></span> Conversions for futures that fail with an `eyre::Report`
+#[cfg(feature = "eyre")]
+pub mod eyre;
+
 /// Generic implementations of PyO3 Asyncio utilities that can be used for any Rust runtime
 pub mod generic;
 
-use std::future::Future;
+/// Structured concurrency across Rust futures and Python awaitables
+pub mod scope;
+
+mod bridge;
+mod budget;
+mod gil;
+mod stats;
+
+/// <span class="module-item stab portability" style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"><code>log</code></span> Forwarding of asyncio errors to the Rust logging ecosystem
+#[cfg(any(feature = "log", feature = "tracing"))]
+pub mod logging;
+
+/// <span class="module-item stab portability" style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"><code>tracing</code></span> Propagation of `tracing` spans across the Python/Rust boundary
+#[cfg(feature = "tracing")]
+pub mod tracing;
+
+#[cfg(unix)]
+use std::os::unix::io::RawFd;
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
 
 use futures::channel::oneshot;
 use once_cell::sync::OnceCell;
-use pyo3::{exceptions::PyKeyboardInterrupt, prelude::*, PyNativeType};
+use pyo3::{
+    exceptions::{PyKeyboardInterrupt, PyRuntimeError, PyTypeError},
+    prelude::*,
+    AsPyPointer, PyNativeType,
+};
+
+mod exceptions {
+    // create_exception! has no way to attach docs to the type, they're on the re-export instead
+    #![allow(missing_docs)]
+
+    pyo3::create_exception!(
+        pyo3_asyncio,
+        EventLoopClosedError,
+        pyo3::exceptions::PyRuntimeError
+    );
+
+    pyo3::create_exception!(
+        pyo3_asyncio,
+        BufferOverflowError,
+        pyo3::exceptions::PyRuntimeError
+    );
+
+    #[cfg(feature = "async-channel")]
+    pyo3::create_exception!(
+        pyo3_asyncio,
+        ChannelClosedError,
+        pyo3::exceptions::PyRuntimeError
+    );
+}
+
+/// Raised when a conversion can't complete because its Python event loop has been closed
+///
+/// This is a subclass of `RuntimeError`, which asyncio raises for closed event loops as well.
+pub use exceptions::EventLoopClosedError;
+
+/// Raised by a stream bridge when its consumer falls behind and the buffer overflows with
+/// [`OverflowPolicy::Error`]
+pub use exceptions::BufferOverflowError;
+
+/// Raised by the Python side of a [`generic::channel`] once the channel is closed
+#[cfg(feature = "async-channel")]
+pub use exceptions::ChannelClosedError;
 
 /// Re-exported for #[test] attributes
 #[cfg(all(feature = "attributes", feature = "testing"))]
 pub use inventory;
 
+pub use bridge::{BridgeConfig, OverflowPolicy};
+pub use budget::{yield_now, Budget, YieldNow};
+pub use gil::{AwaitReleased, PyFutureExt};
+pub use stats::{set_conversion_observer, stats, ConversionKind, ConversionObserver, Stats};
+
 /// Test README
 #[doc(hidden)]
 pub mod doc_test {
@@ -140,15 +272,197 @@ pub mod doc_test {
 
 const EXPECT_INIT: &str = "PyO3 Asyncio has not been initialized";
 
-static ASYNCIO: OnceCell<PyObject> = OnceCell::new();
-static ENSURE_FUTURE: OnceCell<PyObject> = OnceCell::new();
-static EVENT_LOOP: OnceCell<PyObject> = OnceCell::new();
-static EXECUTOR: OnceCell<PyObject> = OnceCell::new();
-static CALL_SOON: OnceCell<PyObject> = OnceCell::new();
-static CREATE_FUTURE: OnceCell<PyObject> = OnceCell::new();
+/// The Python objects cached by PyO3 Asyncio
+///
+/// The state is created by the first call to [`try_init`] and lives until the end of the process.
+struct State {
+    asyncio: PyObject,
+    ensure_future: PyObject,
+    /// The event loop, which is replaced when PyO3 Asyncio is initialized again after the event
+    /// loop was closed, or when it's refreshed
+    current: Mutex<LoopState>,
+    py_helpers: OnceCell<PyObject>,
+    /// Whether unreachable objects are freed right away (CPython) or only when the garbage
+    /// collector runs (PyPy)
+    refcounted: bool,
+    /// The number of [`with_runtime`] calls that are currently running
+    runtime_depth: AtomicUsize,
+    /// The `contextvars.Context` that callbacks scheduled on the event loop run in, while a
+    /// [`generic::PyRunner`] is running it
+    context: Mutex<Option<PyObject>>,
+}
+
+/// The event loop of PyO3 Asyncio and the objects derived from it
+struct LoopState {
+    event_loop: PyObject,
+    executor: PyObject,
+    create_future: PyObject,
+}
+
+impl State {
+    fn event_loop<'p>(&self, py: Python<'p>) -> &'p PyAny {
+        self.current
+            .lock()
+            .unwrap()
+            .event_loop
+            .clone_ref(py)
+            .into_ref(py)
+    }
+
+    fn executor<'p>(&self, py: Python<'p>) -> &'p PyAny {
+        self.current
+            .lock()
+            .unwrap()
+            .executor
+            .clone_ref(py)
+            .into_ref(py)
+    }
+
+    fn create_future<'p>(&self, py: Python<'p>) -> &'p PyAny {
+        self.current
+            .lock()
+            .unwrap()
+            .create_future
+            .clone_ref(py)
+            .into_ref(py)
+    }
+}
+
+/// The state of PyO3 Asyncio, installed by [`install_state`]
+static STATE: OnceCell<State> = OnceCell::new();
+
+fn try_state() -> Option<&'static State> {
+    STATE.get()
+}
+
+fn state() -> &'static State {
+    try_state().expect(EXPECT_INIT)
+}
+
+const PY_HELPERS_CODE: &str = r#"
+import asyncio
+import concurrent.futures
+import contextvars
+import threading
+
+async def await_scoped(fut, finished):
+    try:
+        return await fut
+    except asyncio.CancelledError:
+        # cancelling fut aborts the Rust future, but don't finish until it has actually been dropped
+        while not finished.done():
+            try:
+                await asyncio.shield(finished)
+            except asyncio.CancelledError:
+                pass
+        raise
+
+async def await_deferred(start):
+    loop = asyncio.get_running_loop()
+    fut = loop.create_future()
+    start(loop, fut)
+    return await fut
+
+class SpawnBlockingExecutor(concurrent.futures.ThreadPoolExecutor):
+    """A concurrent.futures executor backed by the blocking thread pool of a Rust runtime
+
+    asyncio only accepts ThreadPoolExecutors as the default executor, but none of the threads (or
+    any other state) of the base class are ever used.
+    """
+
+    def __init__(self, spawn_blocking):
+        super().__init__(max_workers=1)
+        self._spawn_blocking = spawn_blocking
+        self._submit_lock = threading.Lock()
+        self._closed = False
+        self._pending = set()
+
+    def submit(self, fn, *args, **kwargs):
+        with self._submit_lock:
+            if self._closed:
+                raise RuntimeError("cannot schedule new futures after shutdown")
+
+            future = concurrent.futures.Future()
+            self._pending.add(future)
+            future.add_done_callback(self._discard)
+            self._spawn_blocking(future, fn, args, kwargs)
+
+            return future
+
+    def _discard(self, future):
+        with self._submit_lock:
+            self._pending.discard(future)
+
+    def shutdown(self, wait=True, *, cancel_futures=False):
+        with self._submit_lock:
+            self._closed = True
+            pending = list(self._pending)
+
+        if cancel_futures:
+            for future in pending:
+                future.cancel()
+
+        # the blocking pool is shared with Rust, so only this executor's work is waited for
+        if wait:
+            concurrent.futures.wait(pending)
+
+timeouts = contextvars.ContextVar("pyo3_asyncio_timeouts", default=())
+
+def track_timeouts():
+    # asyncio.timeout was added in Python 3.11, and wait_for is built on it since Python 3.12
+    timeout_type = getattr(asyncio, "Timeout", None)
+    if timeout_type is None or getattr(timeout_type, "_pyo3_asyncio_tracked", False):
+        return
+
+    enter, exit = timeout_type.__aenter__, timeout_type.__aexit__
+
+    async def __aenter__(self):
+        result = await enter(self)
+        # tasks created inside the block copy the context, so remember whose timeout this is
+        timeouts.set(timeouts.get() + ((asyncio.current_task(), self),))
+        return result
+
+    async def __aexit__(self, exc_type, exc, tb):
+        timeouts.set(tuple(entry for entry in timeouts.get() if entry[1] is not self))
+        return await exit(self, exc_type, exc, tb)
+
+    timeout_type.__aenter__ = __aenter__
+    timeout_type.__aexit__ = __aexit__
+    timeout_type._pyo3_asyncio_tracked = True
+
+def deadline_remaining():
+    try:
+        loop = asyncio.get_running_loop()
+    except RuntimeError:
+        return None
+
+    task = asyncio.current_task(loop)
+    whens = [
+        timeout.when()
+        for owner, timeout in timeouts.get()
+        if owner is task and timeout.when() is not None
+    ]
+    return max(min(whens) - loop.time(), 0.0) if whens else None
+"#;
+
+fn ensure_future(py: Python<'_>) -> &PyAny {
+    state().ensure_future.as_ref(py)
+}
 
-fn ensure_future(py: Python) -> &PyAny {
-    ENSURE_FUTURE.get().expect(EXPECT_INIT).as_ref(py)
+/// Python functions that can't be expressed with pyclasses (i.e. `async def` wrappers)
+fn py_helpers(py: Python<'_>) -> PyResult<&PyAny> {
+    Ok(state()
+        .py_helpers
+        .get_or_try_init(|| -> PyResult<PyObject> {
+            Ok(PyModule::from_code(
+                py,
+                PY_HELPERS_CODE,
+                "pyo3_asyncio/helpers.py",
+                "pyo3_asyncio_helpers",
+            )?
+            .into())
+        })?
+        .as_ref(py))
 }
 
 #[allow(clippy::needless_doctest_main)]
@@ -156,6 +470,11 @@ fn ensure_future(py: Python) -> &PyAny {
 ///
 /// This function **_MUST_** be called from the main thread.
 ///
+/// Calls to `with_runtime` can be nested. Only the outermost call closes the event loop, the inner
+/// ones reuse it as-is. Likewise, an event loop that is already running when `with_runtime` is
+/// called belongs to someone else and is left open. Once the event loop has been closed, the next
+/// call starts over with a new one.
+///
 /// # Arguments
 /// * `py` - The current PyO3 GIL guard
 /// * `f` - The function to call in between intialization and finalization
@@ -182,47 +501,218 @@ pub fn with_runtime<F, R>(py: Python, f: F) -> PyResult<R>
 where
     F: FnOnce() -> PyResult<R>,
 {
+    let owns_loop = enter_runtime(py)?;
+
+    let result = (f)();
+    let closed = exit_runtime(py, owns_loop);
+    let result = result?;
+    closed?;
+
+    Ok(result)
+}
+
+/// Initialize PyO3 Asyncio for a [`with_runtime`]-like scope
+///
+/// Returns whether the scope owns the event loop, i.e. it's the outermost scope and the event loop
+/// isn't run by someone else.
+pub(crate) fn enter_runtime(py: Python) -> PyResult<bool> {
     try_init(py)?;
 
-    let result = (f)()?;
+    // nothing may fail once the scope is counted, or it would never be left
+    let running = get_event_loop(py).call_method0("is_running")?.is_true()?;
+    let outermost = state().runtime_depth.fetch_add(1, Ordering::SeqCst) == 0;
+
+    Ok(outermost && !running)
+}
 
-    try_close(py)?;
+/// Leave a scope entered with [`enter_runtime`], closing the event loop if the scope owns it
+pub(crate) fn exit_runtime(py: Python, owns_loop: bool) -> PyResult<()> {
+    state().runtime_depth.fetch_sub(1, Ordering::SeqCst);
 
-    Ok(result)
+    if owns_loop {
+        try_close(py)?;
+    }
+
+    Ok(())
 }
 
 /// Attempt to initialize the Python and Rust event loops
 ///
 /// - Must be called before any other pyo3-asyncio functions.
-/// - Calling `try_init` a second time returns `Ok(())` and does nothing.
-///   > In future versions this may return an `Err`.
+/// - Calling `try_init` a second time returns `Ok(())` and does nothing, unless the event loop has
+///   been closed by [`try_close`] in the meantime. In that case, PyO3 Asyncio is initialized again
+///   with a new event loop.
+///
+/// Subinterpreters aren't supported. PyO3 Asyncio caches its Python objects (the `asyncio` module,
+/// the event loop, etc.) for the whole process, and the pyclasses it is built on are process-wide
+/// types in PyO3, so it must only be used from the main interpreter.
 pub fn try_init(py: Python) -> PyResult<()> {
-    EVENT_LOOP.get_or_try_init(|| -> PyResult<PyObject> {
-        let asyncio = py.import("asyncio")?;
-        let ensure_future = asyncio.getattr("ensure_future")?;
-        let event_loop = asyncio.call_method0("get_event_loop")?;
-        let executor = py
-            .import("concurrent.futures.thread")?
-            .getattr("ThreadPoolExecutor")?
-            .call0()?;
-        event_loop.call_method1("set_default_executor", (executor,))?;
-        let call_soon = event_loop.getattr("call_soon_threadsafe")?;
-        let create_future = event_loop.getattr("create_future")?;
-
-        ASYNCIO.get_or_init(|| asyncio.into());
-        ENSURE_FUTURE.get_or_init(|| ensure_future.into());
-        EXECUTOR.get_or_init(|| executor.into());
-        CALL_SOON.get_or_init(|| call_soon.into());
-        CREATE_FUTURE.get_or_init(|| create_future.into());
-        Ok(event_loop.into())
-    })?;
+    if let Some(state) = try_state() {
+        if !state.event_loop(py).call_method0("is_closed")?.is_true()? {
+            return Ok(());
+        }
+    }
+
+    let asyncio = py.import("asyncio")?;
+    let event_loop = resolve_event_loop(py, asyncio)?;
+    install_state(py, asyncio, event_loop)?;
+
+    Ok(())
+}
+
+/// Find the event loop that PyO3 Asyncio should use on the calling thread
+///
+/// This is the current event loop of the asyncio policy, unless it's closed. Otherwise, a new event
+/// loop is created with [`new_event_loop`] and set as the current event loop.
+fn resolve_event_loop<'p>(py: Python<'p>, asyncio: &'p PyAny) -> PyResult<&'p PyAny> {
+    // before the first initialization, the policy may only hand out the loop it implicitly created
+    // for the main thread, which must not stand in for the factory's loop
+    let current_loop = if EVENT_LOOP_FACTORY.get().is_some() && try_state().is_none() {
+        None
+    } else {
+        asyncio
+            .call_method0("get_event_loop_policy")?
+            .call_method0("get_event_loop")
+            .ok()
+    };
+    match current_loop {
+        Some(event_loop) if !event_loop.call_method0("is_closed")?.is_true()? => Ok(event_loop),
+        // Python 3.12+ fails to create the implicit loop without a calling Python frame, and the
+        // loop set for this thread may have been closed by a previous `try_close`
+        _ => {
+            let event_loop = new_event_loop(py)?;
+            asyncio.call_method1("set_event_loop", (event_loop,))?;
+            Ok(event_loop)
+        }
+    }
+}
+
+/// Make `event_loop` the event loop of PyO3 Asyncio, creating the state on first use
+fn install_state(py: Python, asyncio: &PyAny, event_loop: &PyAny) -> PyResult<()> {
+    let executor = py
+        .import("concurrent.futures.thread")?
+        .getattr("ThreadPoolExecutor")?
+        .call0()?;
+    event_loop.call_method1("set_default_executor", (executor,))?;
+    let current = LoopState {
+        event_loop: event_loop.into(),
+        executor: executor.into(),
+        create_future: event_loop.getattr("create_future")?.into(),
+    };
+
+    if let Some(state) = try_state() {
+        let replaced = std::mem::replace(&mut *state.current.lock().unwrap(), current);
+        // without waiting, the work already submitted to it still runs to completion
+        replaced.executor.call_method1(py, "shutdown", (false,))?;
+        return Ok(());
+    }
+
+    let refcounted = py
+        .import("platform")?
+        .call_method0("python_implementation")?
+        .extract::<&str>()?
+        == "CPython";
+    let state = State {
+        asyncio: asyncio.into(),
+        ensure_future: asyncio.getattr("ensure_future")?.into(),
+        current: Mutex::new(current),
+        py_helpers: OnceCell::new(),
+        refcounted,
+        runtime_depth: AtomicUsize::new(0),
+        context: Mutex::new(None),
+    };
+
+    // the GIL is held, so no other thread can have initialized PyO3 Asyncio in the meantime
+    let _ = STATE.set(state);
+
+    // timeouts entered before the first call to `current_deadline` have to be known as well
+    py_helpers(py)?.call_method0("track_timeouts")?;
 
     Ok(())
 }
 
+/// Point PyO3 Asyncio at the current event loop of the calling thread
+///
+/// PyO3 Asyncio keeps a reference to the event loop it was initialized with. Frameworks that
+/// replace the event loop between requests (i.e. test runners that create a new loop for each test)
+/// leave that reference pointing at a loop that is closed. Conversions never schedule callbacks
+/// onto a closed loop, they fail with an [`EventLoopClosedError`] instead, so call this function
+/// once the new loop is in place to switch over to it.
+///
+/// The new event loop is the current event loop of the asyncio policy for the calling thread. If
+/// that one is closed too, a new event loop is created with [`new_event_loop`] and set as the
+/// current event loop. Conversions that are already in flight complete on the loop they were
+/// started on, while the default executor of the previous loop is shut down.
+///
+/// Returns the new event loop.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+pub fn refresh_event_loop(py: Python<'_>) -> PyResult<&PyAny> {
+    let asyncio = py.import("asyncio")?;
+    let event_loop = resolve_event_loop(py, asyncio)?;
+    install_state(py, asyncio, event_loop)?;
+
+    Ok(event_loop)
+}
+
+/// A function that creates event loops for PyO3 Asyncio, see [`set_event_loop_factory`]
+pub type EventLoopFactory = dyn Fn(Python) -> PyResult<PyObject> + Send + Sync;
+
+static EVENT_LOOP_FACTORY: OnceCell<Box<EventLoopFactory>> = OnceCell::new();
+
+/// Set the function used to create event loops
+///
+/// By default, [`try_init`] reuses the event loop that the asyncio policy hands out for the
+/// calling thread and only creates one with `asyncio.new_event_loop` if there's none or it's
+/// closed. Once a factory is registered, every event loop PyO3 Asyncio needs is created with it
+/// instead: by [`try_init`] and everything built on it ([`with_runtime`], [`generic::PyRunner`],
+/// the `#[main]` and `#[test]` attributes), by [`refresh_event_loop`], and by [`new_event_loop`].
+/// This lets applications run on uvloop or a custom loop subclass without swapping out the global
+/// asyncio policy.
+///
+/// The first initialization always starts on a loop from the factory. After that, an open event
+/// loop that has been set as the current event loop (i.e. by a test framework) is still picked up
+/// as is, and the factory only replaces loops that are closed.
+///
+/// # Panics
+/// This function will panic if called a second time.
+///
+/// # Examples
+///
+/// ```no_run
+/// use pyo3::prelude::*;
+///
+/// pyo3_asyncio::set_event_loop_factory(|py| {
+///     Ok(py.import("uvloop")?.call_method0("new_event_loop")?.into())
+/// });
+/// ```
+pub fn set_event_loop_factory<F>(factory: F)
+where
+    F: Fn(Python) -> PyResult<PyObject> + Send + Sync + 'static,
+{
+    if EVENT_LOOP_FACTORY.set(Box::new(factory)).is_err() {
+        panic!("PyO3 Asyncio event loop factory has already been set");
+    }
+}
+
+/// Create a new event loop with the factory set by [`set_event_loop_factory`]
+///
+/// Falls back on `asyncio.new_event_loop` if no factory has been set. The event loop isn't set as
+/// the current event loop of any thread.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+pub fn new_event_loop(py: Python<'_>) -> PyResult<&PyAny> {
+    match EVENT_LOOP_FACTORY.get() {
+        Some(factory) => Ok(factory(py)?.into_ref(py)),
+        None => py.import("asyncio")?.call_method0("new_event_loop"),
+    }
+}
+
 /// Get a reference to the Python Event Loop from Rust
-pub fn get_event_loop(py: Python) -> &PyAny {
-    EVENT_LOOP.get().expect(EXPECT_INIT).as_ref(py)
+pub fn get_event_loop(py: Python<'_>) -> &PyAny {
+    state().event_loop(py)
 }
 
 /// Run the event loop forever
@@ -233,6 +723,13 @@ pub fn get_event_loop(py: Python) -> &PyAny {
 /// After this function returns, the event loop can be resumed with either `run_until_complete` or
 /// [`crate::run_forever`]
 ///
+/// A `KeyboardInterrupt` (i.e. CTRL-C) stops the event loop and is not treated as an error. The
+/// `ProactorEventLoop` that is the default on Windows doesn't support `loop.add_signal_handler`, so
+/// this is the portable way to react to CTRL-C. Python 3.8+ is required for the
+/// `ProactorEventLoop` to wake up on CTRL-C while it's waiting for I/O.
+///
+/// Calling this from a thread that is already running an event loop fails with a `RuntimeError`.
+///
 /// # Arguments
 /// * `py` - The current PyO3 GIL guard
 ///
@@ -272,7 +769,33 @@ pub fn get_event_loop(py: Python) -> &PyAny {
 /// # .unwrap();
 /// # })
 pub fn run_forever(py: Python) -> PyResult<()> {
-    if let Err(e) = get_event_loop(py).call_method0("run_forever") {
+    run_loop_forever(py, get_event_loop(py))
+}
+
+/// Refuse to run an event loop on a thread that is already running one
+///
+/// asyncio would raise an error about the event loop itself, which doesn't point at the Rust code
+/// that tried to block the event loop thread.
+fn check_no_running_loop(py: Python, entry_point: &str) -> PyResult<()> {
+    if state()
+        .asyncio
+        .call_method0(py, "_get_running_loop")?
+        .is_none(py)
+    {
+        return Ok(());
+    }
+
+    Err(PyRuntimeError::new_err(format!(
+        "{} can't be called from a thread that is running an event loop, since it would block \
+         that event loop. Await the Rust future with into_coroutine instead",
+        entry_point
+    )))
+}
+
+fn run_loop_forever(py: Python, event_loop: &PyAny) -> PyResult<()> {
+    check_no_running_loop(py, "run_forever")?;
+
+    if let Err(e) = event_loop.call_method0("run_forever") {
         if e.is_instance::<PyKeyboardInterrupt>(py) {
             Ok(())
         } else {
@@ -283,22 +806,156 @@ pub fn run_forever(py: Python) -> PyResult<()> {
     }
 }
 
+/// Stops an event loop from any Rust thread or task
+///
+/// Stopping is graceful: the callbacks that are already scheduled on the event loop still run, then
+/// [`run_forever_with_stop_handle`] returns. The handle can be triggered before the event loop
+/// starts running, in which case it returns after its first iteration. Triggering it more than once
+/// has no further effect.
+///
+/// # Examples
+///
+/// ```
+/// # use std::{thread, time::Duration};
+/// # use pyo3::prelude::*;
+/// # Python::with_gil(|py| {
+/// # pyo3_asyncio::with_runtime(py, || {
+/// let stop = pyo3_asyncio::StopHandle::new(py);
+///
+/// // i.e. a SIGTERM handler
+/// let trigger = stop.clone();
+/// thread::spawn(move || {
+///     thread::sleep(Duration::from_millis(100));
+///     trigger.stop().unwrap();
+/// });
+///
+/// // block until the handle is triggered
+/// pyo3_asyncio::run_forever_with_stop_handle(py, &stop)?;
+/// # Ok(())
+/// # })
+/// # .map_err(|e| e.print_and_set_sys_last_vars(py))
+/// # .unwrap();
+/// # })
+/// ```
+#[derive(Clone)]
+pub struct StopHandle {
+    event_loop: PyObject,
+    stopped: Arc<AtomicBool>,
+}
+
+impl StopHandle {
+    /// Create a stop handle for the PyO3 Asyncio event loop
+    pub fn new(py: Python) -> Self {
+        Self::with_loop(get_event_loop(py))
+    }
+
+    /// Create a stop handle for the given event loop
+    pub fn with_loop(event_loop: &PyAny) -> Self {
+        Self {
+            event_loop: event_loop.into(),
+            stopped: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Ask the event loop to stop once its scheduled callbacks have run
+    ///
+    /// This acquires the GIL, so it must not be called from a thread that is blocked on the event
+    /// loop while holding it.
+    pub fn stop(&self) -> PyResult<()> {
+        if self.stopped.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        Python::with_gil(|py| {
+            let event_loop = self.event_loop.as_ref(py);
+
+            match call_soon_threadsafe(event_loop, (event_loop.getattr("stop")?,)) {
+                // there's nothing left to stop after the event loop has been closed
+                Err(e) if e.is_instance::<EventLoopClosedError>(py) => Ok(()),
+                result => result,
+            }
+        })
+    }
+
+    /// Whether [`StopHandle::stop`] has been called
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::SeqCst)
+    }
+}
+
+/// Run the event loop of `stop` until the handle is triggered
+///
+/// This behaves like [`run_forever`], but the event loop can be stopped from Rust with a
+/// [`StopHandle`] instead of scheduling `loop.stop` through Python.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `stop` - The handle that stops the event loop
+pub fn run_forever_with_stop_handle(py: Python, stop: &StopHandle) -> PyResult<()> {
+    run_loop_forever(py, stop.event_loop.as_ref(py))
+}
+
 /// Shutdown the event loops and perform any necessary cleanup
+///
+/// Before the event loop is closed, its async generators are finalized and the callbacks that are
+/// already scheduled get a chance to run. This matters for the `ProactorEventLoop` (the default on
+/// Windows), where transports that are only cleaned up after the loop has been closed fail with
+/// `RuntimeError: Event loop is closed` during interpreter shutdown.
+///
+/// Closing an event loop that is already closed does nothing.
 pub fn try_close(py: Python) -> PyResult<()> {
+    let event_loop = get_event_loop(py);
+
+    if event_loop.call_method0("is_closed")?.is_true()? {
+        return Ok(());
+    }
+
     // Shutdown the executor and wait until all threads are cleaned up
-    EXECUTOR
-        .get()
-        .expect(EXPECT_INIT)
-        .call_method0(py, "shutdown")?;
+    state().executor(py).call_method0("shutdown")?;
+
+    if !event_loop.call_method0("is_running")?.is_true()? {
+        event_loop.call_method1(
+            "run_until_complete",
+            (event_loop.call_method0("shutdown_asyncgens")?,),
+        )?;
+        // connection_lost callbacks of transports closed by the async generators
+        event_loop.call_method1(
+            "run_until_complete",
+            (state().asyncio.call_method1(py, "sleep", (0,))?,),
+        )?;
+    }
+
+    if !state().refcounted {
+        // tasks that were dropped without completing still hold the senders of their Rust futures
+        // until they are collected, so collect them while the Rust runtime can still observe it
+        py.import("gc")?.call_method0("collect")?;
+    }
 
-    get_event_loop(py).call_method0("stop")?;
-    get_event_loop(py).call_method0("close")?;
+    event_loop.call_method0("stop")?;
+    event_loop.call_method0("close")?;
     Ok(())
 }
 
+/// Delivers the result of a Python task to the Rust future awaiting it
+///
+/// This is called by the task's done callback, so the result can be extracted while the GIL is
+/// still held.
+type ResultSender = Box<dyn FnOnce(PyResult<&PyAny>) + Send>;
+
+fn result_sender<T>(tx: oneshot::Sender<PyResult<T>>) -> ResultSender
+where
+    T: for<'p> FromPyObject<'p> + Send + 'static,
+{
+    Box::new(move |result| {
+        if tx.send(result.and_then(|value| value.extract())).is_err() {
+            // cancellation is not an error
+        }
+    })
+}
+
 #[pyclass]
 struct PyTaskCompleter {
-    tx: Option<oneshot::Sender<PyResult<PyObject>>>,
+    tx: Option<ResultSender>,
 }
 
 #[pymethods]
@@ -308,29 +965,66 @@ impl PyTaskCompleter {
     pub fn __call__(&mut self, task: &PyAny) -> PyResult<()> {
         debug_assert!(task.call_method0("done")?.extract()?);
 
-        let result = match task.call_method0("result") {
-            Ok(val) => Ok(val.into()),
-            Err(e) => Err(e),
-        };
+        let result = task.call_method0("result");
 
         // unclear to me whether or not this should be a panic or silent error.
         //
         // calling PyTaskCompleter twice should not be possible, but I don't think it really hurts
         // anything if it happens.
         if let Some(tx) = self.tx.take() {
-            if tx.send(result).is_err() {
-                // cancellation is not an error
-            }
+            tx(result);
         }
 
         Ok(())
     }
 }
 
+/// Shared reference to the Python task created for an awaitable by [`into_future`]
+///
+/// The slot is filled on the event loop thread when the task is created. Since callbacks scheduled
+/// with `call_soon_threadsafe` run in order, a [`PyCancelTask`] scheduled after the
+/// [`PyEnsureFuture`] always finds the task in the slot.
+#[derive(Clone, Default)]
+struct TaskSlot(Arc<Mutex<Option<PyObject>>>);
+
+impl TaskSlot {
+    /// Cancel the Python task from any thread
+    fn cancel(&self, py: Python) -> PyResult<()> {
+        let cancel = PyCancelTask { task: self.clone() };
+
+        match call_soon_threadsafe(get_event_loop(py), (cancel,)) {
+            // the task can't run anymore, so there's nothing left to cancel
+            Err(e) if e.is_instance::<EventLoopClosedError>(py) => Ok(()),
+            result => result,
+        }
+    }
+}
+
+#[pyclass]
+struct PyCancelTask {
+    task: TaskSlot,
+}
+
+#[pymethods]
+impl PyCancelTask {
+    #[call]
+    pub fn __call__(&mut self) -> PyResult<()> {
+        Python::with_gil(|py| {
+            if let Some(task) = self.task.0.lock().unwrap().take() {
+                task.call_method0(py, "cancel")?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
 #[pyclass]
 struct PyEnsureFuture {
     awaitable: PyObject,
-    tx: Option<oneshot::Sender<PyResult<PyObject>>>,
+    tx: Option<ResultSender>,
+    task: Option<TaskSlot>,
+    name: Option<String>,
 }
 
 #[pymethods]
@@ -338,22 +1032,87 @@ impl PyEnsureFuture {
     #[call]
     pub fn __call__(&mut self) -> PyResult<()> {
         Python::with_gil(|py| {
-            let task = ensure_future(py).call1((self.awaitable.as_ref(py),))?;
+            let task = match ensure_future(py).call1((self.awaitable.as_ref(py),)) {
+                Ok(task) => task,
+                Err(e) => {
+                    // report the failure to the Rust future rather than the loop's exception handler
+                    if let Some(tx) = self.tx.take() {
+                        tx(Err(e));
+                    }
+
+                    return Ok(());
+                }
+            };
+            // futures and tasks that are awaited as-is keep their names
+            if task.as_ptr() != self.awaitable.as_ptr() {
+                name_task(task, self.awaitable.as_ref(py), self.name.take())
+                    .map_err(dump_err(py))
+                    .ok();
+            }
+
             let on_complete = PyTaskCompleter { tx: self.tx.take() };
             task.call_method1("add_done_callback", (on_complete,))?;
 
+            if let Some(slot) = self.task.take() {
+                *slot.0.lock().unwrap() = Some(task.into());
+            }
+
             Ok(())
         })
     }
 }
 
-/// Convert a Python `awaitable` into a Rust Future
+/// Name a task created by [`into_future`] after `name`, or after the awaitable it runs
+fn name_task(task: &PyAny, awaitable: &PyAny, name: Option<String>) -> PyResult<()> {
+    // tasks don't have names before Python 3.8, and custom task factories may not create Tasks
+    if !task.hasattr("set_name")? {
+        return Ok(());
+    }
+
+    let name = match name {
+        Some(name) => name,
+        None => {
+            let qualname = match awaitable.getattr("__qualname__") {
+                Ok(qualname) => qualname.str()?.to_string(),
+                Err(_) => awaitable.get_type().name()?.to_string(),
+            };
+
+            format!("pyo3_asyncio::into_future({})", qualname)
+        }
+    };
+
+    task.call_method1("set_name", (name,))?;
+
+    Ok(())
+}
+
+/// Convert a Python `awaitable` into a Rust Future
 ///
 /// This function converts the `awaitable` into a Python Task using `run_coroutine_threadsafe`. A
 /// completion handler sends the result of this Task through a
 /// `futures::channel::oneshot::Sender<PyResult<PyObject>>` and the future returned by this function
 /// simply awaits the result through the `futures::channel::oneshot::Receiver<PyResult<PyObject>>`.
 ///
+/// Any awaitable is accepted: coroutines, `asyncio.Future`s and `Task`s (which are awaited as-is
+/// rather than wrapped in a new task), and objects that only implement `__await__`. Passing an
+/// object that isn't awaitable raises a `TypeError` immediately instead of failing later on the
+/// event loop.
+///
+/// The awaitable never starts running inside `into_future` itself, even when it is called from the
+/// event loop thread. It is scheduled with `call_soon_threadsafe` and wrapped in a task by
+/// `ensure_future` once the event loop gets to it. With an eager task factory installed (i.e.
+/// `asyncio.eager_task_factory` in Python 3.12+), the awaitable runs until its first suspension
+/// point at that moment and may already be finished when the task is created. This is supported:
+/// the result is delivered to the Rust future either way.
+///
+/// Tasks created for the conversion are named `pyo3_asyncio::into_future(<qualname>)` after the
+/// awaitable, see [`into_future_named`] to choose the name instead.
+///
+/// If the Python task is destroyed without ever completing (i.e. it was waiting on a future that
+/// nothing else references), the Rust future resolves with `asyncio.CancelledError`. Note that
+/// this happens when the task is freed: right away on CPython unless it's part of a reference
+/// cycle, but only once the garbage collector runs on PyPy.
+///
 /// # Arguments
 /// * `awaitable` - The Python `awaitable` to be converted
 ///
@@ -396,33 +1155,961 @@ impl PyEnsureFuture {
 /// }
 /// ```
 pub fn into_future(awaitable: &PyAny) -> PyResult<impl Future<Output = PyResult<PyObject>> + Send> {
-    let py = awaitable.py();
+    into_future_with_task(get_event_loop(awaitable.py()), awaitable, None, None)
+}
+
+/// Convert a Python `awaitable` into a Rust Future that runs on the given event loop
+///
+/// Same as [`into_future`], except the awaitable is scheduled on `event_loop` instead of the PyO3
+/// Asyncio event loop. The event loop must be running (or run later) on some thread for the
+/// future to complete.
+///
+/// # Arguments
+/// * `event_loop` - The Python event loop that the awaitable should run on
+/// * `awaitable` - The Python `awaitable` to be converted
+pub fn into_future_with_loop(
+    event_loop: &PyAny,
+    awaitable: &PyAny,
+) -> PyResult<impl Future<Output = PyResult<PyObject>> + Send> {
+    into_future_with_task(event_loop, awaitable, None, None)
+}
+
+/// Convert a Python `awaitable` into a Rust Future, naming the Python task that runs it
+///
+/// Same as [`into_future`], except the task is named `name` with `Task.set_name` instead of the
+/// name derived from the awaitable, so it can be told apart in `asyncio.all_tasks()` dumps and
+/// task reprs. The name is only set on tasks created for the conversion: futures and tasks are
+/// awaited as-is and keep their names.
+///
+/// # Arguments
+/// * `awaitable` - The Python `awaitable` to be converted
+/// * `name` - The name of the Python task
+pub fn into_future_named(
+    awaitable: &PyAny,
+    name: &str,
+) -> PyResult<impl Future<Output = PyResult<PyObject>> + Send> {
+    into_future_with_task(
+        get_event_loop(awaitable.py()),
+        awaitable,
+        None,
+        Some(name.to_string()),
+    )
+}
+
+/// Convert a Python `awaitable` into a Rust Future that runs on the given event loop, naming the
+/// Python task that runs it
+///
+/// Same as [`into_future_named`], except the awaitable is scheduled on `event_loop` (see
+/// [`into_future_with_loop`]).
+///
+/// # Arguments
+/// * `event_loop` - The Python event loop that the awaitable should run on
+/// * `awaitable` - The Python `awaitable` to be converted
+/// * `name` - The name of the Python task
+pub fn into_future_named_with_loop(
+    event_loop: &PyAny,
+    awaitable: &PyAny,
+    name: &str,
+) -> PyResult<impl Future<Output = PyResult<PyObject>> + Send> {
+    into_future_with_task(event_loop, awaitable, None, Some(name.to_string()))
+}
+
+/// Convert a Python `awaitable` into a Rust Future that resolves with the extracted result
+///
+/// Same as [`into_future`], except the result of the awaitable is extracted into `T` by the
+/// task's done callback, which already holds the GIL. This saves a `Python::with_gil` block and
+/// a round trip through `PyObject` when the caller only wants the Rust value. If the extraction
+/// fails, the future resolves with the extraction error.
+///
+/// # Arguments
+/// * `awaitable` - The Python `awaitable` to be converted
+///
+/// # Examples
+///
+/// ```
+/// use pyo3::prelude::*;
+///
+/// async fn py_sleep_with_result(seconds: f32) -> PyResult<String> {
+///     Python::with_gil(|py| {
+///         pyo3_asyncio::into_future_of::<String>(
+///             py.import("asyncio")?.call_method1("sleep", (seconds, "done"))?,
+///         )
+///     })?
+///     .await
+/// }
+/// ```
+pub fn into_future_of<T>(awaitable: &PyAny) -> PyResult<impl Future<Output = PyResult<T>> + Send>
+where
+    T: for<'p> FromPyObject<'p> + Send + 'static,
+{
+    into_future_with_task(get_event_loop(awaitable.py()), awaitable, None, None)
+}
+
+/// Convert a Python `awaitable` into a Rust Future that runs on the given event loop and resolves
+/// with the extracted result
+///
+/// Same as [`into_future_of`], except the awaitable is scheduled on `event_loop` (see
+/// [`into_future_with_loop`]).
+///
+/// # Arguments
+/// * `event_loop` - The Python event loop that the awaitable should run on
+/// * `awaitable` - The Python `awaitable` to be converted
+pub fn into_future_of_with_loop<T>(
+    event_loop: &PyAny,
+    awaitable: &PyAny,
+) -> PyResult<impl Future<Output = PyResult<T>> + Send>
+where
+    T: for<'p> FromPyObject<'p> + Send + 'static,
+{
+    into_future_with_task(event_loop, awaitable, None, None)
+}
+
+/// Get the name of the asyncio task running on the current thread
+///
+/// This surfaces Python task names on the Rust side, i.e. to label logs or spans with the task a
+/// Rust function was called from. Returns `None` if no event loop is running on this thread, if it
+/// isn't running a task at the moment, or if tasks don't have names (Python 3.7).
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+pub fn current_task_name(py: Python) -> PyResult<Option<String>> {
+    let asyncio = state().asyncio.as_ref(py);
+
+    if asyncio.call_method0("_get_running_loop")?.is_none() {
+        return Ok(None);
+    }
+
+    let task = asyncio.call_method0("current_task")?;
+    if task.is_none() || !task.hasattr("get_name")? {
+        return Ok(None);
+    }
+
+    task.call_method0("get_name")?.extract()
+}
+
+/// Get the deadline of the asyncio task running on the current thread
+///
+/// When Python code awaits a Rust function inside `asyncio.timeout(...)` or `asyncio.wait_for`,
+/// the Rust future is cancelled from the outside once the timeout expires, wherever it happens to
+/// be. Reading the deadline while the task is still running (i.e. at the top of a `#[pyfunction]`,
+/// before converting the Rust future) lets the Rust code set matching timeouts on its own calls,
+/// so it can give up cleanly instead of being aborted mid-operation. If several timeouts are
+/// nested, the earliest one is returned, and a timeout that is rescheduled reports its new
+/// deadline.
+///
+/// asyncio doesn't keep track of the timeouts around a task, so PyO3 Asyncio wraps
+/// `asyncio.Timeout.__aenter__` and `__aexit__` when it's initialized to remember the `Timeout`s
+/// each task has entered, and reads their `when()`. This covers `asyncio.timeout` and
+/// `asyncio.timeout_at` on Python 3.11+, and `asyncio.wait_for` on Python 3.12+, where it's built
+/// on `asyncio.timeout`. Since only public `Timeout` methods are involved, it works on any event
+/// loop, uvloop included.
+///
+/// Returns `None` if no event loop is running on this thread, if it isn't running a task at the
+/// moment, or if the task isn't inside a timeout. Before Python 3.11, there's no `asyncio.Timeout`
+/// to read, so this always returns `None`.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use pyo3::prelude::*;
+///
+/// /// Sleep for up to `secs`, but stop early if the caller's timeout expires first
+/// # #[cfg(feature = "tokio-runtime")]
+/// #[pyfunction]
+/// fn bounded_sleep(py: Python, secs: u64) -> PyResult<PyObject> {
+///     let deadline = pyo3_asyncio::current_deadline(py)?;
+///
+///     pyo3_asyncio::tokio::into_coroutine(py, async move {
+///         let sleep = tokio::time::sleep(Duration::from_secs(secs));
+///
+///         match deadline {
+///             Some(deadline) => {
+///                 let _ = tokio::time::timeout_at(deadline.into(), sleep).await;
+///             }
+///             None => sleep.await,
+///         }
+///
+///         Python::with_gil(|py| Ok(py.None()))
+///     })
+/// }
+/// ```
+pub fn current_deadline(py: Python) -> PyResult<Option<Instant>> {
+    let remaining: Option<f64> = py_helpers(py)?
+        .call_method0("deadline_remaining")?
+        .extract()?;
+
+    Ok(remaining.map(|remaining| Instant::now() + Duration::from_secs_f64(remaining)))
+}
+
+/// Call a Python coroutine function and convert its coroutine into a Rust Future
+///
+/// This is shorthand for calling `callable` with the GIL held, checking that the result is
+/// awaitable, and passing it to [`into_future`]. Errors raised by the call itself are returned by
+/// the future as well, so there's a single place to handle them.
+///
+/// # Arguments
+/// * `_py` - The current PyO3 GIL guard, which the call requires
+/// * `callable` - The Python coroutine function (or any callable returning an awaitable)
+/// * `args` - The positional arguments for the call
+/// * `kwargs` - The keyword arguments for the call
+///
+/// # Examples
+///
+/// ```
+/// use pyo3::{prelude::*, types::PyDict};
+///
+/// async fn py_sleep(seconds: f32) -> PyResult<()> {
+///     let sleep = Python::with_gil(|py| -> PyResult<_> {
+///         let asyncio = py.import("asyncio")?;
+///         let kwargs = PyDict::new(py);
+///         kwargs.set_item("result", "done")?;
+///
+///         Ok(pyo3_asyncio::call_async(
+///             py,
+///             asyncio.getattr("sleep")?,
+///             (seconds,),
+///             Some(kwargs),
+///         ))
+///     })?;
+///
+///     sleep.await?;
+///     Ok(())
+/// }
+/// ```
+pub fn call_async(
+    _py: Python,
+    callable: &PyAny,
+    args: impl IntoPy<Py<pyo3::types::PyTuple>>,
+    kwargs: Option<&pyo3::types::PyDict>,
+) -> impl Future<Output = PyResult<PyObject>> + Send {
+    let fut = callable.call(args, kwargs).and_then(into_future);
+
+    async move { fut?.await }
+}
+
+/// Fail with a `TypeError` unless `awaitable` can be awaited
+pub(crate) fn ensure_awaitable(awaitable: &PyAny) -> PyResult<()> {
+    let awaitable_ok = awaitable
+        .py()
+        .import("inspect")?
+        .call_method1("isawaitable", (awaitable,))?
+        .is_true()?;
+
+    if !awaitable_ok {
+        return Err(PyTypeError::new_err(format!(
+            "object {} can't be converted into a Rust future because it is not awaitable",
+            awaitable.get_type().name()?
+        )));
+    }
+
+    Ok(())
+}
+
+fn into_future_with_task<T>(
+    event_loop: &PyAny,
+    awaitable: &PyAny,
+    task: Option<TaskSlot>,
+    name: Option<String>,
+) -> PyResult<impl Future<Output = PyResult<T>> + Send>
+where
+    T: for<'p> FromPyObject<'p> + Send + 'static,
+{
+    ensure_awaitable(awaitable)?;
+
     let (tx, rx) = oneshot::channel();
+    let conversion = stats::Conversion::start(ConversionKind::IntoFuture);
 
-    CALL_SOON.get().expect(EXPECT_INIT).call1(
-        py,
+    call_soon_threadsafe(
+        event_loop,
         (PyEnsureFuture {
             awaitable: awaitable.into(),
+            tx: Some(result_sender(tx)),
+            task,
+            name,
+        },),
+    )?;
+    let event_loop = PyObject::from(event_loop);
+
+    Ok(async move {
+        let result = match rx.await {
+            Ok(item) => item,
+            Err(_) => {
+                // the task was destroyed without calling its done callbacks
+                stats::record_unresolved_py_future();
+                Python::with_gil(|py| {
+                    if is_closed(event_loop.as_ref(py)) {
+                        Err(event_loop_closed())
+                    } else {
+                        Err(cancelled(py)?)
+                    }
+                })
+            }
+        };
+
+        conversion.finish(&result);
+        result
+    })
+}
+
+fn cancelled(py: Python<'_>) -> PyResult<PyErr> {
+    Ok(PyErr::from_instance(
+        state()
+            .asyncio
+            .call_method0(py, "CancelledError")?
+            .as_ref(py),
+    ))
+}
+
+fn is_cancelled_err(py: Python, err: &PyErr) -> bool {
+    // conversions for other schedulers (i.e. Trio) don't require asyncio to be initialized
+    try_state()
+        .and_then(|state| state.asyncio.getattr(py, "CancelledError").ok())
+        .map(|exc| err.matches(py, exc))
+        .unwrap_or(false)
+}
+
+fn is_closed(event_loop: &PyAny) -> bool {
+    event_loop
+        .call_method0("is_closed")
+        .and_then(|closed| closed.is_true())
+        .unwrap_or(false)
+}
+
+/// Create a future on the PyO3 Asyncio event loop
+///
+/// Fails with an [`EventLoopClosedError`] if the event loop is closed, since nothing could ever
+/// complete the future.
+pub(crate) fn create_future(py: Python) -> PyResult<PyObject> {
+    let state = state();
+
+    if is_closed(state.event_loop(py)) {
+        return Err(EventLoopClosedError::new_err(
+            "the PyO3 Asyncio event loop is closed, see pyo3_asyncio::refresh_event_loop",
+        ));
+    }
+
+    Ok(state.create_future(py).call0()?.into())
+}
+
+fn event_loop_closed() -> PyErr {
+    EventLoopClosedError::new_err(
+        "the Python event loop was closed before the conversion completed",
+    )
+}
+
+/// Call `call_soon_threadsafe` with `args` (the callback and its arguments), failing cleanly if the
+/// event loop is closed
+fn call_soon_threadsafe(
+    event_loop: &PyAny,
+    args: impl IntoPy<Py<pyo3::types::PyTuple>>,
+) -> PyResult<()> {
+    if is_closed(event_loop) {
+        return Err(event_loop_closed());
+    }
+
+    // the returned handle must not end up in the GIL pool (which may live as long as the program
+    // when this runs inside the first poll of `into_coroutine_eager`), or closing the event loop
+    // can't free the callback
+    let py = event_loop.py();
+    let scheduled = match scheduling_context(event_loop) {
+        Some(context) => {
+            let kwargs = pyo3::types::PyDict::new(py);
+            kwargs.set_item("context", context)?;
+
+            PyObject::from(event_loop).call_method(py, "call_soon_threadsafe", args, Some(kwargs))
+        }
+        None => PyObject::from(event_loop).call_method1(py, "call_soon_threadsafe", args),
+    };
+
+    match scheduled {
+        Ok(_) => Ok(()),
+        // the event loop was closed by another thread in the meantime
+        Err(_) if is_closed(event_loop) => Err(event_loop_closed()),
+        Err(e) => Err(e),
+    }
+}
+
+/// The context that callbacks scheduled on `event_loop` should run in, see [`State::context`]
+fn scheduling_context(event_loop: &PyAny) -> Option<PyObject> {
+    let state = try_state()?;
+
+    if state.event_loop(event_loop.py()).as_ptr() != event_loop.as_ptr() {
+        return None;
+    }
+
+    state.context.lock().unwrap().clone()
+}
+
+/// Run `f` with `context` as the context of callbacks scheduled on the PyO3 Asyncio event loop
+pub(crate) fn with_scheduling_context<T>(context: &PyAny, f: impl FnOnce() -> T) -> T {
+    struct Restore(&'static State, Option<PyObject>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            *self.0.context.lock().unwrap() = self.1.take();
+        }
+    }
+
+    let state = state();
+    let prev = state.context.lock().unwrap().replace(context.into());
+    let _restore = Restore(state, prev);
+
+    f()
+}
+
+/// Well-known categories of exceptions raised by Python awaitables
+///
+/// Matching on a `PyErr` from Rust usually means comparing exception types under the GIL. This enum
+/// sorts the errors that are most relevant to async control flow into variants, so Rust code can
+/// decide whether to retry, cancel, or propagate without touching Python again. The original
+/// `PyErr` is kept in every variant.
+///
+/// # Examples
+///
+/// ```
+/// use pyo3::prelude::*;
+/// use pyo3_asyncio::AwaitError;
+///
+/// async fn sleep_or_bail(awaitable: PyObject) -> PyResult<Option<PyObject>> {
+///     let fut = Python::with_gil(|py| pyo3_asyncio::into_future(awaitable.as_ref(py)))?;
+///
+///     match fut.await {
+///         Ok(val) => Ok(Some(val)),
+///         Err(e) => match Python::with_gil(|py| AwaitError::from_py_err(py, e)) {
+///             // treat cancellation as an early exit rather than an error
+///             AwaitError::Cancelled(_) => Ok(None),
+///             e => Err(e.into()),
+///         },
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub enum AwaitError {
+    /// The awaitable raised an `asyncio.CancelledError`
+    Cancelled(PyErr),
+    /// The awaitable raised a `TimeoutError` or an `asyncio.TimeoutError`
+    Timeout(PyErr),
+    /// The awaitable raised a `StopAsyncIteration`
+    StopAsyncIteration(PyErr),
+    /// The event loop of the awaitable was closed before it completed (see [`EventLoopClosedError`])
+    EventLoopClosed(PyErr),
+    /// The awaitable raised any other exception
+    Other(PyErr),
+}
+
+impl AwaitError {
+    /// Sort `err` into one of the well-known categories
+    ///
+    /// # Arguments
+    /// * `py` - The current PyO3 GIL guard
+    /// * `err` - The error raised by the Python awaitable
+    pub fn from_py_err(py: Python, err: PyErr) -> Self {
+        let asyncio = state().asyncio.as_ref(py);
+        let asyncio_err = |name| asyncio.getattr(name).map(|exc| err.matches(py, exc));
+
+        if is_cancelled_err(py, &err) {
+            AwaitError::Cancelled(err)
+        } else if err.is_instance::<pyo3::exceptions::PyTimeoutError>(py)
+            || asyncio_err("TimeoutError").unwrap_or(false)
+        {
+            AwaitError::Timeout(err)
+        } else if err.is_instance::<pyo3::exceptions::PyStopAsyncIteration>(py) {
+            AwaitError::StopAsyncIteration(err)
+        } else if err.is_instance::<EventLoopClosedError>(py) {
+            AwaitError::EventLoopClosed(err)
+        } else {
+            AwaitError::Other(err)
+        }
+    }
+
+    /// Get the original `PyErr` back
+    pub fn into_inner(self) -> PyErr {
+        match self {
+            AwaitError::Cancelled(e)
+            | AwaitError::Timeout(e)
+            | AwaitError::StopAsyncIteration(e)
+            | AwaitError::EventLoopClosed(e)
+            | AwaitError::Other(e) => e,
+        }
+    }
+}
+
+impl From<AwaitError> for PyErr {
+    fn from(e: AwaitError) -> Self {
+        e.into_inner()
+    }
+}
+
+/// A Python async generator driven from Rust
+///
+/// Where [`into_future`] only awaits a single result, an `AsyncGenerator` maps a Python async
+/// generator onto its `asend`, `athrow` and `aclose` methods so that Rust can take part in
+/// request/response streaming protocols. Each step is scheduled on the PyO3 Asyncio event loop
+/// like any other awaitable, and resolves to `None` once the generator has finished.
+///
+/// See [`generic::PyAsyncGen`] for the other direction.
+///
+/// # Examples
+///
+/// ```
+/// use pyo3::prelude::*;
+///
+/// const PYTHON_CODE: &'static str = r#"
+/// async def doubler():
+///     value = yield
+///     while True:
+///         value = yield value * 2
+/// "#;
+///
+/// async fn double_all(values: Vec<i32>) -> PyResult<Vec<i32>> {
+///     let agen = Python::with_gil(|py| -> PyResult<_> {
+///         let module = PyModule::from_code(py, PYTHON_CODE, "doubler.py", "doubler")?;
+///         pyo3_asyncio::AsyncGenerator::new(module.call_method0("doubler")?)
+///     })?;
+///
+///     // start the generator
+///     Python::with_gil(|py| agen.asend(py, py.None()))?.await?;
+///
+///     let mut doubled = vec![];
+///
+///     for value in values {
+///         let result = Python::with_gil(|py| agen.asend(py, value.into_py(py)))?.await?;
+///
+///         if let Some(result) = result {
+///             doubled.push(Python::with_gil(|py| result.extract(py))?);
+///         }
+///     }
+///
+///     Python::with_gil(|py| agen.aclose(py))?.await?;
+///
+///     Ok(doubled)
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct AsyncGenerator {
+    agen: PyObject,
+}
+
+impl AsyncGenerator {
+    /// Wrap a Python async generator
+    ///
+    /// # Arguments
+    /// * `agen` - An object implementing the async generator protocol
+    pub fn new(agen: &PyAny) -> PyResult<Self> {
+        for method in &["asend", "athrow", "aclose"] {
+            if !agen.hasattr(method)? {
+                return Err(PyTypeError::new_err(format!(
+                    "object {} is not an async generator because it has no {} method",
+                    agen.get_type().name()?,
+                    method
+                )));
+            }
+        }
+
+        Ok(Self { agen: agen.into() })
+    }
+
+    /// Send `value` into the generator and wait for the value it yields next
+    ///
+    /// As in Python, the first value sent into a fresh generator must be `None`.
+    pub fn asend(
+        &self,
+        py: Python,
+        value: PyObject,
+    ) -> PyResult<impl Future<Output = PyResult<Option<PyObject>>> + Send> {
+        Self::step(self.agen.call_method1(py, "asend", (value,))?.as_ref(py))
+    }
+
+    /// Raise `err` inside the generator and wait for the value it yields next
+    pub fn athrow(
+        &self,
+        py: Python,
+        err: PyErr,
+    ) -> PyResult<impl Future<Output = PyResult<Option<PyObject>>> + Send> {
+        Self::step(
+            self.agen
+                .call_method1(py, "athrow", (err.instance(py),))?
+                .as_ref(py),
+        )
+    }
+
+    /// Close the generator, running its cleanup code
+    pub fn aclose(&self, py: Python) -> PyResult<impl Future<Output = PyResult<()>> + Send> {
+        let close = into_future(self.agen.call_method0(py, "aclose")?.as_ref(py))?;
+
+        Ok(async move { close.await.map(|_| ()) })
+    }
+
+    fn step(
+        awaitable: &PyAny,
+    ) -> PyResult<impl Future<Output = PyResult<Option<PyObject>>> + Send> {
+        let step = into_future(awaitable)?;
+
+        Ok(async move {
+            match step.await {
+                Ok(value) => Ok(Some(value)),
+                Err(e) => Python::with_gil(|py| {
+                    if e.is_instance::<pyo3::exceptions::PyStopAsyncIteration>(py) {
+                        Ok(None)
+                    } else {
+                        Err(e)
+                    }
+                }),
+            }
+        })
+    }
+}
+
+#[cfg(unix)]
+#[derive(Clone, Copy)]
+enum FdInterest {
+    Read,
+    Write,
+}
+
+#[cfg(unix)]
+impl FdInterest {
+    fn add_method(self) -> &'static str {
+        match self {
+            FdInterest::Read => "add_reader",
+            FdInterest::Write => "add_writer",
+        }
+    }
+
+    fn remove_method(self) -> &'static str {
+        match self {
+            FdInterest::Read => "remove_reader",
+            FdInterest::Write => "remove_writer",
+        }
+    }
+}
+
+#[cfg(unix)]
+#[pyclass]
+struct PyFdReady {
+    event_loop: PyObject,
+    fd: RawFd,
+    interest: FdInterest,
+    watching: Arc<AtomicBool>,
+    tx: Option<oneshot::Sender<PyResult<()>>>,
+}
+
+#[cfg(unix)]
+#[pymethods]
+impl PyFdReady {
+    #[call]
+    pub fn __call__(&mut self) -> PyResult<()> {
+        Python::with_gil(|py| {
+            // the watcher is one-shot, so stop watching the fd as soon as it fires
+            self.watching.store(false, Ordering::SeqCst);
+            self.event_loop
+                .as_ref(py)
+                .call_method1(self.interest.remove_method(), (self.fd,))?;
+
+            if let Some(tx) = self.tx.take() {
+                if tx.send(Ok(())).is_err() {
+                    // cancellation is not an error
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(unix)]
+#[pyclass]
+struct PyAddFdWatcher {
+    event_loop: PyObject,
+    fd: RawFd,
+    interest: FdInterest,
+    watching: Arc<AtomicBool>,
+    tx: Option<oneshot::Sender<PyResult<()>>>,
+}
+
+#[cfg(unix)]
+#[pymethods]
+impl PyAddFdWatcher {
+    #[call]
+    pub fn __call__(&mut self) -> PyResult<()> {
+        Python::with_gil(|py| {
+            let on_ready = PyFdReady {
+                event_loop: self.event_loop.clone_ref(py),
+                fd: self.fd,
+                interest: self.interest,
+                watching: self.watching.clone(),
+                tx: None,
+            };
+            let on_ready = PyCell::new(py, on_ready)?;
+
+            let added = self
+                .event_loop
+                .as_ref(py)
+                .call_method1(self.interest.add_method(), (self.fd, on_ready));
+
+            match added {
+                Ok(_) => {
+                    self.watching.store(true, Ordering::SeqCst);
+                    on_ready.borrow_mut().tx = self.tx.take();
+                }
+                Err(e) => {
+                    // report registration errors (bad fd, unsupported loop) to the awaiting side
+                    if let Some(tx) = self.tx.take() {
+                        if tx.send(Err(e)).is_err() {
+                            // cancellation is not an error
+                        }
+                    }
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(unix)]
+#[pyclass]
+struct PyRemoveFdWatcher {
+    event_loop: PyObject,
+    fd: RawFd,
+    interest: FdInterest,
+    watching: Arc<AtomicBool>,
+}
+
+#[cfg(unix)]
+#[pymethods]
+impl PyRemoveFdWatcher {
+    #[call]
+    pub fn __call__(&self) -> PyResult<()> {
+        // only remove the watcher if it's still ours, the fd may have fired in the meantime
+        if self.watching.swap(false, Ordering::SeqCst) {
+            Python::with_gil(|py| {
+                self.event_loop
+                    .as_ref(py)
+                    .call_method1(self.interest.remove_method(), (self.fd,))?;
+                PyResult::Ok(())
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Removes the watcher from the event loop if the future is dropped before the fd is ready
+#[cfg(unix)]
+struct FdWatchGuard {
+    event_loop: PyObject,
+    fd: RawFd,
+    interest: FdInterest,
+    watching: Arc<AtomicBool>,
+    done: bool,
+}
+
+#[cfg(unix)]
+impl FdWatchGuard {
+    fn disarm(&mut self) {
+        self.done = true;
+    }
+}
+
+#[cfg(unix)]
+impl Drop for FdWatchGuard {
+    fn drop(&mut self) {
+        if self.done {
+            return;
+        }
+
+        Python::with_gil(|py| {
+            let remove = PyRemoveFdWatcher {
+                event_loop: self.event_loop.clone_ref(py),
+                fd: self.fd,
+                interest: self.interest,
+                watching: self.watching.clone(),
+            };
+
+            // once the loop is closed there's no watcher left to remove
+            if let Err(e) = call_soon_threadsafe(self.event_loop.as_ref(py), (remove,)) {
+                if !e.is_instance::<EventLoopClosedError>(py) {
+                    dump_err(py)(e);
+                }
+            }
+        });
+    }
+}
+
+#[cfg(unix)]
+fn watch_fd(
+    py: Python,
+    fd: RawFd,
+    interest: FdInterest,
+) -> PyResult<impl Future<Output = PyResult<()>> + Send> {
+    let (tx, rx) = oneshot::channel();
+    let event_loop = get_event_loop(py);
+    let watching = Arc::new(AtomicBool::new(false));
+
+    call_soon_threadsafe(
+        event_loop,
+        (PyAddFdWatcher {
+            event_loop: event_loop.into(),
+            fd,
+            interest,
+            watching: watching.clone(),
             tx: Some(tx),
         },),
     )?;
 
+    let mut guard = FdWatchGuard {
+        event_loop: event_loop.into(),
+        fd,
+        interest,
+        watching,
+        done: false,
+    };
+
     Ok(async move {
-        match rx.await {
+        let result = rx.await;
+        guard.disarm();
+
+        match result {
             Ok(item) => item,
-            Err(_) => Python::with_gil(|py| {
-                Err(PyErr::from_instance(
-                    ASYNCIO
-                        .get()
-                        .expect(EXPECT_INIT)
-                        .call_method0(py, "CancelledError")?
-                        .as_ref(py),
-                ))
-            }),
+            Err(_) => Python::with_gil(|py| Err(cancelled(py)?)),
+        }
+    })
+}
+
+/// Wait for the Python event loop to report that `fd` is readable
+///
+/// This registers a one-shot reader on the Python event loop with `loop.add_reader` and returns a
+/// Rust Future that completes the first time the reader fires. The reader is removed again before
+/// the future completes, so the file descriptor can be handed back to the Python side afterwards.
+///
+/// This is useful when a Rust library owns a file descriptor that should be driven by Python's
+/// event loop rather than a Rust reactor. See [`tokio::readable`] for the opposite direction.
+///
+/// Only one reader can be registered for a file descriptor at a time, so this replaces any reader
+/// that was already registered on the Python event loop for `fd`. If the future is dropped before
+/// `fd` is ready, the reader is removed from the event loop again.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `fd` - The raw file descriptor to watch
+///
+/// # Examples
+///
+/// ```
+/// use std::{io::Write, os::unix::{io::AsRawFd, net::UnixStream}};
+///
+/// use pyo3::prelude::*;
+///
+/// async fn wait_for_ping() -> PyResult<()> {
+///     let (mut tx, rx) = UnixStream::pair()?;
+///     tx.write_all(b"ping")?;
+///
+///     Python::with_gil(|py| pyo3_asyncio::add_reader(py, rx.as_raw_fd()))?.await
+/// }
+/// ```
+#[cfg(unix)]
+pub fn add_reader(py: Python, fd: RawFd) -> PyResult<impl Future<Output = PyResult<()>> + Send> {
+    watch_fd(py, fd, FdInterest::Read)
+}
+
+/// Wait for the Python event loop to report that `fd` is writable
+///
+/// This is the `loop.add_writer` counterpart of [`add_reader`].
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `fd` - The raw file descriptor to watch
+#[cfg(unix)]
+pub fn add_writer(py: Python, fd: RawFd) -> PyResult<impl Future<Output = PyResult<()>> + Send> {
+    watch_fd(py, fd, FdInterest::Write)
+}
+
+/// Convert an error chain into a `RuntimeError`
+///
+/// The message of the outermost exception contains the whole chain, and each source of the error
+/// is attached as the `__cause__` of the exception above it. If the root cause of the chain is a
+/// `PyErr`, it is reused as the innermost `__cause__` so the original Python exception isn't lost.
+#[cfg(any(feature = "anyhow", feature = "eyre"))]
+fn error_chain_to_py_err<'a>(
+    message: String,
+    chain: impl Iterator<Item = &'a (dyn std::error::Error + 'static)>,
+) -> PyErr {
+    // the first item in the chain is the error itself, which is covered by `message`
+    let sources: Vec<_> = chain.skip(1).collect();
+
+    Python::with_gil(|py| {
+        let mut cause: Option<PyErr> = None;
+
+        // build the exceptions from the root cause upwards so each one can point at its cause
+        for (i, source) in sources.into_iter().rev().enumerate() {
+            let err = match source.downcast_ref::<PyErr>() {
+                Some(e) if i == 0 => e.clone_ref(py),
+                _ => pyo3::exceptions::PyRuntimeError::new_err(source.to_string()),
+            };
+
+            set_cause(py, &err, cause.take());
+            cause = Some(err);
         }
+
+        let err = pyo3::exceptions::PyRuntimeError::new_err(message);
+        set_cause(py, &err, cause);
+
+        err
     })
 }
 
+/// An error report with a chain of causes, like `anyhow::Error` and `eyre::Report`
+#[cfg(any(feature = "anyhow", feature = "eyre"))]
+trait ErrorReport: std::fmt::Display + Sized {
+    /// Unwrap the report if it wraps a `PyErr`
+    fn downcast_py_err(self) -> Result<PyErr, Self>;
+
+    /// The report itself followed by each of its sources
+    fn chain(&self) -> Vec<&(dyn std::error::Error + 'static)>;
+}
+
+/// Convert an error report into a `PyErr`, see [`error_chain_to_py_err`]
+#[cfg(any(feature = "anyhow", feature = "eyre"))]
+fn report_to_py_err<E: ErrorReport>(err: E) -> PyErr {
+    match err.downcast_py_err() {
+        Ok(err) => err,
+        Err(err) => error_chain_to_py_err(format!("{:#}", err), err.chain().into_iter()),
+    }
+}
+
+/// Adapt a future that fails with an error report so it can be passed to `into_coroutine`
+#[cfg(any(feature = "anyhow", feature = "eyre"))]
+async fn report_to_py_result<F, T, E>(fut: F) -> PyResult<PyObject>
+where
+    F: Future<Output = Result<T, E>>,
+    T: IntoPy<PyObject>,
+    E: ErrorReport,
+{
+    match fut.await {
+        Ok(val) => Ok(Python::with_gil(|py| val.into_py(py))),
+        Err(e) => Err(report_to_py_err(e)),
+    }
+}
+
+#[cfg(any(feature = "anyhow", feature = "eyre"))]
+fn set_cause(py: Python, err: &PyErr, cause: Option<PyErr>) {
+    if let Some(cause) = cause {
+        if err
+            .instance(py)
+            .setattr("__cause__", cause.instance(py))
+            .is_err()
+        {
+            // the cause is only a nicety, the message already contains the whole chain
+        }
+    }
+}
+
 fn dump_err(py: Python<'_>) -> impl FnOnce(PyErr) + '_ {
     move |e| {
         // We can't display Python exceptions via std::fmt::Display,
@@ -0,0 +1,149 @@
+//! Errors that asyncio can't attribute to anyone - an exception in a callback, a task whose
+//! exception was never retrieved, a transport that failed to close - are passed to the event
+//! loop's exception handler, which prints them to `stderr` by default. For applications that embed
+//! Python, `stderr` is often the one place nobody is looking.
+//!
+//! The handler in this module forwards those errors to `tracing` when the `tracing` feature is
+//! enabled, and to `log` otherwise. Every event is emitted at the `ERROR` level with the
+//! `pyo3_asyncio` target, and the contents of the asyncio context are attached as structured
+//! fields:
+//!
+//! - `exception` - the `repr` of the exception, if any
+//! - `traceback` - the formatted traceback of the exception, if any
+//! - `future` - the `repr` of the future or task that failed, if any
+//! - `handle` - the `repr` of the callback handle that failed, if any
+
+use pyo3::{prelude::*, types::PyDict};
+
+use crate::get_event_loop;
+
+struct ExceptionContext {
+    message: String,
+    exception: Option<String>,
+    traceback: Option<String>,
+    future: Option<String>,
+    handle: Option<String>,
+}
+
+impl ExceptionContext {
+    fn from_py(py: Python, context: &PyDict) -> PyResult<Self> {
+        let repr = |key: &str| -> PyResult<Option<String>> {
+            match context.get_item(key) {
+                Some(val) if !val.is_none() => Ok(Some(val.repr()?.to_string())),
+                _ => Ok(None),
+            }
+        };
+
+        let message = match context.get_item("message") {
+            Some(message) => message.str()?.to_string(),
+            None => "Unhandled exception in event loop".to_string(),
+        };
+
+        let traceback = match context.get_item("exception") {
+            Some(exc) if !exc.is_none() => {
+                let lines = py.import("traceback")?.call_method1(
+                    "format_exception",
+                    (exc.get_type(), exc, exc.getattr("__traceback__")?),
+                )?;
+
+                Some(
+                    "".into_py(py)
+                        .call_method1(py, "join", (lines,))?
+                        .extract(py)?,
+                )
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            message,
+            exception: repr("exception")?,
+            traceback,
+            future: repr("future")?.or(repr("task")?),
+            handle: repr("handle")?,
+        })
+    }
+
+    #[cfg(feature = "tracing")]
+    fn emit(&self) {
+        ::tracing::error!(
+            target: "pyo3_asyncio",
+            exception = self.exception.as_deref(),
+            traceback = self.traceback.as_deref(),
+            future = self.future.as_deref(),
+            handle = self.handle.as_deref(),
+            "{}",
+            self.message
+        );
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    fn emit(&self) {
+        ::log::error!(
+            target: "pyo3_asyncio",
+            exception = self.exception.as_deref(),
+            traceback = self.traceback.as_deref(),
+            future = self.future.as_deref(),
+            handle = self.handle.as_deref();
+            "{}",
+            self.message
+        );
+    }
+}
+
+#[pyclass]
+struct PyLogExceptionHandler {}
+
+#[pymethods]
+impl PyLogExceptionHandler {
+    #[call]
+    pub fn __call__(&self, py: Python, event_loop: &PyAny, context: &PyDict) -> PyResult<()> {
+        match ExceptionContext::from_py(py, context) {
+            Ok(context) => {
+                context.emit();
+                Ok(())
+            }
+            // don't lose the original error just because we couldn't format it
+            Err(_) => {
+                event_loop.call_method1("default_exception_handler", (context,))?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Create an asyncio exception handler that forwards errors to the Rust logging ecosystem
+///
+/// The returned object can be installed on any event loop with `loop.set_exception_handler`.
+/// See [`set_exception_handler`] to install it on the PyO3 Asyncio event loop.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+pub fn exception_handler(py: Python) -> PyResult<PyObject> {
+    Ok(PyCell::new(py, PyLogExceptionHandler {})?.into())
+}
+
+/// Forward errors from the PyO3 Asyncio event loop to the Rust logging ecosystem
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+///
+/// # Examples
+///
+/// ```
+/// use pyo3::prelude::*;
+///
+/// Python::with_gil(|py| {
+///     pyo3_asyncio::with_runtime(py, || {
+///         pyo3_asyncio::logging::set_exception_handler(py)?;
+///         Ok(())
+///     })
+///     .map_err(|e| e.print_and_set_sys_last_vars(py))
+///     .unwrap();
+/// });
+/// ```
+pub fn set_exception_handler(py: Python) -> PyResult<()> {
+    get_event_loop(py).call_method1("set_exception_handler", (exception_handler(py)?,))?;
+
+    Ok(())
+}
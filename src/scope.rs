@@ -0,0 +1,142 @@
+use std::{future::Future, pin::Pin};
+
+use futures::{
+    future::{self, Either},
+    stream::{FuturesUnordered, StreamExt},
+};
+use pyo3::{prelude::*, PyNativeType};
+
+use crate::{get_event_loop, into_future_with_task, TaskSlot};
+
+type ScopeTask = Pin<Box<dyn Future<Output = PyResult<()>> + Send>>;
+
+/// A Rust-side mirror of Python's `asyncio.TaskGroup`
+///
+/// Rust futures and Python awaitables can be added to a scope, and [`Scope::join`] waits until all
+/// of them have finished. If any of them fails, the remaining members are cancelled - Rust futures
+/// are dropped and Python tasks are cancelled with `Task.cancel` - and `join` returns the first
+/// error once the cancelled Python tasks have finished. Dropping a scope without joining it
+/// cancels everything that is still running, so no task outlives the scope.
+///
+/// Python awaitables start running on the event loop as soon as they are added to the scope. Rust
+/// futures are driven by the task awaiting [`Scope::join`], so they run concurrently with each
+/// other but not in parallel.
+///
+/// # Examples
+///
+/// ```
+/// use pyo3::prelude::*;
+/// use pyo3_asyncio::scope::Scope;
+///
+/// async fn fetch_all(py_fetch: PyObject) -> PyResult<()> {
+///     let mut scope = Scope::new();
+///
+///     Python::with_gil(|py| scope.spawn_awaitable(py_fetch.call0(py)?.as_ref(py)))?;
+///     scope.spawn(async move {
+///         // if this fails, the Python fetch is cancelled
+///         Ok(())
+///     });
+///
+///     scope.join().await
+/// }
+/// ```
+#[derive(Default)]
+pub struct Scope {
+    rust_tasks: FuturesUnordered<ScopeTask>,
+    py_tasks: FuturesUnordered<ScopeTask>,
+    py_task_slots: Vec<TaskSlot>,
+}
+
+impl Scope {
+    /// Create an empty scope
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a Rust future to the scope
+    ///
+    /// # Arguments
+    /// * `fut` - The Rust future to run in the scope
+    pub fn spawn<F>(&mut self, fut: F)
+    where
+        F: Future<Output = PyResult<()>> + Send + 'static,
+    {
+        self.rust_tasks.push(Box::pin(fut));
+    }
+
+    /// Schedule a Python awaitable on the event loop as a member of the scope
+    ///
+    /// # Arguments
+    /// * `awaitable` - The Python awaitable to run in the scope
+    pub fn spawn_awaitable(&mut self, awaitable: &PyAny) -> PyResult<()> {
+        let slot = TaskSlot::default();
+        let fut = into_future_with_task::<PyObject>(
+            get_event_loop(awaitable.py()),
+            awaitable,
+            Some(slot.clone()),
+            None,
+        )?;
+
+        self.py_task_slots.push(slot);
+        self.py_tasks.push(Box::pin(async move {
+            fut.await?;
+            Ok(())
+        }));
+
+        Ok(())
+    }
+
+    /// Wait for every member of the scope to finish
+    ///
+    /// Returns the first error raised by a member of the scope after cancelling the others.
+    pub async fn join(mut self) -> PyResult<()> {
+        loop {
+            let next = match (self.rust_tasks.is_empty(), self.py_tasks.is_empty()) {
+                (true, true) => {
+                    self.py_task_slots.clear();
+                    return Ok(());
+                }
+                (false, true) => self.rust_tasks.next().await,
+                (true, false) => self.py_tasks.next().await,
+                (false, false) => {
+                    match future::select(self.rust_tasks.next(), self.py_tasks.next()).await {
+                        Either::Left((result, _)) | Either::Right((result, _)) => result,
+                    }
+                }
+            };
+
+            if let Some(Err(e)) = next {
+                self.cancel()?;
+
+                // wait for the Python tasks to acknowledge the cancellation like a TaskGroup would
+                while self.py_tasks.next().await.is_some() {}
+
+                return Err(e);
+            }
+        }
+    }
+
+    fn cancel(&mut self) -> PyResult<()> {
+        self.rust_tasks = FuturesUnordered::new();
+
+        Python::with_gil(|py| {
+            for slot in self.py_task_slots.drain(..) {
+                slot.cancel(py)?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        if !self.py_task_slots.is_empty() {
+            Python::with_gil(|py| {
+                if let Err(e) = self.cancel() {
+                    e.print_and_set_sys_last_vars(py);
+                }
+            });
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use once_cell::sync::{Lazy, OnceCell};
+use pyo3::prelude::*;
+
+use crate::is_cancelled_err;
+
+static PENDING_INTO_COROUTINE: AtomicU64 = AtomicU64::new(0);
+static PENDING_INTO_FUTURE: AtomicU64 = AtomicU64::new(0);
+static DROPPED_INTO_FUTURE: AtomicU64 = AtomicU64::new(0);
+static UNRESOLVED_PY_FUTURES: AtomicU64 = AtomicU64::new(0);
+static SPAWNS: Lazy<Mutex<HashMap<&'static str, u64>>> = Lazy::new(Default::default);
+
+static OBSERVER: OnceCell<Box<dyn ConversionObserver>> = OnceCell::new();
+
+/// A snapshot of the conversions performed by PyO3 Asyncio
+///
+/// The counters are global to the process and are updated with relaxed atomics, so a snapshot
+/// taken while conversions are in flight is only approximately consistent.
+#[derive(Clone, Debug, Default)]
+pub struct Stats {
+    /// Rust futures converted into Python awaitables that haven't completed yet
+    pub pending_into_coroutine: u64,
+    /// Python awaitables converted into Rust futures that haven't completed yet
+    pub pending_into_future: u64,
+    /// Rust futures returned by [`crate::into_future`] that were dropped before the Python
+    /// awaitable resolved
+    pub dropped_into_future: u64,
+    /// Python awaitables whose task was destroyed without ever resolving, i.e. because the event
+    /// loop was closed or stopped while they were still running
+    pub unresolved_py_futures: u64,
+    /// The number of Rust futures spawned for conversions, keyed by the type name of the
+    /// [`crate::generic::Runtime`] that spawned them
+    pub spawns: HashMap<&'static str, u64>,
+}
+
+/// Take a snapshot of the conversions performed by PyO3 Asyncio
+///
+/// Useful for tracking down awaitables that never resolve: a `pending_*` counter that keeps
+/// growing while the application is idle points at conversions that are stuck.
+///
+/// # Examples
+///
+/// ```
+/// let stats = pyo3_asyncio::stats();
+///
+/// println!(
+///     "{} rust futures and {} python awaitables in flight",
+///     stats.pending_into_coroutine, stats.pending_into_future
+/// );
+/// ```
+pub fn stats() -> Stats {
+    Stats {
+        pending_into_coroutine: PENDING_INTO_COROUTINE.load(Ordering::Relaxed),
+        pending_into_future: PENDING_INTO_FUTURE.load(Ordering::Relaxed),
+        dropped_into_future: DROPPED_INTO_FUTURE.load(Ordering::Relaxed),
+        unresolved_py_futures: UNRESOLVED_PY_FUTURES.load(Ordering::Relaxed),
+        spawns: SPAWNS.lock().unwrap().clone(),
+    }
+}
+
+/// The direction of a conversion reported to a [`ConversionObserver`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ConversionKind {
+    /// A Rust future converted into a Python awaitable, i.e. with `into_coroutine`
+    IntoCoroutine,
+    /// A Python awaitable converted into a Rust future with [`crate::into_future`]
+    IntoFuture,
+}
+
+/// Callbacks for the lifecycle of every conversion performed by PyO3 Asyncio
+///
+/// Every conversion is reported to `on_created` once, followed by exactly one of `on_resolved`,
+/// `on_errored`, or `on_cancelled` with the time since it was created. A conversion counts as
+/// cancelled if it failed with an `asyncio.CancelledError` or was dropped before it completed.
+/// Rust futures that panic are reported as errored.
+///
+/// The callbacks are called from whichever thread the conversion happens to be on, sometimes while
+/// holding the GIL, so they should be quick - incrementing a metric is fine, blocking is not.
+///
+/// # Examples
+///
+/// ```
+/// use std::{
+///     sync::atomic::{AtomicU64, Ordering},
+///     time::Duration,
+/// };
+///
+/// use pyo3_asyncio::{ConversionKind, ConversionObserver};
+///
+/// #[derive(Default)]
+/// struct Metrics {
+///     errors: AtomicU64,
+/// }
+///
+/// impl ConversionObserver for Metrics {
+///     fn on_errored(&self, _kind: ConversionKind, _elapsed: Duration) {
+///         self.errors.fetch_add(1, Ordering::Relaxed);
+///     }
+/// }
+///
+/// pyo3_asyncio::set_conversion_observer(Metrics::default());
+/// ```
+pub trait ConversionObserver: Send + Sync + 'static {
+    /// Called when a conversion is created
+    fn on_created(&self, _kind: ConversionKind) {}
+    /// Called when a conversion completes successfully
+    fn on_resolved(&self, _kind: ConversionKind, _elapsed: Duration) {}
+    /// Called when a conversion fails with an exception or a panic
+    fn on_errored(&self, _kind: ConversionKind, _elapsed: Duration) {}
+    /// Called when a conversion is cancelled
+    fn on_cancelled(&self, _kind: ConversionKind, _elapsed: Duration) {}
+}
+
+/// Set the global observer for the lifecycle of conversions
+///
+/// # Panics
+/// This function will panic if called a second time.
+pub fn set_conversion_observer<O: ConversionObserver>(observer: O) {
+    if OBSERVER.set(Box::new(observer)).is_err() {
+        panic!("PyO3 Asyncio conversion observer has already been set");
+    }
+}
+
+pub(crate) fn record_spawn(runtime: &'static str) {
+    *SPAWNS.lock().unwrap().entry(runtime).or_insert(0) += 1;
+}
+
+pub(crate) fn record_unresolved_py_future() {
+    UNRESOLVED_PY_FUTURES.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Tracks a conversion from the moment it is created until it completes or is dropped
+pub(crate) struct Conversion {
+    kind: ConversionKind,
+    start: Instant,
+    finished: bool,
+}
+
+impl Conversion {
+    pub(crate) fn start(kind: ConversionKind) -> Self {
+        pending(kind).fetch_add(1, Ordering::Relaxed);
+
+        if let Some(observer) = OBSERVER.get() {
+            observer.on_created(kind);
+        }
+
+        Self {
+            kind,
+            start: Instant::now(),
+            finished: false,
+        }
+    }
+
+    /// Report the result of the conversion
+    pub(crate) fn finish<T>(mut self, result: &PyResult<T>) {
+        self.finished = true;
+
+        if let Some(observer) = OBSERVER.get() {
+            let elapsed = self.start.elapsed();
+
+            match result {
+                Ok(_) => observer.on_resolved(self.kind, elapsed),
+                Err(e) if Python::with_gil(|py| is_cancelled_err(py, e)) => {
+                    observer.on_cancelled(self.kind, elapsed)
+                }
+                Err(_) => observer.on_errored(self.kind, elapsed),
+            }
+        }
+    }
+}
+
+impl Drop for Conversion {
+    fn drop(&mut self) {
+        pending(self.kind).fetch_sub(1, Ordering::Relaxed);
+
+        if self.finished {
+            return;
+        }
+
+        if self.kind == ConversionKind::IntoFuture {
+            DROPPED_INTO_FUTURE.fetch_add(1, Ordering::Relaxed);
+        }
+
+        if let Some(observer) = OBSERVER.get() {
+            let elapsed = self.start.elapsed();
+
+            // a Rust future that panics drops its state while unwinding
+            if std::thread::panicking() {
+                observer.on_errored(self.kind, elapsed);
+            } else {
+                observer.on_cancelled(self.kind, elapsed);
+            }
+        }
+    }
+}
+
+fn pending(kind: ConversionKind) -> &'static AtomicU64 {
+    match kind {
+        ConversionKind::IntoCoroutine => &PENDING_INTO_COROUTINE,
+        ConversionKind::IntoFuture => &PENDING_INTO_FUTURE,
+    }
+}
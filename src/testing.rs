@@ -55,6 +55,10 @@
 //! # fn main() {}
 //! ```
 //!
+//! The [`pyo3_asyncio::async_std::test_main!`](crate::async_std::test_main) and
+//! [`pyo3_asyncio::tokio::test_main!`](crate::tokio::test_main) macros expand to the same `main`
+//! function, optionally running a setup function shared by all of the tests first.
+//!
 //! ### Cargo Configuration
 //! Next, we need to add our test file to the Cargo manifest. Add the following section to your
 //! `Cargo.toml`
@@ -182,26 +186,21 @@
 //! # fn main() {}
 //! ```
 
-use std::{future::Future, pin::Pin};
+use std::{any::Any, fmt, future::Future, pin::Pin};
 
 use clap::{App, Arg};
 use futures::stream::{self, StreamExt};
-use pyo3::prelude::*;
+use pyo3::{exceptions::PyException, prelude::*};
 
 /// Args that should be provided to the test program
 ///
 /// These args are meant to mirror the default test harness's args.
 /// > Currently only `--filter` is supported.
+#[derive(Default)]
 pub struct Args {
     filter: Option<String>,
 }
 
-impl Default for Args {
-    fn default() -> Self {
-        Self { filter: None }
-    }
-}
-
 /// Parse the test args from the command line
 ///
 /// This should be called at the start of your test harness to give the CLI some
@@ -269,7 +268,92 @@ impl Test {
 
 inventory::collect!(Test);
 
+/// The return types accepted for test functions, like `Termination` for the default test harness
+///
+/// Tests marked with [`#[pyo3_asyncio::async_std::test]`](crate::async_std::test) or
+/// [`#[pyo3_asyncio::tokio::test]`](crate::tokio::test) may return `()` or any `Result<(), E>`
+/// whose error implements `Debug`, so tests written for the default harness can be moved over by
+/// swapping their attribute. Errors other than `PyErr` are reported as an `Exception` with the
+/// `Debug` representation of the error as its message.
+pub trait TestResult {
+    /// Convert the outcome of the test into the result expected by the test harness
+    fn into_test_result(self) -> PyResult<()>;
+}
+
+impl TestResult for () {
+    fn into_test_result(self) -> PyResult<()> {
+        Ok(())
+    }
+}
+
+impl<E> TestResult for Result<(), E>
+where
+    E: fmt::Debug + 'static,
+{
+    fn into_test_result(self) -> PyResult<()> {
+        self.map_err(|e| {
+            let mut e = Some(e);
+
+            match (&mut e as &mut dyn Any).downcast_mut::<Option<PyErr>>() {
+                Some(err) => err.take().unwrap(),
+                None => PyException::new_err(format!("{:?}", e.unwrap())),
+            }
+        })
+    }
+}
+
+/// Skip the running test
+///
+/// Tests that can't run in the current environment (i.e. on a Python version without the feature
+/// under test) should return this error instead of passing without checking anything. The test
+/// harness reports them as ignored, along with the `reason`. Python code called by a test can raise
+/// `unittest.SkipTest` to the same effect.
+///
+/// # Examples
+///
+/// ```
+/// use pyo3::prelude::*;
+///
+/// async fn test_task_group() -> PyResult<()> {
+///     if !Python::with_gil(|py| py.import("asyncio")?.hasattr("TaskGroup"))? {
+///         return Err(pyo3_asyncio::testing::skip("TaskGroup was added in Python 3.11"));
+///     }
+///
+///     // ...
+///     Ok(())
+/// }
+/// ```
+pub fn skip(reason: &str) -> PyErr {
+    Python::with_gil(|py| {
+        match py
+            .import("unittest")
+            .and_then(|unittest| unittest.getattr("SkipTest"))
+        {
+            Ok(skip_test) => match skip_test.downcast() {
+                Ok(skip_test) => PyErr::from_type(skip_test, reason.to_string()),
+                Err(e) => e.into(),
+            },
+            Err(e) => e,
+        }
+    })
+}
+
+/// The reason given for skipping a test, if `err` is a `unittest.SkipTest`
+fn skip_reason(err: &PyErr) -> Option<String> {
+    Python::with_gil(|py| {
+        let skip_test = py.import("unittest").ok()?.getattr("SkipTest").ok()?;
+
+        if err.matches(py, skip_test) {
+            Some(err.pvalue(py).to_string())
+        } else {
+            None
+        }
+    })
+}
+
 /// Run a sequence of tests while applying any necessary filtering from the `Args`
+///
+/// Tests that fail with [`skip`] are reported as ignored.
 pub async fn test_harness(tests: Vec<Test>, args: Args) -> PyResult<()> {
     stream::iter(tests)
         .for_each_concurrent(Some(4), |test| {
@@ -283,7 +367,14 @@ pub async fn test_harness(tests: Vec<Test>, args: Args) -> PyResult<()> {
 
             async move {
                 if !ignore {
-                    test.task().await.unwrap();
+                    let result = test.task().await;
+
+                    if let Some(reason) = result.as_ref().err().and_then(skip_reason) {
+                        println!("test {} ... ignored, {}", test.name, reason);
+                        return;
+                    }
+
+                    result.unwrap();
 
                     println!("test {} ... ok", test.name);
                 }
@@ -314,10 +405,19 @@ pub async fn test_harness(tests: Vec<Test>, args: Args) -> PyResult<()> {
 /// fn main() { }
 /// ```
 pub async fn main() -> PyResult<()> {
+    main_with(Vec::new()).await
+}
+
+/// Same as [`main`], except `tests` are run along with the tests collected from the `inventory`
+/// boilerplate
+///
+/// This is how tests that aren't marked with a `#[test]` attribute, like the conformance tests in
+/// [`crate::generic::testing`], are added to the test harness.
+pub async fn main_with(tests: Vec<Test>) -> PyResult<()> {
     let args = parse_args();
 
     test_harness(
-        inventory::iter::<Test>().map(|test| test.clone()).collect(),
+        inventory::iter::<Test>().cloned().chain(tests).collect(),
         args,
     )
     .await
@@ -1,14 +1,24 @@
-use std::{future::Future, thread};
+use std::{any::Any, cell::Cell, future::Future, panic, thread};
+#[cfg(unix)]
+use std::{io, os::unix::io::RawFd};
 
+#[cfg(unix)]
+use ::tokio::io::{unix::AsyncFd, Interest};
 use ::tokio::{
     runtime::{Builder, Handle, Runtime},
     task,
 };
 use futures::future::pending;
 use once_cell::sync::OnceCell;
-use pyo3::prelude::*;
+use pyo3::{
+    exceptions::PyRuntimeError,
+    prelude::*,
+    types::{PyDict, PyTuple},
+};
+#[cfg(feature = "tokio-util")]
+use tokio_util::sync::CancellationToken;
 
-use crate::generic;
+use crate::{generic, BridgeConfig};
 
 /// <span class="module-item stab portability" style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"><code>attributes</code></span>
 /// re-exports for macros
@@ -30,7 +40,68 @@ pub use pyo3_asyncio_macros::tokio_main as main;
 #[cfg(all(feature = "attributes", feature = "testing"))]
 pub use pyo3_asyncio_macros::tokio_test as test;
 
+/// <span class="module-item stab portability" style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"><code>attributes</code></span>
+/// <span class="module-item stab portability" style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"><code>testing</code></span>
+/// Provides the `main` function of a test binary that runs every test registered with
+/// [`test`]
+///
+/// Python is initialized once and the tests share a single `tokio` runtime, like with
+/// [`main`] and [`crate::testing::main`]. Since test functions may return `()` (see
+/// [`crate::testing::TestResult`]), synchronous tests written for the default harness can live in
+/// the same binary as the async ones by swapping `#[test]` for [`test`].
+///
+/// An optional `setup` function is called with the GIL held before any test runs, i.e. to add
+/// directories to `sys.path` or register Python modules that all of the tests rely on.
+///
+/// # Examples
+///
+/// ```ignore
+/// use std::time::Duration;
+///
+/// use pyo3::prelude::*;
+///
+/// fn setup(py: Python) -> PyResult<()> {
+///     py.import("sys")?.getattr("path")?.call_method1("insert", (0, "python"))?;
+///     Ok(())
+/// }
+///
+/// #[pyo3_asyncio::tokio::test]
+/// fn test_parse() {
+///     assert_eq!("42".parse::<i32>().unwrap(), 42);
+/// }
+///
+/// #[pyo3_asyncio::tokio::test]
+/// async fn test_sleep() -> PyResult<()> {
+///     tokio::time::sleep(Duration::from_millis(10)).await;
+///     Ok(())
+/// }
+///
+/// pyo3_asyncio::tokio::test_main!(setup);
+/// ```
+#[cfg(all(feature = "attributes", feature = "testing"))]
+pub use crate::__tokio_test_main as test_main;
+
+#[cfg(all(feature = "attributes", feature = "testing"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __tokio_test_main {
+    () => {
+        #[$crate::tokio::main]
+        async fn main() -> ::pyo3::PyResult<()> {
+            $crate::testing::main().await
+        }
+    };
+    ($setup:path) => {
+        #[$crate::tokio::main]
+        async fn main() -> ::pyo3::PyResult<()> {
+            ::pyo3::Python::with_gil($setup)?;
+            $crate::testing::main().await
+        }
+    };
+}
+
 static TOKIO_RUNTIME_HANDLE: OnceCell<Handle> = OnceCell::new();
+static TOKIO_MULTI_THREAD_RUNTIME: OnceCell<Runtime> = OnceCell::new();
 
 const EXPECT_TOKIO_INIT: &str = "Tokio runtime must be initialized";
 
@@ -38,9 +109,17 @@ impl generic::JoinError for task::JoinError {
     fn is_panic(&self) -> bool {
         task::JoinError::is_panic(self)
     }
+    fn into_panic(self) -> Box<dyn Any + Send + 'static> {
+        task::JoinError::into_panic(self)
+    }
 }
 
-struct TokioRuntime;
+/// The tokio [`generic::Runtime`], for use with the runtime-generic utilities in
+/// [`crate::generic`]
+///
+/// Tasks are spawned onto the runtime returned by [`get_handle`]. Use a [`Bridge`] to convert
+/// futures on another runtime.
+pub struct TokioRuntime;
 
 impl generic::Runtime for TokioRuntime {
     type JoinError = task::JoinError;
@@ -50,9 +129,118 @@ impl generic::Runtime for TokioRuntime {
     where
         F: Future<Output = ()> + Send + 'static,
     {
-        get_handle().spawn(async move {
-            fut.await;
-        })
+        <OnHandle as generic::Spawner>::spawn(get_handle(), fut)
+    }
+
+    fn spawn_named<F>(name: &str, fut: F) -> Self::JoinHandle
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        <OnHandle as generic::Spawner>::spawn_named(get_handle(), name, fut)
+    }
+
+    fn enter<T>(f: impl FnOnce() -> T) -> T {
+        <OnHandle as generic::Spawner>::enter(get_handle(), f)
+    }
+
+    fn is_runtime_thread() -> bool {
+        match task::try_id() {
+            // blocking the blocking pool is what it's for
+            Some(id) => BLOCKING_TASK.with(|blocking| blocking.get()) != Some(id),
+            None => false,
+        }
+    }
+}
+
+impl generic::SpawnLocalExt for TokioRuntime {
+    fn spawn_local<F>(fut: F) -> Self::JoinHandle
+    where
+        F: Future<Output = ()> + 'static,
+    {
+        // a task of the LocalSet has a task id like any other, so `is_runtime_thread` covers it
+        task::spawn_local(fut)
+    }
+}
+
+// tokio reports the panics of its tasks through their JoinHandles
+impl generic::UnwindSafeExt for TokioRuntime {}
+
+impl generic::SpawnBlockingExt for TokioRuntime {
+    fn spawn_blocking<F>(f: F) -> Self::JoinHandle
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        spawn_blocking_on(get_handle(), f)
+    }
+}
+
+/// Spawn `f` onto the blocking pool of `handle` as work that may block (see [`spawn_blocking`])
+fn spawn_blocking_on<F, T>(handle: &Handle, f: F) -> task::JoinHandle<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    handle.spawn_blocking(move || {
+        let _restore =
+            RestoreBlockingTask(BLOCKING_TASK.with(|blocking| blocking.replace(task::try_id())));
+        f()
+    })
+}
+
+/// Restores [`BLOCKING_TASK`] even if the blocking work panics
+struct RestoreBlockingTask(Option<task::Id>);
+
+impl Drop for RestoreBlockingTask {
+    fn drop(&mut self) {
+        BLOCKING_TASK.with(|blocking| blocking.set(self.0));
+    }
+}
+
+thread_local! {
+    /// The blocking task that this thread is running for [`spawn_blocking`], if any
+    ///
+    /// tokio runs blocking closures as tasks too, and doesn't tell them apart from async tasks, so
+    /// they're marked here. Tasks polled by a runtime that the blocking work drives itself (i.e.
+    /// with `block_on`) have ids of their own, so they aren't mistaken for the blocking work.
+    static BLOCKING_TASK: Cell<Option<task::Id>> = const { Cell::new(None) };
+}
+
+/// Spawns the tasks of a conversion onto the runtime of the given handle
+enum OnHandle {}
+
+impl generic::Spawner for OnHandle {
+    type Runtime = TokioRuntime;
+    type Target = Handle;
+
+    fn spawn<F>(handle: &Handle, fut: F) -> task::JoinHandle<()>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        handle.spawn(fut)
+    }
+
+    #[cfg(all(feature = "tokio-console", tokio_unstable))]
+    fn spawn_named<F>(handle: &Handle, name: &str, fut: F) -> task::JoinHandle<()>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        task::Builder::new()
+            .name(name)
+            .spawn_on(fut, handle)
+            .expect("Couldn't spawn a named tokio task")
+    }
+
+    #[cfg(not(all(feature = "tokio-console", tokio_unstable)))]
+    fn spawn_named<F>(handle: &Handle, _name: &str, fut: F) -> task::JoinHandle<()>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        Self::spawn(handle, fut)
+    }
+
+    fn enter<T>(handle: &Handle, f: impl FnOnce() -> T) -> T {
+        let _guard = handle.enter();
+        f()
     }
 }
 
@@ -70,12 +258,9 @@ fn current_thread() -> Runtime {
         .expect("Couldn't build the current-thread Tokio runtime")
 }
 
-fn start_current_thread() {
+fn start_current_thread(runtime: Runtime) {
     thread::spawn(move || {
-        TOKIO_RUNTIME_HANDLE
-            .get()
-            .unwrap()
-            .block_on(pending::<()>());
+        runtime.block_on(pending::<()>());
     });
 }
 
@@ -85,8 +270,9 @@ fn start_current_thread() {
 /// This function will panic if called a second time. See [`init_current_thread_once`] if you want
 /// to avoid this panic.
 pub fn init_current_thread() {
-    init(current_thread().handle().clone());
-    start_current_thread();
+    let runtime = current_thread();
+    init(runtime.handle().clone());
+    start_current_thread(runtime);
 }
 
 /// Get a reference to the current tokio runtime
@@ -101,13 +287,19 @@ fn multi_thread() -> Runtime {
         .expect("Couldn't build the multi-thread Tokio runtime")
 }
 
+fn start_multi_thread<'a>() -> &'a Handle {
+    TOKIO_MULTI_THREAD_RUNTIME
+        .get_or_init(multi_thread)
+        .handle()
+}
+
 /// Initialize the Tokio Runtime with the multi-thread scheduler
 ///
 /// # Panics
 /// This function will panic if called a second time. See [`init_multi_thread_once`] if you want to
 /// avoid this panic.
 pub fn init_multi_thread() {
-    init(multi_thread().handle().clone());
+    init(start_multi_thread().clone());
 }
 
 /// Ensure that the Tokio Runtime is initialized
@@ -115,7 +307,7 @@ pub fn init_multi_thread() {
 /// If the runtime has not been initialized already, the multi-thread scheduler
 /// is used. Calling this function a second time is a no-op.
 pub fn init_multi_thread_once() {
-    TOKIO_RUNTIME_HANDLE.get_or_init(|| multi_thread().handle().clone());
+    TOKIO_RUNTIME_HANDLE.get_or_init(|| start_multi_thread().clone());
 }
 
 /// Ensure that the Tokio Runtime is initialized
@@ -123,14 +315,16 @@ pub fn init_multi_thread_once() {
 /// If the runtime has not been initialized already, the current-thread
 /// scheduler is used. Calling this function a second time is a no-op.
 pub fn init_current_thread_once() {
-    let mut initialized = false;
+    let mut runtime = None;
     TOKIO_RUNTIME_HANDLE.get_or_init(|| {
-        initialized = true;
-        current_thread().handle().clone()
+        let rt = current_thread();
+        let handle = rt.handle().clone();
+        runtime = Some(rt);
+        handle
     });
 
-    if initialized {
-        start_current_thread();
+    if let Some(runtime) = runtime {
+        start_current_thread(runtime);
     }
 }
 
@@ -162,19 +356,152 @@ pub fn init_current_thread_once() {
 /// #             tokio::time::sleep(Duration::from_secs(1)).await;
 /// #             Ok(())
 /// #           })
-/// #       }) 
-/// #   }).unwrap() 
+/// #       })
+/// #   }).unwrap()
 /// # }
 /// ```
 pub fn run_until_complete<F>(py: Python, fut: F) -> PyResult<()>
 where
     F: Future<Output = PyResult<()>> + Send + 'static,
 {
-    generic::run_until_complete::<TokioRuntime, _>(py, fut)
+    default_bridge().run_until_complete(py, fut)
+}
+
+/// Run the given event loop until the given Future completes
+///
+/// See [`generic::run_until_complete_with_loop`] for details.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `event_loop` - The Python event loop to run
+/// * `fut` - The future to drive to completion
+pub fn run_until_complete_with_loop<F>(py: Python, event_loop: &PyAny, fut: F) -> PyResult<()>
+where
+    F: Future<Output = PyResult<()>> + Send + 'static,
+{
+    default_bridge().run_until_complete_with_loop(py, event_loop, fut)
+}
+
+/// Initialize PyO3 Asyncio, run the event loop until `fut` completes, then close the event loop
+///
+/// See [`generic::with_runtime_async`] for details.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `fut` - The future to drive to completion
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// #
+/// # use pyo3::prelude::*;
+/// #
+/// # #[tokio::main]
+/// # async fn main() {
+/// #   pyo3_asyncio::tokio::init(tokio::runtime::Handle::current());
+/// #
+/// Python::with_gil(|py| {
+///     pyo3_asyncio::tokio::with_runtime_async(py, async move {
+///         tokio::time::sleep(Duration::from_secs(1)).await;
+///         Ok(())
+///     })
+/// })
+/// .unwrap();
+/// # }
+/// ```
+pub fn with_runtime_async<F>(py: Python, fut: F) -> PyResult<()>
+where
+    F: Future<Output = PyResult<()>> + Send + 'static,
+{
+    generic::with_runtime_async::<TokioRuntime, _>(py, fut)
+}
+
+/// A reusable runner for Rust futures, like Python's `asyncio.Runner`
+///
+/// See [`generic::PyRunner`] for details.
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// #
+/// # use pyo3::prelude::*;
+/// #
+/// # #[tokio::main]
+/// # async fn main() {
+/// #   pyo3_asyncio::tokio::init(tokio::runtime::Handle::current());
+/// #
+/// Python::with_gil(|py| -> PyResult<()> {
+///     let mut runner = pyo3_asyncio::tokio::PyRunner::new(py)?;
+///
+///     for millis in 1..=3 {
+///         let slept = runner.run(py, async move {
+///             tokio::time::sleep(Duration::from_millis(millis)).await;
+///             Ok(millis)
+///         })?;
+///         assert_eq!(slept, millis);
+///     }
+///
+///     runner.close(py)
+/// })
+/// .unwrap();
+/// # }
+/// ```
+pub struct PyRunner(generic::PyRunner<TokioRuntime>);
+
+impl PyRunner {
+    /// Initialize PyO3 Asyncio and create a runner with a copy of the current context
+    ///
+    /// # Arguments
+    /// * `py` - The current PyO3 GIL guard
+    pub fn new(py: Python) -> PyResult<Self> {
+        Ok(Self(generic::PyRunner::new(py)?))
+    }
+
+    /// Run the event loop until `fut` completes and return its output
+    ///
+    /// # Arguments
+    /// * `py` - The current PyO3 GIL guard
+    /// * `fut` - The future to drive to completion
+    pub fn run<F, T>(&self, py: Python, fut: F) -> PyResult<T>
+    where
+        F: Future<Output = PyResult<T>> + Send + 'static,
+        T: Send + 'static,
+    {
+        self.0.run(py, fut)
+    }
+
+    /// The event loop that futures are run on
+    ///
+    /// # Arguments
+    /// * `py` - The current PyO3 GIL guard
+    pub fn event_loop<'p>(&self, py: Python<'p>) -> &'p PyAny {
+        self.0.event_loop(py)
+    }
+
+    /// The `contextvars.Context` that futures are run in
+    ///
+    /// # Arguments
+    /// * `py` - The current PyO3 GIL guard
+    pub fn context<'p>(&'p self, py: Python<'p>) -> &'p PyAny {
+        self.0.context(py)
+    }
+
+    /// Close the runner, closing the event loop if the runner owns it
+    ///
+    /// # Arguments
+    /// * `py` - The current PyO3 GIL guard
+    pub fn close(&mut self, py: Python) -> PyResult<()> {
+        self.0.close(py)
+    }
 }
 
 /// Convert a Rust Future into a Python coroutine
 ///
+/// The future is always spawned, see [`into_coroutine_eager`] to skip the runtime for futures that
+/// are ready right away and [`generic::into_coroutine`] for why that's opt-in.
+///
 /// # Arguments
 /// * `py` - The current PyO3 GIL guard
 /// * `fut` - The Rust future to be converted
@@ -197,9 +524,1306 @@ where
 ///     })
 /// }
 /// ```
+#[track_caller]
 pub fn into_coroutine<F>(py: Python, fut: F) -> PyResult<PyObject>
 where
     F: Future<Output = PyResult<PyObject>> + Send + 'static,
 {
-    generic::into_coroutine::<TokioRuntime, _>(py, fut)
+    default_bridge().into_coroutine(py, fut)
+}
+
+/// Convert a Rust Future into a Python coroutine, skipping the runtime if it's ready right away
+///
+/// See [`generic::into_coroutine_eager`] for details.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `fut` - The Rust future to be converted
+#[track_caller]
+pub fn into_coroutine_eager<F>(py: Python, fut: F) -> PyResult<PyObject>
+where
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    default_bridge().into_coroutine_eager(py, fut)
+}
+
+/// Convert a `!Send` Rust Future into a Python coroutine
+///
+/// The future is spawned with `tokio::task::spawn_local`, so it runs on the calling thread. This
+/// must be called from inside a `tokio::task::LocalSet` (or a `LocalRuntime`), the task is only
+/// polled while the `LocalSet` is driven.
+///
+/// # Errors
+///
+/// Returns a `RuntimeError` if no `LocalSet` is active on the calling thread.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `fut` - The Rust future to be converted
+///
+/// # Examples
+///
+/// ```
+/// use std::rc::Rc;
+///
+/// use pyo3::prelude::*;
+///
+/// async fn answer() -> PyResult<PyObject> {
+///     let local = tokio::task::LocalSet::new();
+///
+///     local
+///         .run_until(async move {
+///             let coro = Python::with_gil(|py| {
+///                 let answer = Rc::new(42);
+///
+///                 pyo3_asyncio::tokio::into_coroutine_local(py, async move {
+///                     tokio::task::yield_now().await;
+///                     Ok(Python::with_gil(|py| (*answer).into_py(py)))
+///                 })
+///             })?;
+///
+///             Python::with_gil(|py| pyo3_asyncio::into_future(coro.as_ref(py)))?.await
+///         })
+///         .await
+/// }
+/// ```
+#[track_caller]
+pub fn into_coroutine_local<F>(py: Python, fut: F) -> PyResult<PyObject>
+where
+    F: Future<Output = PyResult<PyObject>> + 'static,
+{
+    // tokio only tells whether a LocalSet is active by panicking in spawn_local
+    if panic::catch_unwind(|| drop(task::spawn_local(async {}))).is_err() {
+        return Err(PyRuntimeError::new_err(
+            "into_coroutine_local must be called from inside a tokio::task::LocalSet",
+        ));
+    }
+
+    generic::into_coroutine_local::<TokioRuntime, _>(py, fut)
+}
+
+/// Convert a Rust Future into a Python coroutine that runs in a named task
+///
+/// Task names are only visible in `tokio-console` when the `tokio-console` feature is enabled
+/// and the crate is built with `RUSTFLAGS="--cfg tokio_unstable"`. In that configuration,
+/// [`into_coroutine`] also names its tasks after the Python function that called into Rust.
+/// Otherwise the name is ignored.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `name` - The name of the spawned task
+/// * `fut` - The Rust future to be converted
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use pyo3::prelude::*;
+///
+/// #[pyfunction]
+/// fn sleep_for(py: Python, secs: u64) -> PyResult<PyObject> {
+///     pyo3_asyncio::tokio::into_coroutine_named(py, "sleep_for", async move {
+///         tokio::time::sleep(Duration::from_secs(secs)).await;
+///         Python::with_gil(|py| Ok(py.None()))
+///     })
+/// }
+/// ```
+#[track_caller]
+pub fn into_coroutine_named<F>(py: Python, name: &str, fut: F) -> PyResult<PyObject>
+where
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    default_bridge().into_coroutine_named(py, name, fut)
+}
+
+/// Convert a Rust Future into a Python awaitable that belongs to the given event loop and runs in
+/// a named task
+///
+/// See [`generic::into_coroutine_named_with_loop`] for details.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `event_loop` - The Python event loop that the awaitable belongs to
+/// * `name` - The name of the spawned task
+/// * `fut` - The Rust future to be converted
+#[track_caller]
+pub fn into_coroutine_named_with_loop<F>(
+    py: Python,
+    event_loop: &PyAny,
+    name: &str,
+    fut: F,
+) -> PyResult<PyObject>
+where
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    default_bridge().into_coroutine_named_with_loop(py, event_loop, name, fut)
+}
+
+/// A PyO3 Asyncio bridge to a specific tokio runtime
+///
+/// The module-level functions in [`crate::tokio`] all spawn onto the single runtime registered with
+/// [`init`] and deliver their results to the PyO3 Asyncio event loop. A `Bridge` spawns onto the
+/// runtime of its own handle instead, and can be bound to its own event loop, so several runtimes
+/// with different configurations (i.e. one per plugin, or one per Python event loop) can be used
+/// side by side.
+///
+/// A bridge covers the coroutine conversions and the blocking entry points: `run_until_complete`,
+/// the `into_coroutine` family (including the `_eager`, `_named`, `_with_error_converter`,
+/// `_scoped`, `_deferred` and `_with_loop` variants), `create_task_in_group` and
+/// `blocking_executor`. The module-level versions of these are implemented with a default bridge.
+/// Everything else in this module always uses the global runtime and panics if [`init`] hasn't
+/// been called, namely [`with_runtime_async`], [`PyRunner`], [`spawn_blocking`],
+/// [`run_blocking_py`], [`shield`], `readable` and `writable`, [`into_coroutine_local`],
+/// `into_trio_coroutine`, the cancellation token functions and the context manager, async
+/// iterator, stream and async generator conversions.
+///
+/// Only the conversions started through a bridge use its runtime. Conversions started with the
+/// module-level functions from inside a bridged future still spawn onto the global runtime, so a
+/// Rust future that converts more futures onto the same runtime must carry its `Bridge` along.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use pyo3::prelude::*;
+/// use pyo3_asyncio::tokio::Bridge;
+///
+/// /// A plugin with its own runtime
+/// struct Plugin {
+///     runtime: tokio::runtime::Runtime,
+///     bridge: Bridge,
+/// }
+///
+/// impl Plugin {
+///     fn new() -> Self {
+///         let runtime = tokio::runtime::Builder::new_multi_thread()
+///             .worker_threads(1)
+///             .enable_all()
+///             .build()
+///             .unwrap();
+///         let bridge = Bridge::new(runtime.handle().clone());
+///
+///         Self { runtime, bridge }
+///     }
+///
+///     fn sleep_for(&self, py: Python, secs: u64) -> PyResult<PyObject> {
+///         self.bridge.into_coroutine(py, async move {
+///             tokio::time::sleep(Duration::from_secs(secs)).await;
+///             Python::with_gil(|py| Ok(py.None()))
+///         })
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Bridge {
+    handle: Handle,
+    event_loop: Option<PyObject>,
+}
+
+/// The bridge behind the module-level functions
+fn default_bridge() -> Bridge {
+    Bridge::new(get_handle().clone())
+}
+
+impl Bridge {
+    /// Create a bridge that spawns onto the runtime of `handle`
+    ///
+    /// The bridge delivers results to the PyO3 Asyncio event loop. The runtime must be driven by
+    /// some thread (i.e. a multi-thread runtime, or a current-thread runtime blocked on in a
+    /// background thread) for the conversions to make progress.
+    pub fn new(handle: Handle) -> Self {
+        Self {
+            handle,
+            event_loop: None,
+        }
+    }
+
+    /// Create a bridge that spawns onto the runtime of `handle` and delivers results to
+    /// `event_loop`
+    ///
+    /// This is the bridge counterpart of the `_with_loop` functions: its conversions create their
+    /// Python futures on `event_loop`, and [`Bridge::run_until_complete`] runs `event_loop`.
+    pub fn with_loop(handle: Handle, event_loop: &PyAny) -> Self {
+        Self {
+            handle,
+            event_loop: Some(event_loop.into()),
+        }
+    }
+
+    /// Get the handle of the runtime that this bridge spawns onto
+    pub fn handle(&self) -> &Handle {
+        &self.handle
+    }
+
+    /// Get the event loop that this bridge delivers results to
+    ///
+    /// # Arguments
+    /// * `py` - The current PyO3 GIL guard
+    pub fn event_loop<'p>(&'p self, py: Python<'p>) -> &'p PyAny {
+        match self.event_loop.as_ref() {
+            Some(event_loop) => event_loop.as_ref(py),
+            None => crate::get_event_loop(py),
+        }
+    }
+
+    /// The event loop of this bridge, or `None` for the PyO3 Asyncio event loop
+    fn bound_loop<'p>(&'p self, py: Python<'p>) -> Option<&'p PyAny> {
+        self.event_loop
+            .as_ref()
+            .map(|event_loop| event_loop.as_ref(py))
+    }
+
+    /// Run the event loop until the given Future completes
+    ///
+    /// See [`run_until_complete`] for details.
+    pub fn run_until_complete<F>(&self, py: Python, fut: F) -> PyResult<()>
+    where
+        F: Future<Output = PyResult<()>> + Send + 'static,
+    {
+        self.run_until_complete_with_loop(py, self.event_loop(py), fut)
+    }
+
+    /// Run the given event loop until the given Future completes
+    ///
+    /// See [`run_until_complete_with_loop`] for details.
+    pub fn run_until_complete_with_loop<F>(
+        &self,
+        py: Python,
+        event_loop: &PyAny,
+        fut: F,
+    ) -> PyResult<()>
+    where
+        F: Future<Output = PyResult<()>> + Send + 'static,
+    {
+        generic::run_until_complete_on::<OnHandle, _>(py, self.handle.clone(), event_loop, fut)
+    }
+
+    #[track_caller]
+    fn spawn_coroutine<F, C>(
+        &self,
+        py: Python,
+        event_loop: Option<&PyAny>,
+        start: generic::Start,
+        fut: F,
+        converter: C,
+    ) -> PyResult<PyObject>
+    where
+        F: Future<Output = PyResult<PyObject>> + Send + 'static,
+        C: FnOnce(Python, generic::JoinFailure) -> PyErr + Send + 'static,
+    {
+        generic::spawn_coroutine::<OnHandle, _, _>(
+            py,
+            self.handle.clone(),
+            event_loop,
+            start,
+            fut,
+            converter,
+        )
+    }
+
+    /// Convert a Rust Future into a Python coroutine
+    ///
+    /// See [`into_coroutine`] for details.
+    #[track_caller]
+    pub fn into_coroutine<F>(&self, py: Python, fut: F) -> PyResult<PyObject>
+    where
+        F: Future<Output = PyResult<PyObject>> + Send + 'static,
+    {
+        #[cfg(all(feature = "tokio-console", tokio_unstable))]
+        {
+            if let Some(name) = generic::python_caller_name(py) {
+                return self.into_coroutine_named(py, &name, fut);
+            }
+        }
+
+        self.spawn_coroutine(
+            py,
+            self.bound_loop(py),
+            generic::Start::Spawn,
+            fut,
+            generic::convert_join_failure,
+        )
+    }
+
+    /// Convert a Rust Future into a Python coroutine, skipping the runtime if it's ready right away
+    ///
+    /// See [`into_coroutine_eager`] for details.
+    #[track_caller]
+    pub fn into_coroutine_eager<F>(&self, py: Python, fut: F) -> PyResult<PyObject>
+    where
+        F: Future<Output = PyResult<PyObject>> + Send + 'static,
+    {
+        self.spawn_coroutine(
+            py,
+            self.bound_loop(py),
+            generic::Start::Eager,
+            fut,
+            generic::convert_join_failure,
+        )
+    }
+
+    /// Convert a Rust Future into a Python coroutine that runs in a named task
+    ///
+    /// See [`into_coroutine_named`] for details.
+    #[track_caller]
+    pub fn into_coroutine_named<F>(&self, py: Python, name: &str, fut: F) -> PyResult<PyObject>
+    where
+        F: Future<Output = PyResult<PyObject>> + Send + 'static,
+    {
+        self.spawn_coroutine(
+            py,
+            self.bound_loop(py),
+            generic::Start::Named(name),
+            fut,
+            generic::convert_join_failure,
+        )
+    }
+
+    /// Convert a Rust Future into a Python awaitable that belongs to the given event loop and runs
+    /// in a named task
+    ///
+    /// See [`into_coroutine_named_with_loop`] for details.
+    #[track_caller]
+    pub fn into_coroutine_named_with_loop<F>(
+        &self,
+        py: Python,
+        event_loop: &PyAny,
+        name: &str,
+        fut: F,
+    ) -> PyResult<PyObject>
+    where
+        F: Future<Output = PyResult<PyObject>> + Send + 'static,
+    {
+        self.spawn_coroutine(
+            py,
+            Some(event_loop),
+            generic::Start::Named(name),
+            fut,
+            generic::convert_join_failure,
+        )
+    }
+
+    /// Convert a Rust Future into a Python coroutine with a custom error converter
+    ///
+    /// See [`into_coroutine_with_error_converter`] for details.
+    #[track_caller]
+    pub fn into_coroutine_with_error_converter<F, C>(
+        &self,
+        py: Python,
+        fut: F,
+        converter: C,
+    ) -> PyResult<PyObject>
+    where
+        F: Future<Output = PyResult<PyObject>> + Send + 'static,
+        C: FnOnce(Python, generic::JoinFailure) -> PyErr + Send + 'static,
+    {
+        self.spawn_coroutine(
+            py,
+            self.bound_loop(py),
+            generic::Start::Spawn,
+            fut,
+            converter,
+        )
+    }
+
+    /// Convert a Rust Future into a Python awaitable that belongs to the given event loop, with a
+    /// custom error converter
+    ///
+    /// See [`into_coroutine_with_error_converter_and_loop`] for details.
+    #[track_caller]
+    pub fn into_coroutine_with_error_converter_and_loop<F, C>(
+        &self,
+        py: Python,
+        event_loop: &PyAny,
+        fut: F,
+        converter: C,
+    ) -> PyResult<PyObject>
+    where
+        F: Future<Output = PyResult<PyObject>> + Send + 'static,
+        C: FnOnce(Python, generic::JoinFailure) -> PyErr + Send + 'static,
+    {
+        self.spawn_coroutine(py, Some(event_loop), generic::Start::Spawn, fut, converter)
+    }
+
+    /// Convert a Rust Future into a Python coroutine that doesn't outlive its awaiter
+    ///
+    /// See [`into_coroutine_scoped`] for details.
+    #[track_caller]
+    pub fn into_coroutine_scoped<F>(&self, py: Python, fut: F) -> PyResult<PyObject>
+    where
+        F: Future<Output = PyResult<PyObject>> + Send + 'static,
+    {
+        generic::spawn_scoped::<OnHandle, _>(py, self.handle.clone(), self.bound_loop(py), fut)
+    }
+
+    /// Convert a Rust Future into a Python coroutine that belongs to the given event loop and
+    /// doesn't outlive its awaiter
+    ///
+    /// See [`into_coroutine_scoped_with_loop`] for details.
+    #[track_caller]
+    pub fn into_coroutine_scoped_with_loop<F>(
+        &self,
+        py: Python,
+        event_loop: &PyAny,
+        fut: F,
+    ) -> PyResult<PyObject>
+    where
+        F: Future<Output = PyResult<PyObject>> + Send + 'static,
+    {
+        generic::spawn_scoped::<OnHandle, _>(py, self.handle.clone(), Some(event_loop), fut)
+    }
+
+    /// Convert a Rust Future into a Python coroutine that binds to an event loop when it is awaited
+    ///
+    /// See [`into_coroutine_deferred`] for details. The coroutine binds to the running event loop
+    /// even if this bridge has an event loop of its own.
+    #[track_caller]
+    pub fn into_coroutine_deferred<F>(&self, py: Python, fut: F) -> PyResult<PyObject>
+    where
+        F: Future<Output = PyResult<PyObject>> + Send + 'static,
+    {
+        generic::into_coroutine_deferred_on::<OnHandle, _>(py, self.handle.clone(), fut)
+    }
+
+    /// Convert a Rust Future into a Python awaitable that belongs to the given event loop
+    ///
+    /// See [`into_coroutine_with_loop`] for details.
+    #[track_caller]
+    pub fn into_coroutine_with_loop<F>(
+        &self,
+        py: Python,
+        event_loop: &PyAny,
+        fut: F,
+    ) -> PyResult<PyObject>
+    where
+        F: Future<Output = PyResult<PyObject>> + Send + 'static,
+    {
+        self.spawn_coroutine(
+            py,
+            Some(event_loop),
+            generic::Start::Spawn,
+            fut,
+            generic::convert_join_failure,
+        )
+    }
+
+    /// Run a Rust Future as a task in a Python `asyncio.TaskGroup`
+    ///
+    /// See [`create_task_in_group`] for details.
+    #[track_caller]
+    pub fn create_task_in_group<F>(
+        &self,
+        py: Python,
+        task_group: &PyAny,
+        fut: F,
+    ) -> PyResult<PyObject>
+    where
+        F: Future<Output = PyResult<PyObject>> + Send + 'static,
+    {
+        let coro = self.into_coroutine_scoped(py, fut)?;
+
+        Ok(task_group.call_method1("create_task", (coro,))?.into())
+    }
+
+    /// Run a Rust Future as a task in a Python `asyncio.TaskGroup` that runs on the given event loop
+    ///
+    /// See [`create_task_in_group_with_loop`] for details.
+    #[track_caller]
+    pub fn create_task_in_group_with_loop<F>(
+        &self,
+        py: Python,
+        event_loop: &PyAny,
+        task_group: &PyAny,
+        fut: F,
+    ) -> PyResult<PyObject>
+    where
+        F: Future<Output = PyResult<PyObject>> + Send + 'static,
+    {
+        let coro = self.into_coroutine_scoped_with_loop(py, event_loop, fut)?;
+
+        Ok(task_group.call_method1("create_task", (coro,))?.into())
+    }
+
+    /// Create a `concurrent.futures.Executor` that runs its work on the blocking pool of this
+    /// bridge's runtime
+    ///
+    /// See [`blocking_executor`] for details.
+    pub fn blocking_executor(&self, py: Python) -> PyResult<PyObject> {
+        let spawn_blocking = PySpawnBlocking {
+            handle: self.handle.clone(),
+        };
+
+        Ok(crate::py_helpers(py)?
+            .call_method1("SpawnBlockingExecutor", (spawn_blocking,))?
+            .into())
+    }
+}
+
+#[cfg(unix)]
+async fn wait_for_fd(fd: RawFd, interest: Interest) -> io::Result<()> {
+    // AsyncFd registers with the reactor of the current runtime, so make sure we're inside ours
+    let async_fd = {
+        let _guard = get_handle().enter();
+        AsyncFd::with_interest(fd, interest)?
+    };
+
+    if interest.is_readable() {
+        async_fd.readable().await?.retain_ready();
+    } else {
+        async_fd.writable().await?.retain_ready();
+    }
+
+    Ok(())
+}
+
+/// Wait for the tokio reactor to report that `fd` is readable
+///
+/// The file descriptor is registered with the tokio runtime for the duration of the call and
+/// deregistered before the future completes. Ownership of `fd` is not taken, so this is suitable for
+/// file descriptors handed out by Python objects (i.e. `socket.fileno()` or an event-driven C
+/// extension). See [`crate::add_reader`] for the opposite direction.
+///
+/// A file descriptor can only be registered with the reactor once, so waiting on the same `fd`
+/// from two tasks at the same time will fail with an `io::Error` for one of them.
+///
+/// # Arguments
+/// * `fd` - The raw file descriptor to watch
+///
+/// # Examples
+///
+/// ```
+/// use pyo3::prelude::*;
+///
+/// async fn wait_for_socket(sock: PyObject) -> PyResult<()> {
+///     let fd = Python::with_gil(|py| sock.call_method0(py, "fileno")?.extract(py))?;
+///
+///     pyo3_asyncio::tokio::readable(fd).await?;
+///     Ok(())
+/// }
+/// ```
+#[cfg(unix)]
+pub async fn readable(fd: RawFd) -> io::Result<()> {
+    wait_for_fd(fd, Interest::READABLE).await
+}
+
+/// Wait for the tokio reactor to report that `fd` is writable
+///
+/// This is the writable counterpart of [`readable`].
+///
+/// # Arguments
+/// * `fd` - The raw file descriptor to watch
+#[cfg(unix)]
+pub async fn writable(fd: RawFd) -> io::Result<()> {
+    wait_for_fd(fd, Interest::WRITABLE).await
+}
+
+/// Run blocking code on tokio's blocking thread pool
+///
+/// This is `tokio::task::spawn_blocking` on the runtime returned by [`get_handle`], except `f` is
+/// allowed to call the blocking entry points of PyO3 Asyncio (i.e. [`run_until_complete`]). They
+/// refuse to run inside of any tokio task, since an event loop would stall the worker thread it
+/// runs on, and tokio runs the closures given to its own `spawn_blocking` as tasks that can't be
+/// told apart from async ones.
+///
+/// # Arguments
+/// * `f` - The blocking code
+///
+/// # Examples
+///
+/// ```no_run
+/// use pyo3::prelude::*;
+///
+/// async fn run_on_other_loop() -> PyResult<()> {
+///     pyo3_asyncio::tokio::spawn_blocking(|| {
+///         Python::with_gil(|py| {
+///             let event_loop = py.import("asyncio")?.call_method0("new_event_loop")?;
+///             pyo3_asyncio::tokio::run_until_complete_with_loop(py, event_loop, async { Ok(()) })
+///         })
+///     })
+///     .await
+///     .unwrap()
+/// }
+/// ```
+pub fn spawn_blocking<F, T>(f: F) -> task::JoinHandle<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    spawn_blocking_on(get_handle(), f)
+}
+
+/// Run blocking Python code on tokio's blocking thread pool
+///
+/// Slow synchronous Python calls (i.e. pandas or requests) hold the GIL and block whatever thread
+/// they run on. Calling them directly from an async task starves the other tasks of its tokio
+/// worker thread, so `f` is moved onto a `spawn_blocking` thread instead and called there with the
+/// GIL held. `block_in_place` isn't used because it can't be awaited and panics on the
+/// current-thread scheduler.
+///
+/// Nothing runs until the returned future is first polled. Like any blocking task, `f` runs to
+/// completion even if the future is dropped. If `f` panics, the future fails with the same
+/// exception as a panicking conversion (see [`generic::set_error_converter`]).
+///
+/// # Arguments
+/// * `f` - The blocking Python code
+///
+/// # Examples
+///
+/// ```
+/// use pyo3::prelude::*;
+///
+/// async fn checksum(data: Vec<u8>) -> PyResult<String> {
+///     pyo3_asyncio::tokio::run_blocking_py(move |py| {
+///         py.import("hashlib")?
+///             .call_method1("sha256", (data.as_slice(),))?
+///             .call_method0("hexdigest")?
+///             .extract()
+///     })
+///     .await
+/// }
+/// ```
+pub async fn run_blocking_py<F, T>(f: F) -> PyResult<T>
+where
+    F: FnOnce(Python) -> PyResult<T> + Send + 'static,
+    T: Send + 'static,
+{
+    generic::run_blocking_py::<TokioRuntime, _, _>(f).await
+}
+
+/// Protect a Rust future from being cancelled along with the Python code awaiting it
+///
+/// See [`generic::shield`] for details.
+///
+/// # Arguments
+/// * `fut` - The Rust future to be shielded
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use pyo3::prelude::*;
+///
+/// /// Commit a transaction, even if the Python task awaiting it is cancelled
+/// #[pyfunction]
+/// fn commit(py: Python) -> PyResult<PyObject> {
+///     pyo3_asyncio::tokio::into_coroutine_scoped(py, async move {
+///         pyo3_asyncio::tokio::shield(async move {
+///             tokio::time::sleep(Duration::from_millis(100)).await;
+///             Python::with_gil(|py| Ok(py.None()))
+///         })
+///         .await
+///     })
+/// }
+/// ```
+pub fn shield<F, T>(fut: F) -> impl Future<Output = PyResult<T>> + Send
+where
+    F: Future<Output = PyResult<T>> + Send + 'static,
+    T: Send + 'static,
+{
+    generic::shield::<TokioRuntime, _, _>(fut)
+}
+
+/// Trips a `CancellationToken` when the Python future it's linked to is cancelled
+#[cfg(feature = "tokio-util")]
+#[pyclass]
+struct PyCancelToken {
+    token: CancellationToken,
+    /// Stops the task watching the token, since the future can't be cancelled anymore
+    done: CancellationToken,
+}
+
+#[cfg(feature = "tokio-util")]
+#[pymethods]
+impl PyCancelToken {
+    #[call]
+    fn __call__(&self, future: &PyAny) -> PyResult<()> {
+        self.done.cancel();
+
+        if future.call_method0("cancelled")?.is_true()? {
+            self.token.cancel();
+        }
+
+        Ok(())
+    }
+}
+
+/// <span class="module-item stab portability" style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"><code>tokio-util</code></span> Link the cancellation of a Python future or task to a `CancellationToken`
+///
+/// The link works both ways: cancelling `future` from Python (i.e. with `task.cancel()`, or because
+/// the task awaiting it was cancelled) cancels `token`, and cancelling `token` cancels `future` on
+/// its event loop. This lets a token-based shutdown tree on the Rust side and asyncio cancellation
+/// on the Python side act as one. A future can be linked to several tokens and a token to several
+/// futures, i.e. a parent token can be linked to every task of a request.
+///
+/// Once `future` is done, the link is removed. Finishing `future` any other way than by cancelling
+/// it leaves `token` alone.
+///
+/// # Arguments
+/// * `future` - The `asyncio.Future` or `asyncio.Task` to be linked
+/// * `token` - The token to be linked
+///
+/// # Examples
+///
+/// ```
+/// use pyo3::prelude::*;
+/// use tokio_util::sync::CancellationToken;
+///
+/// /// Cancel the calling task when the server shuts down
+/// fn cancel_on_shutdown(py: Python, shutdown: &CancellationToken) -> PyResult<()> {
+///     let task = py.import("asyncio")?.call_method0("current_task")?;
+///     pyo3_asyncio::tokio::link_cancellation_token(task, shutdown.child_token())
+/// }
+/// ```
+#[cfg(feature = "tokio-util")]
+pub fn link_cancellation_token(future: &PyAny, token: CancellationToken) -> PyResult<()> {
+    let done = CancellationToken::new();
+
+    future.call_method1(
+        "add_done_callback",
+        (PyCancelToken {
+            token: token.clone(),
+            done: done.clone(),
+        },),
+    )?;
+
+    let event_loop = PyObject::from(future.call_method0("get_loop")?);
+    let cancel = PyObject::from(future.getattr("cancel")?);
+
+    drop(<TokioRuntime as generic::Runtime>::spawn(async move {
+        ::tokio::select! {
+            _ = done.cancelled() => (),
+            _ = token.cancelled() => Python::with_gil(|py| {
+                match crate::call_soon_threadsafe(event_loop.as_ref(py), (cancel,)) {
+                    // the future can't be awaited anymore, so there's nothing left to cancel
+                    Err(e) if e.is_instance::<crate::EventLoopClosedError>(py) => (),
+                    result => result.map_err(crate::dump_err(py)).unwrap_or(()),
+                }
+            }),
+        }
+    }));
+
+    Ok(())
+}
+
+/// <span class="module-item stab portability" style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"><code>tokio-util</code></span> Convert a Rust Future into a Python coroutine that is cancelled along with a `CancellationToken`
+///
+/// This behaves like [`into_coroutine`], with the returned future linked to `token` by
+/// [`link_cancellation_token`]: cancelling the Python task that awaits it cancels `token`, and
+/// cancelling `token` raises a `CancelledError` in that task. The Rust future isn't aborted either
+/// way, it should watch `token` and wind down on its own. Whatever it returns after `token` has
+/// been cancelled is discarded.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `token` - The token linked to the Python side of the conversion
+/// * `fut` - The Rust future to be converted
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use pyo3::prelude::*;
+/// use tokio_util::sync::CancellationToken;
+///
+/// /// Sleep until the timer runs out or the Python caller is cancelled
+/// #[pyfunction]
+/// fn cancellable_sleep(py: Python, secs: u64) -> PyResult<PyObject> {
+///     let token = CancellationToken::new();
+///
+///     pyo3_asyncio::tokio::into_coroutine_with_token(py, token.clone(), async move {
+///         tokio::select! {
+///             _ = tokio::time::sleep(Duration::from_secs(secs)) => (),
+///             _ = token.cancelled() => (),
+///         }
+///
+///         Python::with_gil(|py| Ok(py.None()))
+///     })
+/// }
+/// ```
+#[cfg(feature = "tokio-util")]
+pub fn into_coroutine_with_token<F>(
+    py: Python,
+    token: CancellationToken,
+    fut: F,
+) -> PyResult<PyObject>
+where
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    let cancelled = token.clone();
+    let future = into_coroutine(py, async move {
+        let result = fut.await;
+
+        // a future that winds down because of the token is cancelled as far as Python is concerned
+        if cancelled.is_cancelled() {
+            return Python::with_gil(|py| Err(crate::cancelled(py)?));
+        }
+
+        result
+    })?;
+    link_cancellation_token(future.as_ref(py), token)?;
+
+    Ok(future)
+}
+
+/// <span class="module-item stab portability" style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"><code>tokio-util</code></span> Convert a Python awaitable into a Rust Future that is cancelled along with a `CancellationToken`
+///
+/// This behaves like [`crate::into_future`], but the Python task that runs `awaitable` is linked to
+/// `token`: cancelling `token` cancels the task, and the returned future fails with a
+/// `CancelledError` once the task has stopped. If the task is cancelled from Python instead,
+/// `token` is cancelled too.
+///
+/// # Arguments
+/// * `awaitable` - The Python `awaitable` to be converted
+/// * `token` - The token linked to the Python task
+#[cfg(feature = "tokio-util")]
+pub fn into_future_with_token(
+    awaitable: &PyAny,
+    token: CancellationToken,
+) -> PyResult<impl Future<Output = PyResult<PyObject>> + Send> {
+    let py = pyo3::PyNativeType::py(awaitable);
+    let task = crate::TaskSlot::default();
+    let fut = crate::into_future_with_task::<PyObject>(
+        crate::get_event_loop(py),
+        awaitable,
+        Some(task.clone()),
+        None,
+    )?;
+
+    Ok(async move {
+        ::tokio::pin!(fut);
+
+        let result = ::tokio::select! {
+            result = &mut fut => result,
+            _ = token.cancelled() => {
+                Python::with_gil(|py| task.cancel(py))?;
+                // the task has been told to stop, wait until it actually has
+                fut.await
+            }
+        };
+
+        if let Err(e) = &result {
+            if Python::with_gil(|py| crate::is_cancelled_err(py, e)) {
+                token.cancel();
+            }
+        }
+
+        result
+    })
+}
+
+/// Runs the work submitted to a blocking executor with `spawn_blocking`
+#[pyclass]
+struct PySpawnBlocking {
+    handle: Handle,
+}
+
+#[pymethods]
+impl PySpawnBlocking {
+    #[call]
+    fn __call__(&self, future: PyObject, func: PyObject, args: Py<PyTuple>, kwargs: Py<PyDict>) {
+        spawn_blocking_on(&self.handle, move || {
+            Python::with_gil(|py| {
+                let future = future.as_ref(py);
+
+                // the future was cancelled before it started
+                if !future
+                    .call_method0("set_running_or_notify_cancel")
+                    .and_then(|running| running.is_true())
+                    .map_err(crate::dump_err(py))
+                    .unwrap_or(false)
+                {
+                    return;
+                }
+
+                let completed = match func
+                    .as_ref(py)
+                    .call(args.as_ref(py), Some(kwargs.as_ref(py)))
+                {
+                    Ok(result) => future.call_method1("set_result", (result,)),
+                    Err(e) => future.call_method1("set_exception", (e.instance(py),)),
+                };
+
+                // the future couldn't be resolved, there's nothing else to report this to
+                if let Err(e) = completed {
+                    crate::dump_err(py)(e);
+                }
+            })
+        });
+    }
+}
+
+/// Create a `concurrent.futures` executor backed by tokio's blocking thread pool
+///
+/// Work submitted to the executor runs with `spawn_blocking` on the tokio runtime, so Python's
+/// `run_in_executor` calls share the same threads (and runtime metrics) as Rust's blocking work.
+/// The executor is a `ThreadPoolExecutor` as far as Python is concerned, so it can be installed as
+/// the default executor of an event loop with `loop.set_default_executor`. Shutting it down only
+/// waits for the work it submitted, the blocking pool itself belongs to the tokio runtime.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+///
+/// # Examples
+///
+/// ```
+/// use pyo3::prelude::*;
+///
+/// fn use_tokio_executor(py: Python) -> PyResult<()> {
+///     let executor = pyo3_asyncio::tokio::blocking_executor(py)?;
+///
+///     pyo3_asyncio::get_event_loop(py).call_method1("set_default_executor", (executor,))?;
+///     Ok(())
+/// }
+/// ```
+pub fn blocking_executor(py: Python) -> PyResult<PyObject> {
+    default_bridge().blocking_executor(py)
+}
+
+/// Convert a Rust Future into a Python coroutine with a custom error converter
+///
+/// See [`generic::into_coroutine_with_error_converter`] for details.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `fut` - The Rust future to be converted
+/// * `converter` - Converts the reason the future failed into a Python exception
+///
+/// # Examples
+///
+/// ```
+/// use pyo3::{exceptions::PyValueError, prelude::*};
+///
+/// #[pyfunction]
+/// fn validate(py: Python) -> PyResult<PyObject> {
+///     pyo3_asyncio::tokio::into_coroutine_with_error_converter(
+///         py,
+///         async move {
+///             Python::with_gil(|py| Ok(py.None()))
+///         },
+///         |_py, failure| PyValueError::new_err(failure.panic_message()),
+///     )
+/// }
+/// ```
+#[track_caller]
+pub fn into_coroutine_with_error_converter<F, C>(
+    py: Python,
+    fut: F,
+    converter: C,
+) -> PyResult<PyObject>
+where
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+    C: FnOnce(Python, generic::JoinFailure) -> PyErr + Send + 'static,
+{
+    default_bridge().into_coroutine_with_error_converter(py, fut, converter)
+}
+
+/// Convert a Rust Future into a Python awaitable that belongs to the given event loop, with a
+/// custom error converter
+///
+/// See [`generic::into_coroutine_with_error_converter_and_loop`] for details.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `event_loop` - The Python event loop that the awaitable belongs to
+/// * `fut` - The Rust future to be converted
+/// * `converter` - Converts the reason the future failed into a Python exception
+#[track_caller]
+pub fn into_coroutine_with_error_converter_and_loop<F, C>(
+    py: Python,
+    event_loop: &PyAny,
+    fut: F,
+    converter: C,
+) -> PyResult<PyObject>
+where
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+    C: FnOnce(Python, generic::JoinFailure) -> PyErr + Send + 'static,
+{
+    default_bridge().into_coroutine_with_error_converter_and_loop(py, event_loop, fut, converter)
+}
+
+/// Convert a Rust Future into a Python coroutine that doesn't outlive its awaiter
+///
+/// See [`generic::into_coroutine_scoped`] for details.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `fut` - The Rust future to be converted
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use pyo3::prelude::*;
+///
+/// /// Sleep that stops sleeping as soon as the awaiting task is cancelled
+/// #[pyfunction]
+/// fn scoped_sleep(py: Python, secs: u64) -> PyResult<PyObject> {
+///     pyo3_asyncio::tokio::into_coroutine_scoped(py, async move {
+///         tokio::time::sleep(Duration::from_secs(secs)).await;
+///         Python::with_gil(|py| Ok(py.None()))
+///     })
+/// }
+/// ```
+#[track_caller]
+pub fn into_coroutine_scoped<F>(py: Python, fut: F) -> PyResult<PyObject>
+where
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    default_bridge().into_coroutine_scoped(py, fut)
+}
+
+/// Convert a Rust Future into a Python coroutine that belongs to the given event loop and doesn't
+/// outlive its awaiter
+///
+/// See [`generic::into_coroutine_scoped_with_loop`] for details.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `event_loop` - The Python event loop that the coroutine belongs to
+/// * `fut` - The Rust future to be converted
+#[track_caller]
+pub fn into_coroutine_scoped_with_loop<F>(
+    py: Python,
+    event_loop: &PyAny,
+    fut: F,
+) -> PyResult<PyObject>
+where
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    default_bridge().into_coroutine_scoped_with_loop(py, event_loop, fut)
+}
+
+/// Convert a Rust Future into a Python coroutine that binds to an event loop when it is awaited
+///
+/// See [`generic::into_coroutine_deferred`] for details.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `fut` - The Rust future to be converted
+///
+/// # Examples
+///
+/// ```
+/// use std::{thread, time::Duration};
+///
+/// use pyo3::prelude::*;
+///
+/// /// Create a sleep on a background thread, it can be awaited on whichever loop the caller uses
+/// fn sleep_from_thread(secs: u64) -> PyResult<PyObject> {
+///     thread::spawn(move || {
+///         Python::with_gil(|py| {
+///             pyo3_asyncio::tokio::into_coroutine_deferred(py, async move {
+///                 tokio::time::sleep(Duration::from_secs(secs)).await;
+///                 Python::with_gil(|py| Ok(py.None()))
+///             })
+///         })
+///     })
+///     .join()
+///     .unwrap()
+/// }
+/// ```
+#[track_caller]
+pub fn into_coroutine_deferred<F>(py: Python, fut: F) -> PyResult<PyObject>
+where
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    default_bridge().into_coroutine_deferred(py, fut)
+}
+
+/// Convert a Rust Future into a Python awaitable that belongs to the given event loop
+///
+/// See [`generic::into_coroutine_with_loop`] for details.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `event_loop` - The Python event loop that the awaitable belongs to
+/// * `fut` - The Rust future to be converted
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use pyo3::prelude::*;
+///
+/// /// Sleep on the Rust side, then wake up a task on the GUI's event loop
+/// fn sleep_for_gui(py: Python, gui_loop: &PyAny, secs: u64) -> PyResult<PyObject> {
+///     pyo3_asyncio::tokio::into_coroutine_with_loop(py, gui_loop, async move {
+///         tokio::time::sleep(Duration::from_secs(secs)).await;
+///         Python::with_gil(|py| Ok(py.None()))
+///     })
+/// }
+/// ```
+#[track_caller]
+pub fn into_coroutine_with_loop<F>(py: Python, event_loop: &PyAny, fut: F) -> PyResult<PyObject>
+where
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    default_bridge().into_coroutine_with_loop(py, event_loop, fut)
+}
+
+/// <span class="module-item stab portability" style="display: inline; border-radius: 3px; padding: 2px; font-size: 80%; line-height: 1.2;"><code>trio</code></span> Convert a Rust Future into a coroutine that can be awaited from Trio
+///
+/// See [`crate::trio::into_coroutine`] for details.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `fut` - The Rust future to be converted
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use pyo3::prelude::*;
+///
+/// /// Awaitable sleep function for Trio
+/// #[pyfunction]
+/// fn sleep_for(py: Python, secs: u64) -> PyResult<PyObject> {
+///     pyo3_asyncio::tokio::into_trio_coroutine(py, async move {
+///         tokio::time::sleep(Duration::from_secs(secs)).await;
+///         Python::with_gil(|py| Ok(py.None()))
+///     })
+/// }
+/// ```
+#[cfg(feature = "trio")]
+pub fn into_trio_coroutine<F>(py: Python, fut: F) -> PyResult<PyObject>
+where
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    crate::trio::into_coroutine::<TokioRuntime, _>(py, fut)
+}
+
+/// Run a Rust Future as a task in a Python `asyncio.TaskGroup`
+///
+/// See [`generic::create_task_in_group`] for details.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `task_group` - The entered `asyncio.TaskGroup`
+/// * `fut` - The Rust future to be run in the group
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use pyo3::prelude::*;
+///
+/// /// Sleep as a member of the given task group
+/// #[pyfunction]
+/// fn sleep_in_group(py: Python, task_group: &PyAny, secs: u64) -> PyResult<PyObject> {
+///     pyo3_asyncio::tokio::create_task_in_group(py, task_group, async move {
+///         tokio::time::sleep(Duration::from_secs(secs)).await;
+///         Python::with_gil(|py| Ok(py.None()))
+///     })
+/// }
+/// ```
+#[track_caller]
+pub fn create_task_in_group<F>(py: Python, task_group: &PyAny, fut: F) -> PyResult<PyObject>
+where
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    default_bridge().create_task_in_group(py, task_group, fut)
+}
+
+/// Run a Rust Future as a task in a Python `asyncio.TaskGroup` that runs on the given event loop
+///
+/// See [`generic::create_task_in_group_with_loop`] for details.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `event_loop` - The Python event loop that the task group runs on
+/// * `task_group` - The entered `asyncio.TaskGroup`
+/// * `fut` - The Rust future to be run in the group
+#[track_caller]
+pub fn create_task_in_group_with_loop<F>(
+    py: Python,
+    event_loop: &PyAny,
+    task_group: &PyAny,
+    fut: F,
+) -> PyResult<PyObject>
+where
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    default_bridge().create_task_in_group_with_loop(py, event_loop, task_group, fut)
+}
+
+/// Expose a Rust resource as a Python async context manager
+///
+/// See [`generic::into_async_context_manager`] for details.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `manager` - The Rust resource
+#[track_caller]
+pub fn into_async_context_manager<M>(py: Python, manager: M) -> PyResult<PyObject>
+where
+    M: generic::AsyncContextManager,
+{
+    generic::into_async_context_manager::<TokioRuntime, _>(py, manager)
+}
+
+/// Expose a Rust `Stream` as a Python async iterator
+///
+/// See [`generic::PyAsyncIter`] for details.
+///
+/// # Arguments
+/// * `stream` - The Rust stream to be iterated from Python
+#[track_caller]
+pub fn into_async_iter<S>(stream: S) -> generic::PyAsyncIter
+where
+    S: futures::Stream<Item = PyResult<PyObject>> + Send + 'static,
+{
+    generic::PyAsyncIter::new::<TokioRuntime, _>(stream)
+}
+
+/// Expose a Rust `Stream` as a Python async iterator, buffering items ahead of the consumer
+///
+/// See [`generic::PyAsyncIter::with_config`] for details.
+///
+/// # Arguments
+/// * `stream` - The Rust stream to be iterated from Python
+/// * `config` - The buffering between the stream and the iterator
+#[track_caller]
+pub fn into_async_iter_with_config<S>(stream: S, config: BridgeConfig) -> generic::PyAsyncIter
+where
+    S: futures::Stream<Item = PyResult<PyObject>> + Send + 'static,
+{
+    generic::PyAsyncIter::with_config::<TokioRuntime, _>(stream, config)
+}
+
+/// Convert a Python async iterator into a Rust `Stream`
+///
+/// See [`generic::into_stream`] for details.
+///
+/// # Arguments
+/// * `aiter` - The Python async iterator (or async iterable)
+/// * `config` - The buffering between the iterator and the stream
+pub fn into_stream(
+    aiter: &PyAny,
+    config: BridgeConfig,
+) -> PyResult<impl futures::Stream<Item = PyResult<PyObject>> + Send + 'static> {
+    generic::into_stream::<TokioRuntime>(aiter, config)
+}
+
+/// Expose a Rust `Sink` and `Stream` pair as a Python async generator
+///
+/// See [`generic::PyAsyncGen`] for details.
+///
+/// # Arguments
+/// * `sink` - Receives the values sent and the exceptions thrown in from Python
+/// * `stream` - Produces the values yielded to Python
+#[track_caller]
+pub fn into_async_gen<Si, St>(sink: Si, stream: St) -> generic::PyAsyncGen
+where
+    Si: futures::Sink<PyResult<PyObject>, Error = PyErr> + Send + 'static,
+    St: futures::Stream<Item = PyResult<PyObject>> + Send + 'static,
+{
+    generic::PyAsyncGen::new::<TokioRuntime, _, _>(sink, stream)
 }
@@ -0,0 +1,83 @@
+//! When the `tracing` feature is enabled, every conversion from a Rust future into a Python
+//! coroutine captures the current `tracing` span and re-enters it whenever the Rust future is
+//! polled. This keeps the Rust side of a request in the same trace as the code that created the
+//! coroutine, even though the future is polled on a different thread by the Rust runtime.
+//!
+//! Python code usually doesn't run inside a Rust span though, so the span that's current when
+//! Python calls into Rust is rarely the one you want.
+//! [`set_span_hook`](crate::tracing::set_span_hook) lets you build the span for each conversion
+//! instead, typically from a correlation ID stored in a Python `contextvars` variable by your web
+//! framework.
+
+use std::future::Future;
+
+use ::tracing::{instrument::Instrumented, Instrument, Span};
+use once_cell::sync::OnceCell;
+use pyo3::prelude::*;
+
+/// A function that creates the span for a conversion while the GIL is held
+pub type SpanHook = dyn Fn(Python) -> Option<Span> + Send + Sync;
+
+static SPAN_HOOK: OnceCell<Box<SpanHook>> = OnceCell::new();
+
+/// Set the hook used to create the span for each conversion
+///
+/// The hook is called with the GIL held in the context of the Python code that requested the
+/// conversion, so it can read `contextvars` to attach Python-side correlation IDs to the span, or
+/// [`crate::current_task_name`] to record the Python task. If the hook returns `None`, the current
+/// span is used.
+///
+/// # Panics
+/// This function will panic if called a second time.
+///
+/// # Examples
+///
+/// ```
+/// use pyo3::prelude::*;
+///
+/// pyo3_asyncio::tracing::set_span_hook(|py| {
+///     let request_id = py
+///         .import("my_framework")
+///         .and_then(|framework| framework.getattr("request_id"))
+///         .and_then(|var| pyo3_asyncio::tracing::get_context_var(py, var))
+///         .ok()
+///         .flatten()?;
+///
+///     Some(tracing::info_span!("python_request", request_id = %request_id))
+/// });
+/// ```
+pub fn set_span_hook<F>(hook: F)
+where
+    F: Fn(Python) -> Option<Span> + Send + Sync + 'static,
+{
+    if SPAN_HOOK.set(Box::new(hook)).is_err() {
+        panic!("PyO3 Asyncio span hook has already been set");
+    }
+}
+
+/// Get the value of a Python `contextvars.ContextVar` in the current context
+///
+/// Returns `None` if the variable has no value in the current context.
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `var` - The `contextvars.ContextVar` to read
+pub fn get_context_var(py: Python, var: &PyAny) -> PyResult<Option<PyObject>> {
+    let value = var.call_method1("get", (py.None(),))?;
+
+    if value.is_none() {
+        Ok(None)
+    } else {
+        Ok(Some(value.into()))
+    }
+}
+
+/// Instrument a Rust future with the span for a new conversion
+pub(crate) fn instrument<F: Future>(py: Python, fut: F) -> Instrumented<F> {
+    let span = SPAN_HOOK
+        .get()
+        .and_then(|hook| hook(py))
+        .unwrap_or_else(Span::current);
+
+    fut.instrument(span)
+}
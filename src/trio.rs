@@ -0,0 +1,256 @@
+//! Trio doesn't have futures or an event loop object that can be scheduled on from other threads.
+//! Instead, a running Trio program hands out a `TrioToken`, and `TrioToken.run_sync_soon` is the
+//! one thread-safe way in. These conversions are built on top of it:
+//!
+//! - Rust futures are still spawned onto a Rust runtime (tokio, async-std, ...), and their result
+//! is delivered back to the awaiting Trio task with `run_sync_soon`.
+//! - Trio awaitables are run as Trio system tasks, started with `run_sync_soon`.
+//!
+//! None of this requires [`crate::try_init`] to be called, since no asyncio event loop is involved.
+
+use std::future::Future;
+
+use futures::channel::oneshot;
+use pyo3::{exceptions::PyRuntimeError, prelude::*, types::PyDict, PyNativeType};
+
+use crate::{
+    generic::{self, Completer, Runtime},
+    stats, ConversionKind,
+};
+
+const TRIO_HELPERS_MODULE: &str = "pyo3_asyncio_trio_helpers";
+
+const TRIO_HELPERS_CODE: &str = r#"
+import trio
+
+async def await_rust(start):
+    done = trio.Event()
+    outcome = []
+
+    def complete(is_ok, value):
+        outcome.append((is_ok, value))
+        done.set()
+
+    start(trio.lowlevel.current_trio_token().run_sync_soon, complete)
+    await done.wait()
+
+    is_ok, value = outcome[0]
+    if is_ok:
+        return value
+    raise value
+
+async def _run_system_task(awaitable, complete):
+    try:
+        result = await awaitable
+    except trio.Cancelled as e:
+        # the Trio run is shutting down, system tasks must let this propagate
+        complete(False, e)
+        raise
+    except BaseException as e:
+        complete(False, e)
+    else:
+        complete(True, result)
+
+def spawn(awaitable, complete):
+    trio.lowlevel.spawn_system_task(_run_system_task, awaitable, complete)
+"#;
+
+/// Python functions that the Trio conversions are built on
+///
+/// The module is registered in `sys.modules`, so it's only compiled once.
+fn trio_helpers(py: Python<'_>) -> PyResult<&PyAny> {
+    let modules: &PyDict = py.import("sys")?.getattr("modules")?.downcast()?;
+
+    if let Some(helpers) = modules.get_item(TRIO_HELPERS_MODULE) {
+        return Ok(helpers);
+    }
+
+    let helpers = PyModule::from_code(
+        py,
+        TRIO_HELPERS_CODE,
+        "pyo3_asyncio/trio_helpers.py",
+        TRIO_HELPERS_MODULE,
+    )?;
+    modules.set_item(TRIO_HELPERS_MODULE, helpers)?;
+
+    Ok(helpers)
+}
+
+/// Hands the result of a Rust future to the `complete` callback of an `await_rust` helper
+#[derive(Clone)]
+struct TrioCompleter {
+    run_sync_soon: PyObject,
+    complete: PyObject,
+}
+
+impl TrioCompleter {
+    fn args(py: Python, result: PyResult<PyObject>) -> (bool, PyObject) {
+        match result {
+            Ok(val) => (true, val),
+            Err(err) => (false, err.into_py(py)),
+        }
+    }
+}
+
+impl Completer for TrioCompleter {
+    fn complete(&self, py: Python, result: PyResult<PyObject>) -> PyResult<()> {
+        let (is_ok, value) = Self::args(py, result);
+        self.run_sync_soon
+            .call1(py, (self.complete.clone_ref(py), is_ok, value))?;
+
+        Ok(())
+    }
+}
+
+type StartTrio = Box<dyn FnOnce(Python, TrioCompleter) -> PyResult<()> + Send>;
+
+#[pyclass]
+struct PyStartTrio {
+    start: Option<StartTrio>,
+}
+
+#[pymethods]
+impl PyStartTrio {
+    #[call]
+    pub fn __call__(
+        &mut self,
+        py: Python,
+        run_sync_soon: PyObject,
+        complete: PyObject,
+    ) -> PyResult<()> {
+        match self.start.take() {
+            Some(start) => start(
+                py,
+                TrioCompleter {
+                    run_sync_soon,
+                    complete,
+                },
+            ),
+            None => Err(PyRuntimeError::new_err(
+                "coroutine converted from a Rust future was awaited twice",
+            )),
+        }
+    }
+}
+
+/// Convert a Rust Future into a coroutine that can be awaited from Trio
+///
+/// The Rust future is spawned onto `R` when the coroutine is first awaited, and its result is
+/// delivered to the awaiting Trio task with `TrioToken.run_sync_soon`. Like
+/// [`generic::into_coroutine`], cancelling the awaiting task does not cancel the Rust future.
+///
+/// The runtime modules provide this as `into_trio_coroutine` (i.e.
+/// [`crate::tokio::into_trio_coroutine`]).
+///
+/// # Arguments
+/// * `py` - The current PyO3 GIL guard
+/// * `fut` - The Rust future to be converted
+pub fn into_coroutine<R, F>(py: Python, fut: F) -> PyResult<PyObject>
+where
+    R: Runtime,
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    let start = PyStartTrio {
+        start: Some(Box::new(move |py, completer| {
+            generic::spawn_with_completer::<R, _, _, _>(
+                py,
+                (),
+                completer,
+                generic::Start::Spawn,
+                fut,
+                generic::convert_join_failure,
+            )
+        })),
+    };
+
+    Ok(trio_helpers(py)?
+        .call_method1("await_rust", (start,))?
+        .into())
+}
+
+#[pyclass]
+struct PyTrioCompleter {
+    tx: Option<oneshot::Sender<PyResult<PyObject>>>,
+}
+
+#[pymethods]
+impl PyTrioCompleter {
+    #[call]
+    pub fn __call__(&mut self, is_ok: bool, value: &PyAny) -> PyResult<()> {
+        let result = if is_ok {
+            Ok(value.into())
+        } else {
+            Err(PyErr::from_instance(value))
+        };
+
+        if let Some(tx) = self.tx.take() {
+            if tx.send(result).is_err() {
+                // cancellation is not an error
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Convert a Trio `awaitable` into a Rust Future
+///
+/// This must be called from inside a Trio run (i.e. from a Rust function that was called by a Trio
+/// task), since it uses the `TrioToken` of the current run. Use [`into_future_with_token`] to
+/// convert awaitables from other threads.
+///
+/// # Arguments
+/// * `awaitable` - The Trio `awaitable` to be converted
+pub fn into_future(awaitable: &PyAny) -> PyResult<impl Future<Output = PyResult<PyObject>> + Send> {
+    let token = awaitable
+        .py()
+        .import("trio")?
+        .getattr("lowlevel")?
+        .call_method0("current_trio_token")?;
+
+    into_future_with_token(token, awaitable)
+}
+
+/// Convert a Trio `awaitable` into a Rust Future that runs in the Trio run of `token`
+///
+/// The awaitable runs as a Trio system task. If it is still running when the Trio run shuts down,
+/// the Rust future resolves with `trio.Cancelled`.
+///
+/// # Arguments
+/// * `token` - The `trio.lowlevel.TrioToken` of the Trio run that the awaitable should run in
+/// * `awaitable` - The Trio `awaitable` to be converted
+pub fn into_future_with_token(
+    token: &PyAny,
+    awaitable: &PyAny,
+) -> PyResult<impl Future<Output = PyResult<PyObject>> + Send> {
+    let py = awaitable.py();
+    crate::ensure_awaitable(awaitable)?;
+
+    let (tx, rx) = oneshot::channel();
+    let conversion = stats::Conversion::start(ConversionKind::IntoFuture);
+
+    token.call_method1(
+        "run_sync_soon",
+        (
+            trio_helpers(py)?.getattr("spawn")?,
+            awaitable,
+            PyTrioCompleter { tx: Some(tx) },
+        ),
+    )?;
+
+    Ok(async move {
+        let result = match rx.await {
+            Ok(item) => item,
+            Err(_) => {
+                // the Trio run finished before the system task could be started
+                stats::record_unresolved_py_future();
+                Err(PyRuntimeError::new_err(
+                    "the Trio run finished before the awaitable completed",
+                ))
+            }
+        };
+
+        conversion.finish(&result);
+        result
+    })
+}